@@ -22,8 +22,18 @@ export default {
 
   prover: {
     url: process.env.PROVER_URL || "http://34.44.244.184:7048",
+    // Общий секрет с Prover (PROVER_API_TOKEN) — нужен для submit
+    apiToken: process.env.PROVER_API_TOKEN || "",
   },
 
   // Сервисный API-ключ для service-to-service вызовов (без SQLite)
   serviceApiKey: process.env.SERVICE_API_KEY || "",
+
+  // Аккаунты, которым разрешён submit: prover отправляет аттестацию ключом
+  // оператора (газ и предоплаченная комиссия — за счёт оператора).
+  // "service" — сервисный ключ. Пусто — submit запрещён всем.
+  submitAccounts: (process.env.SUBMIT_ACCOUNTS || "")
+    .split(",")
+    .map((s) => s.trim())
+    .filter(Boolean),
 };
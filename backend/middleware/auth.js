@@ -7,7 +7,16 @@
 import { validateApiKey, DAILY_LIMIT } from "../services/auth.js";
 import config from "../config.js";
 
-export function requireAuth(req, res, next) {
+export const requireAuth = authenticate({ count: true });
+
+// Без списания дневного лимита — для опроса и отмены /jobs
+export const requireKey = authenticate({ count: false });
+
+function authenticate({ count }) {
+  return (req, res, next) => checkApiKey(req, res, next, count);
+}
+
+function checkApiKey(req, res, next, count) {
   const apiKey = req.headers["x-api-key"];
 
   if (!apiKey) {
@@ -22,7 +31,7 @@ export function requireAuth(req, res, next) {
     return next();
   }
 
-  const keyInfo = validateApiKey(apiKey);
+  const keyInfo = validateApiKey(apiKey, { count });
 
   if (!keyInfo) {
     return res.status(401).json({ error: "Invalid or deactivated API key" });
@@ -1,15 +1,18 @@
 /**
  * REST API маршруты TLS Oracle
  *
- * POST /prove — защищён API-ключом (X-API-Key).
- * Submit удалён — пользователь отправляет транзакции из своего кошелька.
+ * POST /prove, /prove/stream, /jobs — защищены API-ключом (X-API-Key).
+ * /jobs — для клиентов за прокси с коротким таймаутом: задача выполняется
+ * в Prover, клиент опрашивает GET /jobs/:id. Задачи видны только их ключу.
+ * submit (отправка в NEAR ключом оператора) — только аккаунтам из
+ * SUBMIT_ACCOUNTS; остальные отправляют транзакции из своего кошелька.
  */
 
 import { Router } from "express";
 import * as near from "../services/near.js";
 import * as proverClient from "../services/prover-client.js";
 import config from "../config.js";
-import { requireAuth } from "../middleware/auth.js";
+import { requireAuth, requireKey } from "../middleware/auth.js";
 
 const router = Router();
 
@@ -56,45 +59,88 @@ router.get("/templates", (_req, res) => {
   res.json(TEMPLATES);
 });
 
+/**
+ * Проверка тела /prove и /prove/stream
+ * @param {object} auth - req.auth (accountId для разрешения submit)
+ * @returns {{ error: string, status?: number } | { params: object }}
+ */
+function parseProveRequest(reqBody, auth) {
+  const { url, method, headers, body, contentType, secrets, disclose, submit } = reqBody;
+  if (!url) return { error: "URL обязателен" };
+
+  // submit тратит газ и предоплату оператора — только разрешённым аккаунтам
+  if (submit !== undefined && !config.submitAccounts.includes(auth.accountId)) {
+    return {
+      error: "submit не разрешён для этого API-ключа — отправьте аттестацию из своего кошелька",
+      status: 403,
+    };
+  }
+
+  // Валидация URL
+  let parsed;
+  try {
+    parsed = new URL(url);
+  } catch {
+    return { error: "Неверный формат URL" };
+  }
+
+  if (parsed.protocol !== "https:") {
+    return { error: "Разрешён только HTTPS" };
+  }
+
+  if (url.length > 2048) {
+    return { error: "URL слишком длинный (макс 2048)" };
+  }
+
+  const m = (method || "GET").toUpperCase();
+  if (!["GET", "POST", "PUT"].includes(m)) {
+    return { error: "Метод должен быть GET, POST или PUT" };
+  }
+
+  return {
+    params: { url, method: m, headers, body, contentType, secrets, disclose, submit },
+  };
+}
+
 // Запросить аттестацию (защищённый — требует API-ключ)
 router.post("/prove", requireAuth, async (req, res) => {
   try {
-    const { url, method, headers } = req.body;
-    if (!url) return res.status(400).json({ error: "URL обязателен" });
-
-    // Валидация URL
-    let parsed;
-    try {
-      parsed = new URL(url);
-    } catch {
-      return res.status(400).json({ error: "Неверный формат URL" });
-    }
+    const { error, status, params } = parseProveRequest(req.body, req.auth);
+    if (error) return res.status(status ?? 400).json({ error });
 
-    if (parsed.protocol !== "https:") {
-      return res.status(400).json({ error: "Разрешён только HTTPS" });
-    }
+    const attestation = await proverClient.requestProof(params);
+    res.json(attestation);
+  } catch (err) {
+    console.error("[api] /prove ошибка:", err.message);
+    res.status(502).json({ error: err.message });
+  }
+});
 
-    if (url.length > 2048) {
-      return res
-        .status(400)
-        .json({ error: "URL слишком длинный (макс 2048)" });
-    }
+// То же с прогрессом: Server-Sent Events от Prover передаются как есть
+// (event: progress ..., последним — result или error)
+router.post("/prove/stream", requireAuth, async (req, res) => {
+  const { error, status, params } = parseProveRequest(req.body, req.auth);
+  if (error) return res.status(status ?? 400).json({ error });
 
-    const m = (method || "GET").toUpperCase();
-    if (!["GET", "POST"].includes(m)) {
-      return res
-        .status(400)
-        .json({ error: "Метод должен быть GET или POST" });
-    }
+  // Клиент закрыл соединение — Prover прерывает сессию
+  const controller = new AbortController();
+  res.on("close", () => controller.abort());
 
-    const attestation = await proverClient.requestProof({
-      url,
-      method: m,
-      headers,
+  try {
+    const stream = await proverClient.openProofStream(params, controller.signal);
+    res.writeHead(200, {
+      "Content-Type": "text/event-stream",
+      "Cache-Control": "no-cache",
+      "X-Accel-Buffering": "no",
     });
-    res.json(attestation);
+    for await (const chunk of stream) {
+      res.write(chunk);
+    }
+    res.end();
   } catch (err) {
-    console.error("[api] /prove ошибка:", err.message);
+    if (controller.signal.aborted) return;
+    console.error("[api] /prove/stream ошибка:", err.message);
+    if (res.headersSent) return res.end();
     res.status(502).json({ error: err.message });
   }
 });
@@ -121,6 +167,70 @@ router.post("/prove-espn", requireAuth, async (req, res) => {
   }
 });
 
+// ── Асинхронные задачи ───────────────────────────────────────
+
+/**
+ * Проверка тела POST /jobs
+ * @returns {{ error: string, status?: number } | { params: object }}
+ */
+function parseJobRequest(reqBody, auth) {
+  const { type = "prove", callbackUrl, callbackSecret } = reqBody;
+  if (type === "espn") {
+    const { espnEventId, sport, league } = reqBody;
+    if (!espnEventId || !sport || !league) {
+      return { error: "espnEventId, sport и league обязательны" };
+    }
+    return { params: { type, espnEventId, sport, league } };
+  }
+  if (type !== "prove") return { error: "type: prove или espn" };
+
+  const { error, status, params } = parseProveRequest(reqBody, auth);
+  if (error) return { error, status };
+  return { params: { type, ...params, callbackUrl, callbackSecret } };
+}
+
+// Ошибки Prover: 4xx (валидация, лимит задач) — как есть, остальное — 502
+function sendProverError(res, route, err) {
+  const status = err.status >= 400 && err.status < 500 ? err.status : 502;
+  if (status === 502) console.error(`[api] ${route} ошибка:`, err.message);
+  res.status(status).json({ error: err.message });
+}
+
+// Запустить аттестацию в фоне → 202 { id, phase: "queued", ... }
+router.post("/jobs", requireAuth, async (req, res) => {
+  const { error, status, params } = parseJobRequest(req.body, req.auth);
+  if (error) return res.status(status ?? 400).json({ error });
+
+  try {
+    const job = await proverClient.createJob(params, req.auth.accountId);
+    res.status(202).json(job);
+  } catch (err) {
+    sendProverError(res, "/jobs", err);
+  }
+});
+
+// Статус задачи (опрос не расходует дневной лимит)
+router.get("/jobs/:id", requireKey, async (req, res) => {
+  try {
+    const job = await proverClient.getJob(req.params.id, req.auth.accountId);
+    if (!job) return res.status(404).json({ error: "Задача не найдена" });
+    res.json(job);
+  } catch (err) {
+    sendProverError(res, "/jobs/:id", err);
+  }
+});
+
+// Отменить задачу или удалить результат
+router.delete("/jobs/:id", requireKey, async (req, res) => {
+  try {
+    const found = await proverClient.cancelJob(req.params.id, req.auth.accountId);
+    if (!found) return res.status(404).json({ error: "Задача не найдена" });
+    res.status(204).end();
+  } catch (err) {
+    sendProverError(res, "/jobs/:id", err);
+  }
+});
+
 // Список аттестаций (публичный)
 router.get("/attestations", async (req, res) => {
   const fromIndex = req.query.from ? parseInt(req.query.from) : undefined;
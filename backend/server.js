@@ -21,7 +21,7 @@ const app = express();
 app.use(
   cors({
     origin: config.frontendUrl || false,
-    methods: ["GET", "POST"],
+    methods: ["GET", "POST", "DELETE"],
     allowedHeaders: ["Content-Type", "X-API-Key"],
   }),
 );
@@ -69,8 +69,10 @@ export async function verifyAndIssueKey(accountId, nonce, publicKey) {
 
 /**
  * Проверить API-ключ (для middleware)
+ *
+ * count: false — без списания дневного лимита (опрос статуса задачи)
  */
-export function validateApiKey(apiKey) {
+export function validateApiKey(apiKey, { count = true } = {}) {
   if (!apiKey || !apiKey.startsWith("tlso_")) return null;
 
   const db = getDb();
@@ -79,6 +81,7 @@ export function validateApiKey(apiKey) {
     .get(apiKey);
 
   if (!row) return null;
+  if (!count) return { ...row, rateLimited: false };
 
   // Сброс дневного счётчика
   const today = new Date().toISOString().slice(0, 10);
@@ -4,15 +4,23 @@
 
 import config from "../config.js";
 
+// X-Prover-Token: Prover выполняет submit и доверяет X-Caller-Id только
+// запросам с общим секретом PROVER_API_TOKEN
+function proverHeaders(headers = {}) {
+  return config.prover.apiToken
+    ? { ...headers, "X-Prover-Token": config.prover.apiToken }
+    : headers;
+}
+
 /**
  * Запросить MPC-TLS аттестацию + ZK proof через Prover
- * @param {object} params - { url, method?, headers? }
+ * @param {object} params - { url, method?, headers?, body?, contentType?, secrets?, disclose? }
  * @returns {object} - { sourceUrl, serverName, timestamp, responseData, proofA, proofB, proofC, publicSignals }
  */
 export async function requestProof(params) {
   const resp = await fetch(`${config.prover.url}/prove`, {
     method: "POST",
-    headers: { "Content-Type": "application/json" },
+    headers: proverHeaders({ "Content-Type": "application/json" }),
     body: JSON.stringify(params),
   });
 
@@ -24,6 +32,81 @@ export async function requestProof(params) {
   return resp.json();
 }
 
+/**
+ * Открыть поток прогресса аттестации (Server-Sent Events)
+ * @param {object} params - как у requestProof
+ * @param {AbortSignal} signal - отмена при отключении клиента
+ * @returns {ReadableStream<Uint8Array>} - тело text/event-stream
+ */
+export async function openProofStream(params, signal) {
+  const resp = await fetch(`${config.prover.url}/prove/stream`, {
+    method: "POST",
+    headers: proverHeaders({ "Content-Type": "application/json" }),
+    body: JSON.stringify(params),
+    signal,
+  });
+
+  if (!resp.ok) {
+    const text = await resp.text();
+    throw new Error(`Prover ошибка (${resp.status}): ${text}`);
+  }
+
+  return resp.body;
+}
+
+/**
+ * Ошибка Prover с HTTP статусом (4xx передаются клиенту как есть)
+ */
+async function proverError(resp) {
+  const text = await resp.text();
+  const err = new Error(`Prover ошибка (${resp.status}): ${text}`);
+  err.status = resp.status;
+  return err;
+}
+
+/**
+ * Создать фоновую задачу аттестации
+ * @param {object} params - { type: "prove", ...поля requestProof, callbackUrl?, callbackSecret? } или { type: "espn", espnEventId, sport, league }
+ * @param {string} callerId - владелец задачи (аккаунт API-ключа); лимит задач — на него
+ * @returns {object} - { id, phase: "queued", createdAt, timings }
+ */
+export async function createJob(params, callerId) {
+  const resp = await fetch(`${config.prover.url}/jobs`, {
+    method: "POST",
+    headers: proverHeaders({ "Content-Type": "application/json", "X-Caller-Id": callerId }),
+    body: JSON.stringify(params),
+  });
+  if (!resp.ok) throw await proverError(resp);
+  return resp.json();
+}
+
+/**
+ * Статус задачи: phase, timings, result или error
+ * @returns {object | null} - null, если задачи нет (или она чужая)
+ */
+export async function getJob(id, callerId) {
+  const resp = await fetch(`${config.prover.url}/jobs/${encodeURIComponent(id)}`, {
+    headers: proverHeaders({ "X-Caller-Id": callerId }),
+  });
+  if (resp.status === 404) return null;
+  if (!resp.ok) throw await proverError(resp);
+  return resp.json();
+}
+
+/**
+ * Отменить задачу или удалить её результат
+ * @returns {boolean} - false, если задачи нет (или она чужая)
+ */
+export async function cancelJob(id, callerId) {
+  const resp = await fetch(`${config.prover.url}/jobs/${encodeURIComponent(id)}`, {
+    method: "DELETE",
+    headers: proverHeaders({ "X-Caller-Id": callerId }),
+  });
+  if (resp.status === 404) return false;
+  if (!resp.ok) throw await proverError(resp);
+  return true;
+}
+
 /**
  * Запросить ESPN аттестацию — MPC-TLS + ZK proof с извлечением scores
  * @param {object} params - { espnEventId, sport, league }
@@ -32,7 +115,7 @@ export async function requestProof(params) {
 export async function requestEspnProof(params) {
   const resp = await fetch(`${config.prover.url}/prove-espn`, {
     method: "POST",
-    headers: { "Content-Type": "application/json" },
+    headers: proverHeaders({ "Content-Type": "application/json" }),
     body: JSON.stringify(params),
   });
 
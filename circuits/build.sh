@@ -5,6 +5,7 @@ set -e
 #
 # Требования: circom, snarkjs, node
 # Выход: build/verification_key.json, build/att_final.zkey, build/attestation_js/
+#        ../verifier/tests/fixtures/vk_data_groth16_* — proof для тестов контракта
 
 SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
 cd "$SCRIPT_DIR"
@@ -38,6 +39,10 @@ echo "=== Генерация Rust VK ==="
 node vk_to_rust.js build/verification_key.json > ../contract/src/vk_data.rs
 echo "Rust VK записан в ../contract/src/vk_data.rs"
 
+echo "=== Fixture для тестов контракта ==="
+# Новый zkey меняет vk_data.rs — прежний proof fixture к нему не подходит
+bash groth16_fixture.sh
+
 echo ""
 echo "=== Готово ==="
 echo "Proving key:      build/att_final.zkey"
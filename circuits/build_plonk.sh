@@ -0,0 +1,70 @@
+#!/bin/bash
+set -e
+
+# ── Компиляция и PLONK setup для AttestationVerifier circuit ──
+#
+# PLONK использует universal setup: достаточно готового Powers of Tau
+# (например, публичного Hermez ptau), per-circuit церемония не нужна.
+#
+# Требования: circom, snarkjs, node
+# Выход: build/plonk_verification_key.json, build/att_plonk.zkey, build/attestation_js/
+#        ../verifier/tests/fixtures/snarkjs_plonk_* — proof для тестов контракта
+#
+# PTAU=<path> — использовать готовый ptau (по умолчанию локальный 2^15)
+
+SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
+cd "$SCRIPT_DIR"
+
+echo "=== Установка зависимостей ==="
+npm install
+
+echo "=== Компиляция circom → R1CS + WASM ==="
+mkdir -p build
+circom attestation.circom --r1cs --wasm --sym -o build/
+
+echo "=== Circuit info ==="
+snarkjs r1cs info build/attestation.r1cs
+
+if [ -z "$PTAU" ]; then
+  echo "=== Powers of Tau (BN128, 2^15) ==="
+  # PLONK считает и линейные ограничения — нужен домен больше, чем для Groth16
+  PTAU=build/pot15_final.ptau
+  if [ ! -f "$PTAU" ]; then
+    snarkjs powersoftau new bn128 15 build/pot15_0.ptau -v
+    snarkjs powersoftau contribute build/pot15_0.ptau build/pot15_1.ptau \
+      --name="TLS Oracle Phase 1" -v -e="random-entropy-$(date +%s)"
+    snarkjs powersoftau prepare phase2 build/pot15_1.ptau "$PTAU" -v
+  fi
+fi
+
+echo "=== PLONK Setup ==="
+snarkjs plonk setup build/attestation.r1cs "$PTAU" build/att_plonk.zkey
+
+echo "=== Экспорт Verification Key ==="
+snarkjs zkey export verificationkey build/att_plonk.zkey build/plonk_verification_key.json
+
+echo "=== Fixture для тестов контракта ==="
+# fixture_attestation.json — те же данные, что в contract/src/tests.rs
+# (RESPONSE, SERVER_NAME, NOW, pubkey notary_key())
+FIXTURES=../verifier/tests/fixtures
+node input_generator.js fixture_attestation.json build/fixture_input.json
+snarkjs plonk fullprove build/fixture_input.json build/attestation_js/attestation.wasm \
+  build/att_plonk.zkey "$FIXTURES/snarkjs_plonk_proof.json" "$FIXTURES/snarkjs_plonk_public.json"
+cp build/plonk_verification_key.json "$FIXTURES/snarkjs_plonk_verification_key.json"
+snarkjs plonk verify "$FIXTURES/snarkjs_plonk_verification_key.json" \
+  "$FIXTURES/snarkjs_plonk_public.json" "$FIXTURES/snarkjs_plonk_proof.json"
+
+echo ""
+echo "=== Готово ==="
+echo "Proving key:      build/att_plonk.zkey"
+echo "Verification key: build/plonk_verification_key.json"
+echo "Fixture:          $FIXTURES/snarkjs_plonk_*.json"
+echo "  cd ../contract && cargo test snarkjs_plonk -- --ignored"
+echo ""
+echo "Регистрация в контракте:"
+echo "  near call <oracle> set_plonk_vk \"{\\\"vk\\\": \$(cat build/plonk_verification_key.json)}\" --accountId <owner>"
+echo ""
+echo "Для тестирования:"
+echo "  node input_generator.js --test"
+echo "  snarkjs plonk fullprove input.json build/attestation_js/attestation.wasm build/att_plonk.zkey proof.json public.json"
+echo "  snarkjs plonk verify build/plonk_verification_key.json public.json proof.json"
@@ -0,0 +1,36 @@
+#!/bin/bash
+set -e
+
+# ── Groth16 fixture для встроенного ключа контракта (vk_data.rs) ──
+#
+# Proof создаётся текущим build/att_final.zkey на данных
+# fixture_attestation.json (те же RESPONSE, SERVER_NAME, NOW и pubkey
+# notary_key(), что в contract/src/tests.rs). Перед этим проверяется, что
+# contract/src/vk_data.rs получен из этого же zkey — иначе proof бесполезен.
+#
+# Требования: snarkjs, node, результат build.sh
+# Выход: ../verifier/tests/fixtures/vk_data_groth16_{proof,public}.json
+
+SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
+cd "$SCRIPT_DIR"
+
+FIXTURES=../verifier/tests/fixtures
+
+echo "=== Проверка vk_data.rs против build/att_final.zkey ==="
+snarkjs zkey export verificationkey build/att_final.zkey build/fixture_vk.json
+node vk_to_rust.js build/fixture_vk.json > build/fixture_vk_data.rs
+if ! cmp -s build/fixture_vk_data.rs ../contract/src/vk_data.rs; then
+  echo "vk_data.rs не соответствует build/att_final.zkey — пересоберите build.sh" >&2
+  exit 1
+fi
+
+echo "=== Groth16 fullprove ==="
+node input_generator.js fixture_attestation.json build/fixture_input.json
+snarkjs groth16 fullprove build/fixture_input.json build/attestation_js/attestation.wasm \
+  build/att_final.zkey "$FIXTURES/vk_data_groth16_proof.json" "$FIXTURES/vk_data_groth16_public.json"
+snarkjs groth16 verify build/fixture_vk.json \
+  "$FIXTURES/vk_data_groth16_public.json" "$FIXTURES/vk_data_groth16_proof.json"
+
+echo ""
+echo "Fixture: $FIXTURES/vk_data_groth16_*.json"
+echo "  cd ../contract && cargo test snarkjs_groth16 -- --ignored"
@@ -0,0 +1,219 @@
+//! tls-oracle-consumer — чтение данных tls-oracle из других контрактов
+//!
+//! - `ext_tls_oracle` — cross-contract bindings (#[ext_contract]) публичных
+//!   методов oracle
+//! - `types` — типы API oracle из общего с контрактом crate `tls-oracle-types`
+//! - `policy` — проверка свежести, нотариуса и финальности в callback
+//! - `mock` (feature "mock") — ответы oracle для testing_env тестов
+//!
+//! ```ignore
+//! ext_tls_oracle::ext(oracle)
+//!     .get_latest(url, Some(300))
+//!     .then(Self::ext(env::current_account_id()).on_price(url));
+//!
+//! #[private]
+//! pub fn on_price(
+//!     &mut self,
+//!     url: String,
+//!     #[callback_result] result: Result<Option<AttestationView>, PromiseError>,
+//! ) {
+//!     let attestation = self.policy.require(result);
+//!     ...
+//! }
+//! ```
+//!
+//! Bindings покрывают все публичные методы oracle, кроме init / миграций
+//! состояния, private callbacks, `ft_on_transfer` (его вызывает NEP-141
+//! токен) и `stage_upgrade` — он принимает wasm сырым input, а не JSON.
+//! Owner-методы под governance исполняются только предложением
+//! `ProposalKind::FunctionCall`, поэтому из контракта совета их вызывают как
+//! `propose`, а не напрямую.
+
+use groth16_verifier::snarkjs::{SnarkjsPlonkProof, SnarkjsPlonkVerificationKey};
+use near_contract_standards::non_fungible_token::metadata::NFTContractMetadata;
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+use near_sdk::json_types::{Base58CryptoHash, U128};
+use near_sdk::{ext_contract, AccountId};
+
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod policy;
+pub use tls_oracle_types as types;
+
+pub use policy::{AttestationPolicy, PolicyError};
+pub use types::*;
+
+#[ext_contract(ext_tls_oracle)]
+#[allow(dead_code)]
+pub trait TlsOracle {
+    // ── Аттестации ───────────────────────────────────────────
+
+    fn get_attestation(&self, id: u64) -> Option<AttestationView>;
+    fn get_attestations(&self, from_index: Option<u64>, limit: Option<u64>)
+        -> Vec<AttestationView>;
+    fn get_attestations_by_source(
+        &self,
+        server_name: String,
+        limit: Option<u64>,
+    ) -> Vec<AttestationView>;
+    /// Самая свежая действительная аттестация URL не старше max_age_secs
+    fn get_latest(&self, source_url: String, max_age_secs: Option<u64>) -> Option<AttestationView>;
+    fn get_range(
+        &self,
+        source_url: String,
+        from_ts: u64,
+        to_ts: u64,
+        limit: Option<u64>,
+    ) -> Vec<AttestationView>;
+    fn is_attestation_finalized(&self, attestation_id: u64) -> bool;
+    fn get_proof_systems(&self) -> Vec<ProofSystem>;
+    fn get_stats(&self) -> serde_json::Value;
+    fn get_owner(&self) -> AccountId;
+    fn get_circuit_metadata(&self) -> CircuitMetadata;
+    fn get_verifier(&self) -> Option<VerifierConfig>;
+
+    /// Submit аттестации (payable: комиссия домена, см. get_fee)
+    #[allow(clippy::too_many_arguments)]
+    fn submit_attestation(
+        &mut self,
+        source_url: String,
+        server_name: String,
+        timestamp: u64,
+        response_data: String,
+        proof_system: Option<ProofSystem>,
+        proof_a: Option<[String; 2]>,
+        proof_b: Option<[[String; 2]; 2]>,
+        proof_c: Option<[String; 2]>,
+        plonk_proof: Option<SnarkjsPlonkProof>,
+        public_signals: [String; 4],
+        notary_signature: String,
+        notary_sig_v: u8,
+        request_hash: Option<String>,
+    ) -> Option<u64>;
+    /// То же с аргументами в Borsh (payable)
+    fn submit_attestation_borsh(
+        &mut self,
+        #[serializer(borsh)] args: SubmitAttestationArgs,
+    ) -> Option<u64>;
+    /// Submit TLSNotary presentation (payable)
+    fn submit_tlsn_attestation(
+        &mut self,
+        source_url: String,
+        server_name: String,
+        response_data: String,
+        presentation: TlsnPresentation,
+    ) -> u64;
+    /// Переписать до `limit` аттестаций V1 в текущую версию, возвращает остаток
+    fn migrate_attestations(&mut self, limit: u32) -> u32;
+
+    // ── Нотариусы ────────────────────────────────────────────
+
+    fn get_notaries(&self) -> Vec<NotaryInfo>;
+    fn get_notaries_with_stats(&self) -> Vec<NotaryWithStats>;
+    fn get_notary_stats(&self, pubkey_hash: String) -> Option<NotaryStats>;
+    fn get_inactive_notaries(&self, blocks: u64) -> Vec<NotaryWithStats>;
+    fn add_notary(
+        &mut self,
+        pubkey_hash: String,
+        name: String,
+        url: String,
+        raw_pubkey: Option<String>,
+    );
+    fn remove_notary(&mut self, pubkey_hash: String);
+
+    // ── Комиссии ─────────────────────────────────────────────
+
+    fn get_fee_config(&self) -> Option<FeeConfig>;
+    fn get_fee(&self, server_name: String) -> U128;
+    fn get_notary_rewards(&self, pubkey_hash: String) -> U128;
+    fn get_notary_reward_account(&self, pubkey_hash: String) -> Option<AccountId>;
+    fn get_treasury_balance(&self) -> U128;
+    fn claim_rewards(&mut self, pubkey_hash: String);
+
+    // ── NEP-141 платежи ──────────────────────────────────────
+
+    fn get_ft_tokens(&self) -> Vec<FtTokenInfo>;
+    fn get_ft_fee(&self, server_name: String, token_id: AccountId) -> Option<U128>;
+    /// Конец подписки аккаунта (UNIX, секунды)
+    fn get_subscription(&self, account_id: AccountId) -> Option<u64>;
+    fn get_ft_balance(&self, holder: FtHolder, token_id: AccountId) -> U128;
+    fn ft_withdraw(&mut self, token_id: AccountId, amount: Option<U128>);
+    fn ft_claim_rewards(&mut self, pubkey_hash: String, token_id: AccountId);
+
+    // ── Disputes ─────────────────────────────────────────────
+
+    fn get_challenge_period(&self, server_name: String) -> Option<u64>;
+    fn get_dispute(&self, id: u64) -> Option<Dispute>;
+    fn get_open_dispute(&self, attestation_id: u64) -> Option<Dispute>;
+    /// payable: attached deposit ≥ bond
+    fn open_dispute(&mut self, attestation_id: u64, reason: String) -> u64;
+    fn resolve_dispute(&mut self, dispute_id: u64, upheld: bool);
+    fn get_dispute_config(&self) -> Option<DisputeConfig>;
+
+    // ── Soulbound NFT (NEP-171, transfer запрещён) ───────────
+
+    fn get_soulbound_mint(&self) -> bool;
+    fn nft_token(&self, token_id: TokenId) -> Option<Token>;
+    fn nft_metadata(&self) -> NFTContractMetadata;
+    fn nft_total_supply(&self) -> U128;
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token>;
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128;
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token>;
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> bool;
+
+    // ── Governance ───────────────────────────────────────────
+
+    fn init_governance(&mut self, council: Vec<CouncilMember>, policy: GovernancePolicy);
+    /// Предложение совета, возвращает id
+    fn propose(&mut self, kind: ProposalKind, description: String) -> u64;
+    fn vote(&mut self, proposal_id: u64, approve: bool) -> ProposalStatus;
+    fn close_proposal(&mut self, proposal_id: u64) -> ProposalStatus;
+    fn get_governance_policy(&self) -> Option<GovernancePolicy>;
+    fn get_council(&self) -> Vec<CouncilMember>;
+    fn get_proposal(&self, id: u64) -> Option<Proposal>;
+    fn get_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Proposal>;
+
+    // ── Owner ────────────────────────────────────────────────
+
+    fn set_owner(&mut self, new_owner: AccountId);
+    fn set_plonk_vk(&mut self, vk: Option<SnarkjsPlonkVerificationKey>);
+    fn set_verifier(&mut self, verifier: Option<VerifierConfig>);
+    fn set_soulbound_mint(&mut self, enabled: bool);
+    fn set_fee_config(&mut self, config: Option<FeeConfig>);
+    fn set_domain_fee(&mut self, server_name: String, fee: Option<U128>);
+    fn set_notary_reward_account(&mut self, pubkey_hash: String, account_id: AccountId);
+    fn withdraw_treasury(&mut self);
+    fn set_ft_token(&mut self, token_id: AccountId, fee: Option<U128>);
+    fn set_ft_domain_fee(&mut self, server_name: String, token_id: AccountId, fee: Option<U128>);
+    fn set_ft_subscription_price(&mut self, token_id: AccountId, price: Option<U128>);
+    fn ft_withdraw_treasury(&mut self, token_id: AccountId);
+    fn set_dispute_config(&mut self, config: Option<DisputeConfig>);
+    fn set_challenge_period(&mut self, server_name: String, period_secs: Option<u64>);
+
+    // ── Обновление контракта ─────────────────────────────────
+
+    fn get_staged_upgrade(&self) -> Option<StagedUpgrade>;
+    fn approve_upgrade(&mut self, code_hash: Base58CryptoHash);
+    fn cancel_upgrade(&mut self);
+    /// Развернуть staged код после timelock (любой аккаунт, ~300 TGas)
+    fn deploy_upgrade(&mut self);
+}
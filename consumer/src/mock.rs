@@ -0,0 +1,70 @@
+//! Mock ответов tls-oracle для testing_env тестов (feature "mock")
+//!
+//! В unit тестах cross-contract вызов не исполняется: callback потребителя
+//! либо вызывается напрямую с `Ok(Some(view))`, либо читает promise result,
+//! подложенный `with_promise_result`.
+
+use near_sdk::{testing_env, AccountId, PromiseResult, RuntimeFeesConfig, VMContext};
+use serde::Serialize;
+
+use crate::types::{Attestation, AttestationView};
+
+/// Poseidon hash нотариуса в mock аттестациях
+pub const NOTARY_HASH: &str = "1";
+
+/// Финальная аттестация URL с timestamp (секунды)
+///
+/// server_name берётся из URL, остальные поля — фиксированные заглушки.
+pub fn attestation(id: u64, source_url: &str, timestamp: u64) -> AttestationView {
+    let host = source_url
+        .split_once("://")
+        .map_or(source_url, |(_, rest)| rest);
+    let server_name = host
+        .split(['/', '?', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    AttestationView {
+        attestation: Attestation {
+            id,
+            source_url: source_url.to_string(),
+            server_name,
+            timestamp,
+            response_data: String::new(),
+            data_commitment: id.to_string(),
+            server_name_hash: "0".to_string(),
+            notary_pubkey_hash: NOTARY_HASH.to_string(),
+            submitter: "submitter.near".parse::<AccountId>().unwrap(),
+            block_height: 0,
+            sig_verified: true,
+            request_hash: None,
+        },
+        finalized: true,
+        challenge_ends_at: None,
+        invalidated: false,
+    }
+}
+
+/// testing_env с успешным результатом вызова oracle (JSON), для #[callback_result]
+pub fn with_promise_result<T: Serialize>(context: VMContext, value: &T) {
+    let bytes = serde_json::to_vec(value).unwrap();
+    testing_env!(
+        context,
+        near_sdk::test_vm_config(),
+        RuntimeFeesConfig::test(),
+        Default::default(),
+        vec![PromiseResult::Successful(bytes)]
+    );
+}
+
+/// testing_env с неудавшимся вызовом oracle
+pub fn with_failed_promise(context: VMContext) {
+    testing_env!(
+        context,
+        near_sdk::test_vm_config(),
+        RuntimeFeesConfig::test(),
+        Default::default(),
+        vec![PromiseResult::Failed]
+    );
+}
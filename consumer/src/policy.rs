@@ -0,0 +1,174 @@
+//! Проверка аттестации в callback контракта-потребителя
+//!
+//! Oracle проверяет proof, подпись и доверенность нотариуса на момент submit.
+//! Потребителю остаётся решить, насколько свежие данные ему нужны, каким
+//! нотариусам он доверяет сам и ждать ли закрытия окна оспаривания.
+
+use std::fmt;
+
+use near_sdk::{env, PromiseError};
+
+use crate::types::{Attestation, AttestationView};
+
+/// Требования потребителя к аттестации
+#[derive(Clone, Debug, Default)]
+pub struct AttestationPolicy {
+    /// Максимальный возраст данных (по timestamp TLS сессии), секунды
+    pub max_age_secs: Option<u64>,
+    /// Poseidon hash pubkey допустимых нотариусов (пусто — любой доверенный oracle)
+    pub trusted_notaries: Vec<String>,
+    /// Только аттестации с закрытым окном оспаривания
+    pub require_finalized: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolicyError {
+    /// Cross-contract вызов oracle не удался
+    CallFailed,
+    NotFound,
+    Invalidated,
+    NotFinalized,
+    Stale {
+        age_secs: u64,
+    },
+    UntrustedNotary(String),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CallFailed => write!(f, "Вызов tls-oracle не удался"),
+            Self::NotFound => write!(f, "Аттестация не найдена"),
+            Self::Invalidated => write!(f, "Аттестация признана недействительной"),
+            Self::NotFinalized => write!(f, "Окно оспаривания аттестации не закрыто"),
+            Self::Stale { age_secs } => write!(f, "Аттестация устарела ({} с)", age_secs),
+            Self::UntrustedNotary(hash) => write!(f, "Нотариус {} не доверенный", hash),
+        }
+    }
+}
+
+impl AttestationPolicy {
+    /// Свежесть — относительно текущего блока
+    pub fn check(&self, view: &AttestationView) -> Result<(), PolicyError> {
+        if view.invalidated {
+            return Err(PolicyError::Invalidated);
+        }
+        if self.require_finalized && !view.finalized {
+            return Err(PolicyError::NotFinalized);
+        }
+        if let Some(max_age) = self.max_age_secs {
+            let now = env::block_timestamp() / 1_000_000_000;
+            // timestamp может быть чуть впереди блока (допуск oracle 60 с)
+            let age_secs = now.saturating_sub(view.attestation.timestamp);
+            if age_secs > max_age {
+                return Err(PolicyError::Stale { age_secs });
+            }
+        }
+        let notary = &view.attestation.notary_pubkey_hash;
+        if !self.trusted_notaries.is_empty() && !self.trusted_notaries.contains(notary) {
+            return Err(PolicyError::UntrustedNotary(notary.clone()));
+        }
+        Ok(())
+    }
+
+    /// Результат get_attestation / get_latest из #[callback_result]
+    pub fn check_result(
+        &self,
+        result: Result<Option<AttestationView>, PromiseError>,
+    ) -> Result<Attestation, PolicyError> {
+        let view = result
+            .map_err(|_| PolicyError::CallFailed)?
+            .ok_or(PolicyError::NotFound)?;
+        self.check(&view)?;
+        Ok(view.attestation)
+    }
+
+    /// То же, что check_result, но паникует с текстом ошибки
+    pub fn require(&self, result: Result<Option<AttestationView>, PromiseError>) -> Attestation {
+        self.check_result(result)
+            .unwrap_or_else(|err| env::panic_str(&err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn set_now() {
+        testing_env!(VMContextBuilder::new()
+            .block_timestamp(NOW * 1_000_000_000)
+            .build());
+    }
+
+    #[test]
+    fn test_fresh_attestation() {
+        set_now();
+        let policy = AttestationPolicy {
+            max_age_secs: Some(300),
+            ..Default::default()
+        };
+        let view = mock::attestation(1, "https://api.example.com/price", NOW - 300);
+        assert_eq!(
+            policy.check_result(Ok(Some(view.clone()))),
+            Ok(view.attestation)
+        );
+    }
+
+    #[test]
+    fn test_stale_attestation() {
+        set_now();
+        let policy = AttestationPolicy {
+            max_age_secs: Some(300),
+            ..Default::default()
+        };
+        let view = mock::attestation(1, "https://api.example.com/price", NOW - 301);
+        assert_eq!(
+            policy.check(&view),
+            Err(PolicyError::Stale { age_secs: 301 })
+        );
+    }
+
+    #[test]
+    fn test_untrusted_notary() {
+        set_now();
+        let policy = AttestationPolicy {
+            trusted_notaries: vec!["42".to_string()],
+            ..Default::default()
+        };
+        let view = mock::attestation(1, "https://api.example.com/price", NOW);
+        assert_eq!(
+            policy.check(&view),
+            Err(PolicyError::UntrustedNotary(mock::NOTARY_HASH.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_not_finalized_and_invalidated() {
+        set_now();
+        let policy = AttestationPolicy {
+            require_finalized: true,
+            ..Default::default()
+        };
+        let mut view = mock::attestation(1, "https://api.example.com/price", NOW);
+        view.finalized = false;
+        assert_eq!(policy.check(&view), Err(PolicyError::NotFinalized));
+        view.invalidated = true;
+        assert_eq!(policy.check(&view), Err(PolicyError::Invalidated));
+    }
+
+    #[test]
+    fn test_failed_call() {
+        set_now();
+        let policy = AttestationPolicy::default();
+        assert_eq!(policy.check_result(Ok(None)), Err(PolicyError::NotFound));
+        assert_eq!(
+            policy.check_result(Err(PromiseError::Failed)),
+            Err(PolicyError::CallFailed)
+        );
+    }
+}
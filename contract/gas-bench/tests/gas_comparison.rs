@@ -0,0 +1,195 @@
+//! Сравнение газа: submit_attestation (JSON, decimal) vs submit_attestation_borsh
+//!
+//! Оба вызова проходят одинаковые проверки (timestamp, нотариус, ecrecover)
+//! и падают на одном шаге — pairing check с заведомо невалидным proof
+//! (генераторы G1/G2). Разница в сожжённом газе — стоимость десериализации
+//! аргументов и парсинга элементов поля.
+//!
+//! Запуск (нужна сеть — near-workspaces скачивает sandbox):
+//! cd contract/gas-bench && cargo test -- --nocapture
+
+use borsh::BorshSerialize;
+use k256::ecdsa::SigningKey;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use near_workspaces::types::{Gas, NearToken};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+// Генератор G1: (1, 2)
+const G1_X: &str = "1";
+const G1_Y: &str = "2";
+
+// Генератор G2 в формате snarkjs: [[x_re, x_im], [y_re, y_im]]
+const G2: [[&str; 2]; 2] = [
+    [
+        "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+        "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+    ],
+    [
+        "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+        "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+    ],
+];
+
+const NOTARY_HASH: &str = "12345";
+
+/// Зеркало `SubmitAttestationArgs` из контракта
+#[derive(BorshSerialize)]
+struct SubmitAttestationArgs {
+    source_url: String,
+    server_name: String,
+    timestamp: u64,
+    response_data: String,
+    proof_a: [[u8; 32]; 2],
+    proof_b: [[[u8; 32]; 2]; 2],
+    proof_c: [[u8; 32]; 2],
+    public_signals: [[u8; 32]; 4],
+    notary_signature: [u8; 64],
+    notary_sig_v: u8,
+    request_hash: Option<[u8; 32]>,
+}
+
+/// Decimal string → 32 байта LE (для небольших чисел и координат < 2^256)
+fn decimal_to_le(s: &str) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for ch in s.bytes() {
+        let mut carry = (ch - b'0') as u16;
+        for byte in out.iter_mut() {
+            let val = (*byte as u16) * 10 + carry;
+            *byte = (val & 0xff) as u8;
+            carry = val >> 8;
+        }
+    }
+    out
+}
+
+/// Подпись в формате контракта v2: SHA-256(tag || len_be4 url || url
+/// || len_be4 server || server || ts_be8 || len_be4 data || data || 0x00)
+fn sign(key: &SigningKey, url: &str, server: &str, ts: u64, data: &str) -> ([u8; 64], u8) {
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"tls-oracle/attestation/v2");
+    for field in [url, server] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(ts.to_be_bytes());
+    hasher.update((data.len() as u32).to_be_bytes());
+    hasher.update(data.as_bytes());
+    hasher.update([0x00]);
+    let hash: [u8; 32] = hasher.finalize().into();
+
+    let (sig, recid): (k256::ecdsa::Signature, k256::ecdsa::RecoveryId) =
+        key.sign_prehash_recoverable(&hash).unwrap();
+    (sig.to_bytes().into(), recid.to_byte())
+}
+
+#[tokio::test]
+async fn borsh_submit_burns_less_gas_than_json() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = near_workspaces::compile_project("../").await?;
+    let contract = worker.dev_deploy(&wasm).await?;
+
+    contract
+        .call("new")
+        .args_json(json!({ "owner": contract.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Нотариус с известным ключом
+    let key = SigningKey::random(&mut rand_core::OsRng);
+    let uncompressed = key.verifying_key().to_encoded_point(false);
+    contract
+        .call("add_notary")
+        .args_json(json!({
+            "pubkey_hash": NOTARY_HASH,
+            "name": "gas-test",
+            "url": "https://example.com",
+            "raw_pubkey": hex::encode(&uncompressed.as_bytes()[1..65]),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let block = worker.view_block().await?;
+    let timestamp = block.timestamp() / 1_000_000_000;
+    let source_url = "https://api.coingecko.com/api/v3/simple/price?ids=near&vs_currencies=usd";
+    let server_name = "api.coingecko.com";
+    let response_data = r#"{"near":{"usd":3.42}}"#;
+    let (signature, sig_v) = sign(&key, source_url, server_name, timestamp, response_data);
+
+    let signals = [
+        "1111111111111111111111111111111".to_string(),
+        "2222222222222222222222222222222".to_string(),
+        timestamp.to_string(),
+        NOTARY_HASH.to_string(),
+    ];
+
+    // ── JSON / decimal ───────────────────────────────────────
+    let json_outcome = contract
+        .call("submit_attestation")
+        .args_json(json!({
+            "source_url": source_url,
+            "server_name": server_name,
+            "timestamp": timestamp,
+            "response_data": response_data,
+            "proof_a": [G1_X, G1_Y],
+            "proof_b": G2,
+            "proof_c": [G1_X, G1_Y],
+            "public_signals": signals,
+            "notary_signature": hex::encode(signature),
+            "notary_sig_v": sig_v,
+        }))
+        .gas(Gas::from_tgas(300))
+        .deposit(NearToken::from_millinear(50))
+        .transact()
+        .await?;
+
+    // ── Borsh / LE bytes ─────────────────────────────────────
+    let args = SubmitAttestationArgs {
+        source_url: source_url.to_string(),
+        server_name: server_name.to_string(),
+        timestamp,
+        response_data: response_data.to_string(),
+        proof_a: [decimal_to_le(G1_X), decimal_to_le(G1_Y)],
+        proof_b: G2.map(|row| row.map(decimal_to_le)),
+        proof_c: [decimal_to_le(G1_X), decimal_to_le(G1_Y)],
+        public_signals: [
+            decimal_to_le(&signals[0]),
+            decimal_to_le(&signals[1]),
+            decimal_to_le(&signals[2]),
+            decimal_to_le(&signals[3]),
+        ],
+        notary_signature: signature,
+        notary_sig_v: sig_v,
+        request_hash: None,
+    };
+    let borsh_outcome = contract
+        .call("submit_attestation_borsh")
+        .args_borsh(args)
+        .gas(Gas::from_tgas(300))
+        .deposit(NearToken::from_millinear(50))
+        .transact()
+        .await?;
+
+    // Оба падают на pairing check, а не раньше
+    for outcome in [&json_outcome, &borsh_outcome] {
+        assert!(outcome.is_failure());
+        let err = format!("{:?}", outcome.failures());
+        assert!(err.contains("ZK proof невалиден"), "неожиданная ошибка: {err}");
+    }
+
+    let json_gas = json_outcome.total_gas_burnt;
+    let borsh_gas = borsh_outcome.total_gas_burnt;
+    println!(
+        "submit_attestation:       {:>8.3} TGas\nsubmit_attestation_borsh: {:>8.3} TGas\nэкономия:                 {:>8.3} TGas",
+        json_gas.as_gas() as f64 / 1e12,
+        borsh_gas.as_gas() as f64 / 1e12,
+        (json_gas.as_gas() as f64 - borsh_gas.as_gas() as f64) / 1e12,
+    );
+    assert!(borsh_gas < json_gas, "Borsh вызов должен сжигать меньше газа");
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Окно оспаривания аттестаций (disputes)
+//!
+//! Для доменов с challenge period аттестация не финальна, пока окно не
+//! закрылось. В окне любой аккаунт может открыть dispute с залогом (bond).
+//! Dispute решает arbiter или совет (предложение `resolveDispute`):
+//!   - upheld   — аттестация признана недействительной, bond возвращается
+//!   - rejected — bond уходит в treasury (`withdraw_treasury`; без настроенных
+//!     комиссий — на owner)
+//!
+//! finalized = окно закрыто, открытого dispute нет, аттестация не признана
+//! недействительной. Аттестации доменов без окна финальны сразу.
+
+use near_sdk::json_types::U128;
+use near_sdk::{env, near, require, NearToken, Promise};
+
+pub use tls_oracle_types::{AttestationView, Dispute, DisputeConfig, DisputeStatus};
+
+use crate::{Attestation, TlsOracle, TlsOracleExt};
+
+fn now_secs() -> u64 {
+    env::block_timestamp() / 1_000_000_000
+}
+
+impl TlsOracle {
+    /// Открывает окно оспаривания для новой аттестации (если у домена есть период)
+    pub(crate) fn start_challenge_window(&mut self, attestation: &Attestation) {
+        if let Some(period) = self.challenge_periods.get(&attestation.server_name) {
+            self.challenge_ends
+                .insert(attestation.id, now_secs() + *period);
+        }
+    }
+
+    pub(crate) fn is_finalized(&self, attestation_id: u64) -> bool {
+        if self.invalidated.contains(&attestation_id)
+            || self.open_disputes.contains_key(&attestation_id)
+        {
+            return false;
+        }
+        self.challenge_ends
+            .get(&attestation_id)
+            .is_none_or(|ends| now_secs() >= *ends)
+    }
+
+    pub(crate) fn attestation_view(&self, attestation: &Attestation) -> AttestationView {
+        AttestationView {
+            attestation: attestation.clone(),
+            finalized: self.is_finalized(attestation.id),
+            challenge_ends_at: self.challenge_ends.get(&attestation.id).copied(),
+            invalidated: self.invalidated.contains(&attestation.id),
+        }
+    }
+
+    /// Решение по dispute (arbiter или совет). Err — dispute уже закрыт
+    pub(crate) fn settle_dispute(
+        &mut self,
+        dispute_id: u64,
+        upheld: bool,
+    ) -> Result<(), &'static str> {
+        let Some(mut dispute) = self.disputes.get(dispute_id as u32).cloned() else {
+            return Err("dispute не найден");
+        };
+        if dispute.status != DisputeStatus::Open {
+            return Err("dispute уже закрыт");
+        }
+
+        self.open_disputes.remove(&dispute.attestation_id);
+        dispute.resolved_at = Some(now_secs());
+
+        if upheld {
+            dispute.status = DisputeStatus::Upheld;
+            self.invalidated.insert(dispute.attestation_id);
+            if let Some(attestation) = self.attestation(dispute.attestation_id) {
+                self.record_notary_dispute(&attestation.notary_pubkey_hash);
+            }
+            Promise::new(dispute.challenger.clone())
+                .transfer(NearToken::from_yoctonear(dispute.bond.0))
+                .detach();
+        } else {
+            dispute.status = DisputeStatus::Rejected;
+            self.treasury_balance += dispute.bond.0;
+        }
+
+        env::log_str(&format!(
+            "Dispute #{} по аттестации #{}: {}",
+            dispute_id,
+            dispute.attestation_id,
+            if upheld {
+                "признан"
+            } else {
+                "отклонён"
+            }
+        ));
+        self.disputes.replace(dispute_id as u32, dispute);
+        Ok(())
+    }
+}
+
+#[near]
+impl TlsOracle {
+    // ── Настройки (admin) ────────────────────────────────────
+
+    pub fn set_dispute_config(&mut self, config: Option<DisputeConfig>) {
+        self.assert_owner("Только owner может менять настройки disputes");
+        self.dispute_config = config;
+    }
+
+    /// Окно оспаривания для домена, секунды (None — аттестации финальны сразу)
+    ///
+    /// Действует на аттестации, сохранённые после изменения.
+    pub fn set_challenge_period(&mut self, server_name: String, period_secs: Option<u64>) {
+        self.assert_owner("Только owner может менять окно оспаривания");
+        match period_secs {
+            Some(period) => {
+                require!(period <= 30 * 24 * 3600, "Окно оспаривания макс 30 дней");
+                self.challenge_periods.insert(server_name, period);
+            }
+            None => {
+                self.challenge_periods.remove(&server_name);
+            }
+        }
+    }
+
+    // ── Disputes ─────────────────────────────────────────────
+
+    /// Открыть dispute против аттестации (attached deposit ≥ bond). Возвращает id
+    #[payable]
+    pub fn open_dispute(&mut self, attestation_id: u64, reason: String) -> u64 {
+        let config = self.dispute_config.clone().expect("Disputes не настроены");
+        require!(reason.len() <= 1024, "reason макс 1KB");
+        require!(
+            self.has_attestation(attestation_id),
+            "Аттестация не найдена"
+        );
+        let ends = *self
+            .challenge_ends
+            .get(&attestation_id)
+            .expect("У аттестации нет окна оспаривания");
+        require!(now_secs() < ends, "Окно оспаривания закрыто");
+        require!(
+            !self.open_disputes.contains_key(&attestation_id),
+            "По аттестации уже открыт dispute"
+        );
+        require!(
+            !self.invalidated.contains(&attestation_id),
+            "Аттестация уже признана недействительной"
+        );
+
+        let deposit = env::attached_deposit().as_yoctonear();
+        require!(
+            deposit >= config.bond.0,
+            format!("Нужен залог {} yoctoNEAR", config.bond.0)
+        );
+
+        let id = self.disputes.len() as u64;
+        self.disputes.push(Dispute {
+            id,
+            attestation_id,
+            challenger: env::predecessor_account_id(),
+            bond: U128(deposit),
+            reason,
+            status: DisputeStatus::Open,
+            opened_at: now_secs(),
+            resolved_at: None,
+        });
+        self.open_disputes.insert(attestation_id, id);
+        env::log_str(&format!(
+            "Dispute #{} открыт по аттестации #{}",
+            id, attestation_id
+        ));
+        id
+    }
+
+    /// Решение arbiter по dispute
+    pub fn resolve_dispute(&mut self, dispute_id: u64, upheld: bool) {
+        let arbiter = self
+            .dispute_config
+            .as_ref()
+            .and_then(|c| c.arbiter.clone())
+            .expect("Arbiter не назначен — disputes решает совет");
+        require!(env::predecessor_account_id() == arbiter, "Только arbiter");
+        if let Err(reason) = self.settle_dispute(dispute_id, upheld) {
+            env::panic_str(reason);
+        }
+    }
+
+    // ── View методы ──────────────────────────────────────────
+
+    pub fn get_dispute_config(&self) -> Option<DisputeConfig> {
+        self.dispute_config.clone()
+    }
+
+    pub fn get_challenge_period(&self, server_name: String) -> Option<u64> {
+        self.challenge_periods.get(&server_name).copied()
+    }
+
+    pub fn get_dispute(&self, id: u64) -> Option<Dispute> {
+        self.disputes.get(id as u32).cloned()
+    }
+
+    /// Открытый dispute по аттестации
+    pub fn get_open_dispute(&self, attestation_id: u64) -> Option<Dispute> {
+        self.open_disputes
+            .get(&attestation_id)
+            .and_then(|id| self.disputes.get(*id as u32).cloned())
+    }
+
+    pub fn is_attestation_finalized(&self, attestation_id: u64) -> bool {
+        self.has_attestation(attestation_id) && self.is_finalized(attestation_id)
+    }
+}
@@ -0,0 +1,155 @@
+//! Верификация Groth16 / PLONK через внешний groth16-verifier контракт
+//!
+//! Один аудированный verifier с реестром verification keys обслуживает
+//! несколько circuits и потребителей. Oracle вызывает `verify_bytes`
+//! (или `verify_plonk_bytes`) через promise и сохраняет аттестацию в callback.
+//! Если сам callback падает (газ, storage), `on_submit_resolved` возвращает
+//! комиссию: изменения упавшего callback откатываются, а депозит уже у контракта.
+
+use near_sdk::{env, ext_contract, near, AccountId, Gas, Promise, PromiseError};
+
+use groth16_verifier::bn254_types::Scalar;
+use groth16_verifier::groth16::Proof;
+use groth16_verifier::{plonk, PlonkVerifyArgs, VerifyArgs};
+
+pub use tls_oracle_types::VerifierConfig;
+
+use crate::{Attestation, Fee, TlsOracle, TlsOracleExt};
+
+/// Газ на pairing check во внешнем verifier (4 пары + MSM)
+const GAS_FOR_VERIFY: Gas = Gas::from_tgas(50);
+/// Газ на callback сохранения аттестации: запись аттестации, индекса URL,
+/// статистики нотариуса и комиссии. Host-функции в testing_env — ~0.7 TGas
+/// (tests::test_on_proof_verified_gas_budget), остальное — исполнение wasm и
+/// чтение состояния; бюджет с запасом на рост индексов
+pub(crate) const GAS_FOR_ON_VERIFIED: Gas = Gas::from_tgas(30);
+/// Газ на проверку результата callback и возврат комиссии
+const GAS_FOR_ON_RESOLVED: Gas = Gas::from_tgas(10);
+
+#[ext_contract(ext_groth16_verifier)]
+#[allow(dead_code)]
+trait Groth16Verifier {
+    fn verify_bytes(&self, #[serializer(borsh)] args: VerifyArgs) -> bool;
+    fn verify_plonk_bytes(&self, #[serializer(borsh)] args: PlonkVerifyArgs) -> bool;
+}
+
+/// verify → on_proof_verified → on_submit_resolved
+fn then_store(verify: Promise, attestation: Attestation, fee: Fee) -> Promise {
+    let submitter = attestation.submitter.clone();
+    verify
+        .then(
+            TlsOracle::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_VERIFIED)
+                .on_proof_verified(attestation, fee.clone()),
+        )
+        .then(
+            TlsOracle::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_RESOLVED)
+                .on_submit_resolved(submitter, fee),
+        )
+}
+
+/// verify_bytes → on_proof_verified
+pub(crate) fn verify_then_store(
+    verifier: &VerifierConfig,
+    proof: Proof,
+    public_inputs: Vec<Scalar>,
+    attestation: Attestation,
+    fee: Fee,
+) -> Promise {
+    let verify = ext_groth16_verifier::ext(verifier.account_id.clone())
+        .with_static_gas(GAS_FOR_VERIFY)
+        .verify_bytes(VerifyArgs {
+            vk_id: verifier.vk_id.clone(),
+            proof,
+            public_inputs,
+        });
+    then_store(verify, attestation, fee)
+}
+
+/// verify_plonk_bytes → on_proof_verified
+pub(crate) fn verify_plonk_then_store(
+    verifier: &VerifierConfig,
+    proof: plonk::Proof,
+    public_inputs: Vec<Scalar>,
+    attestation: Attestation,
+    fee: Fee,
+) -> Promise {
+    let vk_id = verifier
+        .plonk_vk_id
+        .clone()
+        .unwrap_or_else(|| env::panic_str("PLONK vk_id не настроен во внешнем verifier"));
+    let verify = ext_groth16_verifier::ext(verifier.account_id.clone())
+        .with_static_gas(GAS_FOR_VERIFY)
+        .verify_plonk_bytes(PlonkVerifyArgs {
+            vk_id,
+            proof,
+            public_inputs,
+        });
+    then_store(verify, attestation, fee)
+}
+
+#[near]
+impl TlsOracle {
+    /// Настроить внешний verifier (None — верификация внутри контракта)
+    pub fn set_verifier(&mut self, verifier: Option<VerifierConfig>) {
+        self.assert_owner("Только owner может менять verifier");
+        #[cfg(not(feature = "linked-verifier"))]
+        near_sdk::require!(
+            verifier.is_some(),
+            "Контракт собран без linked-verifier — нужен внешний verifier"
+        );
+        self.verifier = verifier;
+    }
+
+    pub fn get_verifier(&self) -> Option<VerifierConfig> {
+        self.verifier.clone()
+    }
+
+    /// Callback: результат внешней Groth16 / PLONK верификации
+    ///
+    /// При отказе не паникует: комиссия возвращается submitter, результат — None.
+    #[private]
+    pub fn on_proof_verified(
+        &mut self,
+        attestation: Attestation,
+        fee: Fee,
+        #[callback_result] verified: Result<bool, PromiseError>,
+    ) -> Option<u64> {
+        let rejection = if !matches!(verified, Ok(true)) {
+            Some("ZK proof невалиден")
+        } else if self.used_commitments.contains(&attestation.data_commitment) {
+            // Повторная replay-проверка: между submit и callback мог пройти такой же commitment
+            Some("Эта аттестация уже была отправлена (replay)")
+        } else {
+            None
+        };
+
+        if let Some(reason) = rejection {
+            env::log_str(&format!("Аттестация отклонена: {}", reason));
+            self.refund_fee(attestation.submitter, fee);
+            return None;
+        }
+
+        Some(self.finish_zk_attestation(attestation, fee))
+    }
+
+    /// Callback: on_proof_verified упал — его изменения откатились, комиссия
+    /// возвращается submitter. Отказ верификации комиссию уже вернул (Ok(None))
+    #[private]
+    pub fn on_submit_resolved(
+        &mut self,
+        submitter: AccountId,
+        fee: Fee,
+        #[callback_result] stored: Result<Option<u64>, PromiseError>,
+    ) -> Option<u64> {
+        match stored {
+            Ok(id) => id,
+            Err(_) => {
+                env::log_str("Сохранение аттестации не удалось, комиссия возвращена");
+                self.refund_fee(submitter, fee);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Комиссии за аттестации: treasury + вознаграждения нотариусов
+//!
+//! Submitter прикладывает комиссию к submit (attached deposit). При сохранении
+//! аттестации комиссия делится: treasury_bps — в treasury, остаток — на
+//! claimable баланс нотариуса по `notary_pubkey_hash` аттестации.
+//! Нотариус забирает баланс через `claim_rewards` на свой reward account.
+//!
+//! Если NEAR депозита не хватает, комиссия списывается из предоплаты
+//! в NEP-141 токенах (см. `ft_payments`). Аккаунт с активной подпиской
+//! комиссию не платит.
+
+use near_sdk::json_types::U128;
+use near_sdk::{env, near, require, AccountId, Gas, NearToken, Promise, PromiseError};
+
+pub use tls_oracle_types::{Fee, FeeConfig};
+
+use crate::ft_payments::FtHolder;
+use crate::{TlsOracle, TlsOracleExt};
+
+/// Газ на callback после перевода (восстановление баланса при неудаче)
+const GAS_FOR_ON_TRANSFER: Gas = Gas::from_tgas(5);
+/// 100% в basis points
+const BPS_DENOMINATOR: u128 = 10_000;
+
+impl TlsOracle {
+    /// Комиссия для домена: per-domain override или значение по умолчанию
+    pub(crate) fn fee_for(&self, server_name: &str) -> u128 {
+        let Some(config) = &self.fee_config else {
+            return 0;
+        };
+        self.domain_fees.get(server_name).unwrap_or(&config.fee).0
+    }
+
+    /// Взимает комиссию: attached deposit, иначе предоплата в NEP-141 токене
+    ///
+    /// Излишек депозита сверх комиссии возвращается submitter; при оплате
+    /// токенами или по подписке возвращается весь депозит.
+    pub(crate) fn require_fee(&mut self, server_name: &str) -> Fee {
+        let submitter = env::predecessor_account_id();
+        let deposit = env::attached_deposit().as_yoctonear();
+        let fee = if self.has_subscription(&submitter) {
+            0
+        } else {
+            self.fee_for(server_name)
+        };
+        if deposit >= fee {
+            refund_deposit(deposit - fee);
+            return Fee::Near(U128(fee));
+        }
+        let charged = self
+            .charge_prepaid(&submitter, server_name)
+            .unwrap_or_else(|| {
+                env::panic_str(&format!(
+                    "Недостаточный депозит: комиссия {} yoctoNEAR",
+                    fee
+                ))
+            });
+        refund_deposit(deposit);
+        charged
+    }
+
+    /// Получатель treasury: из настроек комиссий, иначе owner
+    ///
+    /// В treasury попадают и залоги отклонённых disputes, и оплата подписок —
+    /// они выводятся и без настроенных комиссий.
+    pub(crate) fn treasury_account(&self) -> AccountId {
+        self.fee_config
+            .as_ref()
+            .map_or_else(|| self.owner.clone(), |c| c.treasury.clone())
+    }
+
+    /// Делит комиссию сохранённой аттестации между treasury и нотариусом
+    pub(crate) fn distribute_fee(&mut self, notary_pubkey_hash: &str, fee: &Fee) {
+        let treasury_bps = self
+            .fee_config
+            .as_ref()
+            .map_or(0, |c| c.treasury_bps as u128);
+        match fee {
+            Fee::Near(U128(amount)) => {
+                let treasury_share = amount * treasury_bps / BPS_DENOMINATOR;
+                self.treasury_balance += treasury_share;
+
+                let reward = self
+                    .notary_rewards
+                    .get(notary_pubkey_hash)
+                    .copied()
+                    .unwrap_or(0);
+                self.notary_rewards.insert(
+                    notary_pubkey_hash.to_string(),
+                    reward + amount - treasury_share,
+                );
+            }
+            Fee::Token { token_id, amount } => {
+                let treasury_share = amount.0 * treasury_bps / BPS_DENOMINATOR;
+                self.ft_credit(FtHolder::Treasury, token_id, treasury_share);
+                self.ft_credit(
+                    FtHolder::Notary(notary_pubkey_hash.to_string()),
+                    token_id,
+                    amount.0 - treasury_share,
+                );
+            }
+        }
+    }
+
+    /// Возврат комиссии отклонённой аттестации
+    pub(crate) fn refund_fee(&mut self, submitter: AccountId, fee: Fee) {
+        match fee {
+            Fee::Near(U128(0)) => {}
+            Fee::Near(U128(amount)) => {
+                Promise::new(submitter)
+                    .transfer(NearToken::from_yoctonear(amount))
+                    .detach();
+            }
+            Fee::Token { token_id, amount } => {
+                self.ft_credit(FtHolder::Account(submitter), &token_id, amount.0);
+            }
+        }
+    }
+}
+
+/// Возврат неиспользованной части attached deposit вызывающему
+pub(crate) fn refund_deposit(amount: u128) {
+    if amount > 0 {
+        Promise::new(env::predecessor_account_id())
+            .transfer(NearToken::from_yoctonear(amount))
+            .detach();
+    }
+}
+
+#[near]
+impl TlsOracle {
+    // ── Настройки (admin) ────────────────────────────────────
+
+    /// Настроить комиссии (None — аттестации бесплатны)
+    pub fn set_fee_config(&mut self, config: Option<FeeConfig>) {
+        self.assert_owner("Только owner может менять комиссии");
+        if let Some(c) = &config {
+            require!(
+                c.treasury_bps as u128 <= BPS_DENOMINATOR,
+                "treasury_bps: 0..10000"
+            );
+        }
+        self.fee_config = config;
+    }
+
+    /// Комиссия для конкретного домена (None — комиссия по умолчанию)
+    pub fn set_domain_fee(&mut self, server_name: String, fee: Option<U128>) {
+        self.assert_owner("Только owner может менять комиссии");
+        match fee {
+            Some(fee) => {
+                self.domain_fees.insert(server_name, fee);
+            }
+            None => {
+                self.domain_fees.remove(&server_name);
+            }
+        }
+    }
+
+    /// Аккаунт, на который нотариус получает вознаграждения
+    pub fn set_notary_reward_account(&mut self, pubkey_hash: String, account_id: AccountId) {
+        self.assert_owner("Только owner может менять reward account");
+        require!(
+            self.trusted_notaries.contains_key(&pubkey_hash),
+            "Нотариус не найден"
+        );
+        self.notary_reward_accounts.insert(pubkey_hash, account_id);
+    }
+
+    // ── Выплаты ──────────────────────────────────────────────
+
+    /// Вывести накопленные вознаграждения нотариуса на его reward account
+    pub fn claim_rewards(&mut self, pubkey_hash: String) -> Promise {
+        let account_id = self
+            .notary_reward_accounts
+            .get(&pubkey_hash)
+            .cloned()
+            .expect("Reward account нотариуса не установлен");
+        require!(
+            env::predecessor_account_id() == account_id,
+            "Только reward account нотариуса"
+        );
+
+        let amount = self.notary_rewards.remove(&pubkey_hash).unwrap_or(0);
+        require!(amount > 0, "Нет вознаграждений");
+
+        Promise::new(account_id)
+            .transfer(NearToken::from_yoctonear(amount))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_TRANSFER)
+                    .on_rewards_claimed(pubkey_hash, U128(amount)),
+            )
+    }
+
+    /// Перевести накопленную долю treasury на treasury аккаунт (без комиссий — на owner)
+    pub fn withdraw_treasury(&mut self) -> Promise {
+        self.assert_owner("Только owner");
+        let treasury = self.treasury_account();
+        let amount = std::mem::take(&mut self.treasury_balance);
+        require!(amount > 0, "Treasury пуст");
+
+        Promise::new(treasury)
+            .transfer(NearToken::from_yoctonear(amount))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_TRANSFER)
+                    .on_treasury_withdrawn(U128(amount)),
+            )
+    }
+
+    /// Callback: при неудачном переводе возвращаем баланс нотариуса
+    #[private]
+    pub fn on_rewards_claimed(
+        &mut self,
+        pubkey_hash: String,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            let reward = self.notary_rewards.get(&pubkey_hash).copied().unwrap_or(0);
+            self.notary_rewards.insert(pubkey_hash, reward + amount.0);
+            env::log_str("Перевод вознаграждения не удался, баланс восстановлен");
+        }
+    }
+
+    /// Callback: при неудачном переводе возвращаем баланс treasury
+    #[private]
+    pub fn on_treasury_withdrawn(
+        &mut self,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.treasury_balance += amount.0;
+            env::log_str("Перевод в treasury не удался, баланс восстановлен");
+        }
+    }
+
+    // ── View методы ──────────────────────────────────────────
+
+    pub fn get_fee_config(&self) -> Option<FeeConfig> {
+        self.fee_config.clone()
+    }
+
+    /// Комиссия за аттестацию для домена (yoctoNEAR)
+    pub fn get_fee(&self, server_name: String) -> U128 {
+        U128(self.fee_for(&server_name))
+    }
+
+    pub fn get_notary_rewards(&self, pubkey_hash: String) -> U128 {
+        U128(self.notary_rewards.get(&pubkey_hash).copied().unwrap_or(0))
+    }
+
+    pub fn get_notary_reward_account(&self, pubkey_hash: String) -> Option<AccountId> {
+        self.notary_reward_accounts.get(&pubkey_hash).cloned()
+    }
+
+    pub fn get_treasury_balance(&self) -> U128 {
+        U128(self.treasury_balance)
+    }
+}
@@ -0,0 +1,381 @@
+//! Оплата в NEP-141 токенах (USDC и др. из whitelist)
+//!
+//! `ft_transfer_call` на контракт oracle с `msg`:
+//!   - `{"action": "prepay"}` — предоплата комиссий за аттестации отправителя
+//!   - `{"action": "bounty", "serverName": "..."}` — bounty за следующую
+//!     аттестацию домена, начисляется на баланс её submitter
+//!   - `{"action": "subscribe"}` — подписка: аттестации отправителя без комиссии
+//!     на целое число периодов `SUBSCRIPTION_PERIOD`, остаток возвращается
+//!
+//! Нераспознанный msg — токены возвращаются целиком через стандартный
+//! refund (ft_resolve_transfer).
+//!
+//! Каждая новая запись баланса (предоплата, bounty домена) занимает storage
+//! контракта, поэтому первое пополнение должно покрывать хотя бы одну комиссию
+//! в этом токене — иначе токены возвращаются. Так запись стоит отправителю
+//! не меньше комиссии, и спам пылью не раздувает storage.
+
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    env, near, require, AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue,
+};
+
+pub use tls_oracle_types::{FtHolder, FtTokenInfo};
+
+use crate::fees::Fee;
+use crate::{TlsOracle, TlsOracleExt};
+
+/// Максимум токенов в whitelist (bounties проверяются по всем токенам при сохранении)
+pub const MAX_FT_TOKENS: u32 = 10;
+/// Газ на ft_transfer в контракте токена
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+/// Газ на callback после ft_transfer
+const GAS_FOR_ON_FT_PAYOUT: Gas = Gas::from_tgas(5);
+/// Период подписки, секунды (30 дней)
+pub const SUBSCRIPTION_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// Действие в `msg` ft_transfer_call
+#[near(serializers = [json])]
+#[serde(tag = "action", rename_all = "camelCase")]
+enum FtMessage {
+    Prepay,
+    #[serde(rename_all = "camelCase")]
+    Bounty {
+        server_name: String,
+    },
+    Subscribe,
+}
+
+impl TlsOracle {
+    fn ft_balance(&self, holder: &FtHolder, token_id: &AccountId) -> u128 {
+        self.ft_balances
+            .get(&(holder.clone(), token_id.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn ft_credit(&mut self, holder: FtHolder, token_id: &AccountId, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let balance = self.ft_balance(&holder, token_id);
+        self.ft_balances
+            .insert((holder, token_id.clone()), balance + amount);
+    }
+
+    fn ft_debit(&mut self, holder: FtHolder, token_id: &AccountId, amount: u128) {
+        let balance = self.ft_balance(&holder, token_id);
+        require!(balance >= amount, "Недостаточно токенов на балансе");
+        if balance == amount {
+            self.ft_balances.remove(&(holder, token_id.clone()));
+        } else {
+            self.ft_balances
+                .insert((holder, token_id.clone()), balance - amount);
+        }
+    }
+
+    /// Комиссия домена в токене
+    ///
+    /// Per-domain комиссия в токене, иначе комиссия токена по умолчанию. Если
+    /// для домена задана только NEAR комиссия, токен для него не принимается —
+    /// иначе домен с повышенной комиссией оплачивался бы по базовой цене.
+    fn ft_fee_for(&self, server_name: &str, token_id: &AccountId, default: U128) -> Option<u128> {
+        if let Some(fee) = self
+            .ft_domain_fees
+            .get(&(server_name.to_string(), token_id.clone()))
+        {
+            return Some(fee.0);
+        }
+        (!self.domain_fees.contains_key(server_name)).then_some(default.0)
+    }
+
+    /// Списывает комиссию домена из предоплаты в первом токене с достаточным балансом
+    pub(crate) fn charge_prepaid(
+        &mut self,
+        account_id: &AccountId,
+        server_name: &str,
+    ) -> Option<Fee> {
+        let holder = FtHolder::Account(account_id.clone());
+        let (token_id, fee) = self.ft_tokens.iter().find_map(|(token_id, default)| {
+            let fee = self.ft_fee_for(server_name, token_id, *default)?;
+            (self.ft_balance(&holder, token_id) >= fee).then(|| (token_id.clone(), fee))
+        })?;
+        self.ft_debit(holder, &token_id, fee);
+        Some(Fee::Token {
+            token_id,
+            amount: U128(fee),
+        })
+    }
+
+    /// Активная подписка аккаунта: аттестации без комиссии
+    pub(crate) fn has_subscription(&self, account_id: &AccountId) -> bool {
+        self.subscriptions
+            .get(account_id)
+            .is_some_and(|&until| until > env::block_timestamp() / 1_000_000_000)
+    }
+
+    /// Продлевает подписку на целое число периодов; возвращает неиспользованный остаток
+    fn subscribe(&mut self, account_id: AccountId, token_id: &AccountId, amount: u128) -> u128 {
+        let Some(price) = self.ft_subscription_prices.get(token_id).map(|p| p.0) else {
+            env::log_str(
+                "ft_on_transfer: подписка в этом токене не продаётся, токены возвращаются",
+            );
+            return amount;
+        };
+        let periods = amount / price;
+        if periods == 0 {
+            env::log_str("ft_on_transfer: сумма меньше цены подписки, токены возвращаются");
+            return amount;
+        }
+        let periods =
+            u64::try_from(periods).unwrap_or_else(|_| env::panic_str("Слишком много периодов"));
+
+        let now = env::block_timestamp() / 1_000_000_000;
+        let start = self
+            .subscriptions
+            .get(&account_id)
+            .copied()
+            .unwrap_or(0)
+            .max(now);
+        let until = periods
+            .checked_mul(SUBSCRIPTION_PERIOD)
+            .and_then(|d| start.checked_add(d))
+            .unwrap_or_else(|| env::panic_str("Слишком длинная подписка"));
+        self.subscriptions.insert(account_id.clone(), until);
+
+        // Подписка не привязана к нотариусу — оплата целиком в treasury
+        let paid = price * periods as u128;
+        self.ft_credit(FtHolder::Treasury, token_id, paid);
+        env::log_str(&format!("Подписка {} до {}", account_id, until));
+        amount - paid
+    }
+
+    /// Переводит bounties домена на баланс submitter сохранённой аттестации
+    pub(crate) fn pay_bounties(&mut self, server_name: &str, submitter: &AccountId) {
+        let tokens: Vec<AccountId> = self.ft_tokens.keys().cloned().collect();
+        for token_id in tokens {
+            let key = (FtHolder::Bounty(server_name.to_string()), token_id.clone());
+            if let Some(amount) = self.ft_balances.remove(&key) {
+                self.ft_credit(FtHolder::Account(submitter.clone()), &token_id, amount);
+                env::log_str(&format!(
+                    "Bounty {} {} за {} → {}",
+                    amount, token_id, server_name, submitter
+                ));
+            }
+        }
+    }
+
+    /// ft_transfer с баланса holder; при неудаче баланс восстанавливается
+    fn ft_payout(
+        &mut self,
+        holder: FtHolder,
+        token_id: AccountId,
+        receiver_id: AccountId,
+        amount: u128,
+    ) -> Promise {
+        require!(amount > 0, "Нечего выводить");
+        self.ft_debit(holder.clone(), &token_id, amount);
+
+        ext_ft_core::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver_id, U128(amount), None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_FT_PAYOUT)
+                    .on_ft_payout(holder, token_id, U128(amount)),
+            )
+    }
+}
+
+#[near]
+impl FungibleTokenReceiver for TlsOracle {
+    /// Приём токенов: предоплата комиссий или bounty. Возвращает неиспользованную сумму
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token_id = env::predecessor_account_id();
+        let fee = *self
+            .ft_tokens
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Токен не в whitelist"));
+
+        let Ok(message) = serde_json::from_str::<FtMessage>(&msg) else {
+            env::log_str("ft_on_transfer: неизвестный msg, токены возвращаются");
+            return PromiseOrValue::Value(amount);
+        };
+
+        let holder = match message {
+            FtMessage::Prepay => FtHolder::Account(sender_id),
+            FtMessage::Bounty { server_name } => {
+                require!(
+                    !server_name.is_empty() && server_name.len() <= 253,
+                    "serverName: 1..253 символов"
+                );
+                FtHolder::Bounty(server_name)
+            }
+            FtMessage::Subscribe => {
+                return PromiseOrValue::Value(U128(self.subscribe(sender_id, &token_id, amount.0)));
+            }
+        };
+
+        // Новая запись баланса — не меньше одной комиссии (storage)
+        if amount.0 < fee.0 && self.ft_balance(&holder, &token_id) == 0 {
+            env::log_str("ft_on_transfer: сумма меньше комиссии токена, токены возвращаются");
+            return PromiseOrValue::Value(amount);
+        }
+        self.ft_credit(holder, &token_id, amount.0);
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
+#[near]
+impl TlsOracle {
+    // ── Whitelist (admin) ────────────────────────────────────
+
+    /// Добавить токен в whitelist с комиссией за аттестацию (None — убрать)
+    ///
+    /// Балансы в убранном токене остаются доступными для вывода; bounties
+    /// в нём выплачиваются только после возврата токена в whitelist.
+    pub fn set_ft_token(&mut self, token_id: AccountId, fee: Option<U128>) {
+        self.assert_owner("Только owner может менять whitelist токенов");
+        match fee {
+            Some(fee) => {
+                // Комиссия — минимум новой записи баланса (storage)
+                require!(fee.0 > 0, "Комиссия в токене должна быть больше 0");
+                require!(
+                    self.ft_tokens.contains_key(&token_id) || self.ft_tokens.len() < MAX_FT_TOKENS,
+                    "Whitelist токенов заполнен"
+                );
+                self.ft_tokens.insert(token_id, fee);
+            }
+            None => {
+                self.ft_tokens.remove(&token_id);
+            }
+        }
+    }
+
+    /// Комиссия домена в токене (None — убрать)
+    ///
+    /// Нужна для доменов с `set_domain_fee`: без неё токен для такого домена
+    /// не принимается.
+    pub fn set_ft_domain_fee(
+        &mut self,
+        server_name: String,
+        token_id: AccountId,
+        fee: Option<U128>,
+    ) {
+        self.assert_owner("Только owner может менять комиссии");
+        let key = (server_name, token_id);
+        match fee {
+            Some(fee) => {
+                require!(self.ft_tokens.contains_key(&key.1), "Токен не в whitelist");
+                self.ft_domain_fees.insert(key, fee);
+            }
+            None => {
+                self.ft_domain_fees.remove(&key);
+            }
+        }
+    }
+
+    /// Цена подписки в токене за `SUBSCRIPTION_PERIOD` (None — не продаётся)
+    pub fn set_ft_subscription_price(&mut self, token_id: AccountId, price: Option<U128>) {
+        self.assert_owner("Только owner может менять комиссии");
+        match price {
+            Some(price) => {
+                require!(
+                    self.ft_tokens.contains_key(&token_id),
+                    "Токен не в whitelist"
+                );
+                require!(price.0 > 0, "Цена подписки должна быть больше 0");
+                self.ft_subscription_prices.insert(token_id, price);
+            }
+            None => {
+                self.ft_subscription_prices.remove(&token_id);
+            }
+        }
+    }
+
+    // ── Вывод токенов ────────────────────────────────────────
+
+    /// Вывести предоплату / выигранные bounties (None — весь баланс)
+    pub fn ft_withdraw(&mut self, token_id: AccountId, amount: Option<U128>) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let holder = FtHolder::Account(account_id.clone());
+        let amount = amount.map_or_else(|| self.ft_balance(&holder, &token_id), |a| a.0);
+        self.ft_payout(holder, token_id, account_id, amount)
+    }
+
+    /// Вывести награды нотариуса в токене на его reward account
+    pub fn ft_claim_rewards(&mut self, pubkey_hash: String, token_id: AccountId) -> Promise {
+        let account_id = self
+            .notary_reward_accounts
+            .get(&pubkey_hash)
+            .cloned()
+            .expect("Reward account нотариуса не установлен");
+        require!(
+            env::predecessor_account_id() == account_id,
+            "Только reward account нотариуса"
+        );
+        let holder = FtHolder::Notary(pubkey_hash);
+        let amount = self.ft_balance(&holder, &token_id);
+        self.ft_payout(holder, token_id, account_id, amount)
+    }
+
+    /// Перевести долю treasury в токене на treasury аккаунт
+    pub fn ft_withdraw_treasury(&mut self, token_id: AccountId) -> Promise {
+        self.assert_owner("Только owner");
+        let treasury = self.treasury_account();
+        let amount = self.ft_balance(&FtHolder::Treasury, &token_id);
+        self.ft_payout(FtHolder::Treasury, token_id, treasury, amount)
+    }
+
+    /// Callback: при неудачном ft_transfer возвращаем баланс
+    #[private]
+    pub fn on_ft_payout(
+        &mut self,
+        holder: FtHolder,
+        token_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.ft_credit(holder, &token_id, amount.0);
+            env::log_str("ft_transfer не удался, баланс восстановлен");
+        }
+    }
+
+    // ── View методы ──────────────────────────────────────────
+
+    pub fn get_ft_tokens(&self) -> Vec<FtTokenInfo> {
+        self.ft_tokens
+            .iter()
+            .map(|(token_id, fee)| FtTokenInfo {
+                token_id: token_id.clone(),
+                fee: *fee,
+                subscription_price: self.ft_subscription_prices.get(token_id).copied(),
+            })
+            .collect()
+    }
+
+    /// Комиссия домена в токене (None — токен для домена не принимается)
+    pub fn get_ft_fee(&self, server_name: String, token_id: AccountId) -> Option<U128> {
+        let default = *self.ft_tokens.get(&token_id)?;
+        self.ft_fee_for(&server_name, &token_id, default).map(U128)
+    }
+
+    /// Конец подписки аккаунта (UNIX, секунды)
+    pub fn get_subscription(&self, account_id: AccountId) -> Option<u64> {
+        self.subscriptions.get(&account_id).copied()
+    }
+
+    /// Баланс держателя в токене (предоплата, bounty, награды нотариуса, treasury)
+    pub fn get_ft_balance(&self, holder: FtHolder, token_id: AccountId) -> U128 {
+        U128(self.ft_balance(&holder, &token_id))
+    }
+}
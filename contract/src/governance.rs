@@ -0,0 +1,390 @@
+//! Governance набора нотариусов: предложения и взвешенные голоса совета
+//!
+//! Owner один раз включает governance (`init_governance`) — после этого
+//! add_notary / remove_notary закрыты, нотариусы и политика меняются только
+//! предложениями. Остальные owner-методы (комиссии, verifier, disputes,
+//! upgrade, set_owner) вызывает сам контракт по предложению `FunctionCall` —
+//! owner напрямую их больше не вызывает.
+//!
+//! Предложение исполняется автоматически, как только вес голосов "за" достигает
+//! кворума. По истечении voting period оно Expired: view методы показывают это
+//! сразу, `close_proposal` (любой аккаунт) записывает статус в состояние.
+
+use near_sdk::{env, near, require, AccountId, Gas, NearToken, Promise, PromiseError};
+
+pub use tls_oracle_types::{
+    CouncilMember, GovernancePolicy, Proposal, ProposalKind, ProposalStatus, Vote,
+};
+
+use crate::{TlsOracle, TlsOracleExt};
+
+/// Owner-методы, доступные предложению `FunctionCall`
+const OWNER_METHODS: &[&str] = &[
+    "set_owner",
+    "set_plonk_vk",
+    "set_verifier",
+    "set_soulbound_mint",
+    "set_fee_config",
+    "set_domain_fee",
+    "set_notary_reward_account",
+    "withdraw_treasury",
+    "set_ft_token",
+    "set_ft_domain_fee",
+    "set_ft_subscription_price",
+    "ft_withdraw_treasury",
+    "set_dispute_config",
+    "set_challenge_period",
+    "approve_upgrade",
+    "cancel_upgrade",
+];
+/// Максимальный размер аргументов `FunctionCall` (хранятся в предложении)
+const MAX_CALL_ARGS_LEN: usize = 16 * 1024;
+/// Газ на owner-метод, вызванный предложением
+const GAS_FOR_PROPOSAL_CALL: Gas = Gas::from_tgas(50);
+/// Газ на callback результата owner-метода
+const GAS_FOR_ON_PROPOSAL_CALL: Gas = Gas::from_tgas(5);
+
+fn now_secs() -> u64 {
+    env::block_timestamp() / 1_000_000_000
+}
+
+/// Предложение со статусом на текущий блок: Active после expires_at — Expired
+fn with_current_status(mut proposal: Proposal) -> Proposal {
+    proposal.status = proposal.status_at(now_secs());
+    proposal
+}
+
+fn assert_valid_policy(policy: &GovernancePolicy) {
+    require!(
+        policy.quorum_bps > 0 && policy.quorum_bps <= 10_000,
+        "quorum_bps: 1..10000"
+    );
+    require!(policy.voting_period_secs > 0, "voting_period_secs > 0");
+}
+
+impl TlsOracle {
+    /// Owner-метод: до governance — owner, после — только сам контракт
+    /// (исполнение предложения `FunctionCall`)
+    pub(crate) fn assert_owner(&self, message: &str) {
+        let caller = env::predecessor_account_id();
+        if self.governance.is_some() {
+            require!(
+                caller == env::current_account_id(),
+                "Owner-методы вызываются через governance (propose FunctionCall)"
+            );
+        } else {
+            require!(caller == self.owner, message);
+        }
+    }
+
+    /// Член совета (только при включённом governance)
+    pub(crate) fn is_council_member(&self, account_id: &AccountId) -> bool {
+        self.governance.is_some() && self.council.contains_key(account_id)
+    }
+
+    /// add_notary / remove_notary доступны owner только до включения governance
+    pub(crate) fn assert_owner_manages_notaries(&self) {
+        require!(
+            self.governance.is_none(),
+            "Нотариусы управляются через governance (propose)"
+        );
+    }
+
+    fn council_weight(&self) -> u64 {
+        self.council.values().map(|w| *w as u64).sum()
+    }
+
+    /// Применяет изменение. Err — предложение устарело (состояние уже другое)
+    fn execute_proposal(&mut self, proposal: &Proposal) -> Result<(), &'static str> {
+        match proposal.kind.clone() {
+            ProposalKind::AddNotary {
+                pubkey_hash,
+                name,
+                url,
+                raw_pubkey,
+            } => {
+                if self.trusted_notaries.contains_key(&pubkey_hash) {
+                    return Err("нотариус уже добавлен");
+                }
+                self.upsert_notary(
+                    pubkey_hash,
+                    name,
+                    url,
+                    raw_pubkey,
+                    proposal.proposer.clone(),
+                );
+            }
+            ProposalKind::UpdateNotary {
+                pubkey_hash,
+                name,
+                url,
+                raw_pubkey,
+            } => {
+                if !self.trusted_notaries.contains_key(&pubkey_hash) {
+                    return Err("нотариус не найден");
+                }
+                self.upsert_notary(
+                    pubkey_hash,
+                    name,
+                    url,
+                    raw_pubkey,
+                    proposal.proposer.clone(),
+                );
+            }
+            ProposalKind::RemoveNotary { pubkey_hash } => {
+                if !self.trusted_notaries.contains_key(&pubkey_hash) {
+                    return Err("нотариус не найден");
+                }
+                self.delete_notary(&pubkey_hash);
+            }
+            ProposalKind::SetPolicy { policy } => {
+                self.governance = Some(policy);
+            }
+            ProposalKind::SetCouncilMember { account_id, weight } => match weight {
+                Some(weight) => {
+                    self.council.insert(account_id, weight);
+                }
+                None => {
+                    if self.council.len() == 1 && self.council.contains_key(&account_id) {
+                        return Err("совет не может быть пустым");
+                    }
+                    self.council.remove(&account_id);
+                }
+            },
+            ProposalKind::ResolveDispute { dispute_id, upheld } => {
+                self.settle_dispute(dispute_id, upheld)?;
+            }
+            ProposalKind::FunctionCall { method_name, args } => {
+                // Результат метода — в on_proposal_call (Failed при ошибке)
+                Promise::new(env::current_account_id())
+                    .function_call(
+                        method_name,
+                        args.0,
+                        NearToken::from_yoctonear(0),
+                        GAS_FOR_PROPOSAL_CALL,
+                    )
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_ON_PROPOSAL_CALL)
+                            .on_proposal_call(proposal.id),
+                    )
+                    .detach();
+            }
+        }
+        Ok(())
+    }
+
+    /// Active предложение для голосования; истёкшее закрывается
+    fn load_active_proposal(&mut self, proposal_id: u64) -> Option<Proposal> {
+        let mut proposal = self
+            .proposals
+            .get(proposal_id as u32)
+            .cloned()
+            .expect("Предложение не найдено");
+        require!(
+            proposal.status == ProposalStatus::Active,
+            "Голосование закрыто"
+        );
+        if proposal.status_at(now_secs()) == ProposalStatus::Expired {
+            proposal.status = ProposalStatus::Expired;
+            self.proposals.replace(proposal_id as u32, proposal);
+            env::log_str(&format!("Предложение #{} истекло", proposal_id));
+            return None;
+        }
+        Some(proposal)
+    }
+}
+
+#[near]
+impl TlsOracle {
+    /// Включить governance: передать управление нотариусами совету (однократно, owner)
+    pub fn init_governance(&mut self, council: Vec<CouncilMember>, policy: GovernancePolicy) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Только owner может включить governance"
+        );
+        require!(self.governance.is_none(), "Governance уже включён");
+        require!(!council.is_empty(), "Совет не может быть пустым");
+        assert_valid_policy(&policy);
+
+        for member in council {
+            require!(member.weight > 0, "Вес члена совета > 0");
+            self.council.insert(member.account_id, member.weight);
+        }
+        self.governance = Some(policy);
+        env::log_str("Governance включён");
+    }
+
+    /// Создать предложение (член совета). Возвращает id
+    pub fn propose(&mut self, kind: ProposalKind, description: String) -> u64 {
+        let policy = self.governance.clone().expect("Governance не включён");
+        let proposer = env::predecessor_account_id();
+        require!(self.council.contains_key(&proposer), "Только член совета");
+        require!(description.len() <= 1024, "description макс 1KB");
+
+        match &kind {
+            ProposalKind::AddNotary { raw_pubkey, .. }
+            | ProposalKind::UpdateNotary { raw_pubkey, .. } => {
+                if let Some(pk) = raw_pubkey {
+                    require!(pk.len() == 128, "raw_pubkey: 128 hex chars (64 bytes x||y)");
+                    require!(
+                        pk.chars().all(|c| c.is_ascii_hexdigit()),
+                        "raw_pubkey: невалидный hex"
+                    );
+                }
+            }
+            ProposalKind::SetPolicy { policy } => assert_valid_policy(policy),
+            ProposalKind::FunctionCall { method_name, args } => {
+                require!(
+                    OWNER_METHODS.contains(&method_name.as_str()),
+                    "FunctionCall: только owner-методы контракта"
+                );
+                require!(args.0.len() <= MAX_CALL_ARGS_LEN, "args макс 16KB");
+            }
+            ProposalKind::SetCouncilMember {
+                weight: Some(weight),
+                ..
+            } => require!(*weight > 0, "Вес члена совета > 0 (None — исключить)"),
+            _ => {}
+        }
+
+        let total_weight = self.council_weight();
+        let quorum_weight = (total_weight * policy.quorum_bps as u64).div_ceil(10_000);
+        let id = self.proposals.len() as u64;
+        let created_at = now_secs();
+        self.proposals.push(Proposal {
+            id,
+            proposer,
+            kind,
+            description,
+            status: ProposalStatus::Active,
+            quorum_weight,
+            total_weight,
+            votes_for: 0,
+            votes_against: 0,
+            votes: Vec::new(),
+            created_at,
+            expires_at: created_at + policy.voting_period_secs,
+        });
+        env::log_str(&format!("Предложение #{} создано", id));
+        id
+    }
+
+    /// Голос члена совета. Исполняет предложение при достижении кворума
+    ///
+    /// Голос за истёкшее предложение не учитывается — оно закрывается как Expired.
+    pub fn vote(&mut self, proposal_id: u64, approve: bool) -> ProposalStatus {
+        let voter = env::predecessor_account_id();
+        let weight = *self.council.get(&voter).expect("Только член совета");
+
+        let Some(mut proposal) = self.load_active_proposal(proposal_id) else {
+            // Не паникуем — статус Expired должен сохраниться
+            return ProposalStatus::Expired;
+        };
+        require!(
+            proposal.votes.iter().all(|v| v.account_id != voter),
+            "Уже проголосовали"
+        );
+        if approve {
+            proposal.votes_for += weight as u64;
+        } else {
+            proposal.votes_against += weight as u64;
+        }
+        proposal.votes.push(Vote {
+            account_id: voter,
+            approve,
+            weight,
+        });
+
+        if proposal.votes_for >= proposal.quorum_weight {
+            match self.execute_proposal(&proposal) {
+                Ok(()) => {
+                    proposal.status = ProposalStatus::Executed;
+                    env::log_str(&format!("Предложение #{} исполнено", proposal_id));
+                }
+                Err(reason) => {
+                    proposal.status = ProposalStatus::Rejected;
+                    env::log_str(&format!(
+                        "Предложение #{} не исполнено: {}",
+                        proposal_id, reason
+                    ));
+                }
+            }
+        } else if proposal.total_weight.saturating_sub(proposal.votes_against)
+            < proposal.quorum_weight
+        {
+            // Кворум "за" уже недостижим. Вес совета мог вырасти после создания —
+            // голоса "против" способны превысить снимок total_weight
+            proposal.status = ProposalStatus::Rejected;
+            env::log_str(&format!("Предложение #{} отклонено", proposal_id));
+        }
+
+        let status = proposal.status;
+        self.proposals.replace(proposal_id as u32, proposal);
+        status
+    }
+
+    /// Записать Expired для истёкшего предложения (любой аккаунт)
+    pub fn close_proposal(&mut self, proposal_id: u64) -> ProposalStatus {
+        require!(
+            self.load_active_proposal(proposal_id).is_none(),
+            "Голосование ещё идёт"
+        );
+        ProposalStatus::Expired
+    }
+
+    /// Callback: результат owner-метода предложения FunctionCall
+    #[private]
+    pub fn on_proposal_call(
+        &mut self,
+        proposal_id: u64,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            if let Some(proposal) = self.proposals.get_mut(proposal_id as u32) {
+                proposal.status = ProposalStatus::Failed;
+            }
+            env::log_str(&format!("Предложение #{}: вызов метода упал", proposal_id));
+        }
+    }
+
+    // ── View методы ──────────────────────────────────────────
+
+    pub fn get_governance_policy(&self) -> Option<GovernancePolicy> {
+        self.governance.clone()
+    }
+
+    pub fn get_council(&self) -> Vec<CouncilMember> {
+        self.council
+            .iter()
+            .map(|(account_id, weight)| CouncilMember {
+                account_id: account_id.clone(),
+                weight: *weight,
+            })
+            .collect()
+    }
+
+    pub fn get_proposal(&self, id: u64) -> Option<Proposal> {
+        self.proposals
+            .get(id as u32)
+            .cloned()
+            .map(with_current_status)
+    }
+
+    /// История предложений, новые первыми
+    pub fn get_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Proposal> {
+        let total = self.proposals.len() as u64;
+        if total == 0 {
+            return vec![];
+        }
+        let limit = limit.unwrap_or(20).min(100);
+        let from = from_index.unwrap_or(total - 1).min(total - 1);
+
+        (0..=from)
+            .rev()
+            .take(limit as usize)
+            .filter_map(|i| self.proposals.get(i as u32).cloned())
+            .map(with_current_status)
+            .collect()
+    }
+}
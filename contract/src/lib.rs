@@ -1,12 +1,37 @@
 use near_sdk::borsh::BorshSerialize;
-use near_sdk::store::{IterableMap, LookupMap, LookupSet};
-use near_sdk::{env, near, require, AccountId, BorshStorageKey, PanicOnDefault};
+use near_sdk::json_types::U128;
+use near_sdk::store::{IterableMap, LookupMap, LookupSet, TreeMap, Vector};
+use near_sdk::{env, near, require, AccountId, BorshStorageKey, PanicOnDefault, PromiseOrValue};
 
-mod bn254_types;
-mod groth16;
+use groth16_verifier::snarkjs::{SnarkjsPlonkProof, SnarkjsPlonkVerificationKey};
+use groth16_verifier::{bn254_types, groth16, plonk, ProofSystem, FFLONK_UNSUPPORTED};
+
+mod disputes;
+mod external_verifier;
+mod fees;
+mod ft_payments;
+mod governance;
+mod notary_stats;
+mod soulbound;
+#[cfg(test)]
+mod tests;
+mod tlsn;
+mod upgrade;
+mod url_index;
+#[cfg(feature = "linked-verifier")]
 mod vk_data;
 
 use bn254_types::{G1Point, G2Point, Scalar};
+use disputes::{AttestationView, Dispute, DisputeConfig};
+use external_verifier::VerifierConfig;
+use fees::{Fee, FeeConfig};
+use ft_payments::FtHolder;
+use governance::{GovernancePolicy, Proposal};
+use notary_stats::NotaryStats;
+use tlsn::{Direction, TlsnPresentation};
+use upgrade::StagedUpgrade;
+
+pub use tls_oracle_types::{Attestation, NotaryInfo, SubmitAttestationArgs};
 
 // ── Ключи хранилища ─────────────────────────────────────────
 
@@ -14,55 +39,144 @@ use bn254_types::{G1Point, G2Point, Scalar};
 #[borsh(crate = "near_sdk::borsh")]
 enum StorageKey {
     TrustedNotaries,
+    // Префиксы Attestation без версии: записи V1 остаются под ними
+    // (legacy_attestations), новые под этими ключами не создаются
+    #[allow(dead_code)]
     Attestations,
     AttestationsBySource,
     UsedCommitments,
-    // v2: новые префиксы для миграции (старые данные с Borsh v1 не десериализуются)
+    // Префиксы развёртываний V1 после прежнего сброса состояния (testnet):
+    // их коллекции читаются из состояния V1 (migrate_state_v1), новые
+    // под ними не создаются
+    #[allow(dead_code)]
     TrustedNotariesV2,
+    #[allow(dead_code)]
     AttestationsV2,
+    #[allow(dead_code)]
     AttestationsBySourceV2,
+    #[allow(dead_code)]
     UsedCommitmentsV2,
+    SoulboundTokens,
+    SoulboundByOwner,
+    DomainFees,
+    NotaryRewards,
+    NotaryRewardAccounts,
+    FtTokens,
+    FtBalances,
+    Council,
+    Proposals,
+    NotaryStats,
+    NotaryDomains,
+    ChallengePeriods,
+    ChallengeEnds,
+    Disputes,
+    OpenDisputes,
+    Invalidated,
+    // Записи VersionedAttestation; записи V1 без версии — в legacy_attestations
+    AttestationsVersioned,
+    AttestationsByUrl,
+    FtDomainFees,
+    FtSubscriptionPrices,
+    Subscriptions,
 }
 
 // ── Модели данных ────────────────────────────────────────────
 
-/// Аттестация — ZK-верифицированные данные с веб-сайта
-#[near(serializers = [borsh, json])]
+/// Схема Attestation до request_hash
+#[near(serializers = [borsh])]
 #[derive(Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Attestation {
+pub struct AttestationV1 {
     pub id: u64,
     pub source_url: String,
     pub server_name: String,
     pub timestamp: u64,
     pub response_data: String,
-    /// Poseidon commitment данных (public signal)
     pub data_commitment: String,
-    /// Poseidon hash server_name (public signal)
     pub server_name_hash: String,
-    /// Poseidon hash notary pubkey (public signal)
     pub notary_pubkey_hash: String,
     pub submitter: AccountId,
     pub block_height: u64,
-    /// Подпись нотариуса верифицирована on-chain через ecrecover
-    #[serde(default)]
     pub sig_verified: bool,
 }
 
-/// Информация о доверенном нотариусе
-#[near(serializers = [borsh, json])]
+/// Аттестация в хранилище с версией схемы
+///
+/// Новое Borsh поле: текущая структура переименовывается в `AttestationV<n>`,
+/// добавляется вариант с новой `Attestation`, а `into_current` конвертирует
+/// старые варианты при чтении. Хранилище при чтении не меняется: новые записи
+/// пишутся последней версией, старые остаются как есть — сброс storage prefix
+/// не нужен.
+///
+/// Записи V1 (до версий) лежат без тега варианта под старым префиксом
+/// (`legacy_attestations`) и читаются как `V1`; `migrate_attestations`
+/// переписывает их в текущую версию батчами.
+#[near(serializers = [borsh])]
 #[derive(Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct NotaryInfo {
-    /// Poseidon hash secp256k1 pubkey
-    pub pubkey_hash: String,
-    /// Raw uncompressed secp256k1 pubkey x||y (hex, 128 chars = 64 bytes)
-    /// Нужен для ecrecover верификации подписи
-    pub raw_pubkey: Option<String>,
-    pub name: String,
-    pub url: String,
-    pub added_by: AccountId,
-    pub added_at: u64,
+pub enum VersionedAttestation {
+    V1(AttestationV1),
+    V2(Attestation),
+}
+
+impl VersionedAttestation {
+    /// Аттестация в текущей схеме
+    pub fn into_current(self) -> Attestation {
+        match self {
+            Self::V1(a) => Attestation {
+                id: a.id,
+                source_url: a.source_url,
+                server_name: a.server_name,
+                timestamp: a.timestamp,
+                response_data: a.response_data,
+                data_commitment: a.data_commitment,
+                server_name_hash: a.server_name_hash,
+                notary_pubkey_hash: a.notary_pubkey_hash,
+                submitter: a.submitter,
+                block_height: a.block_height,
+                sig_verified: a.sig_verified,
+                request_hash: None,
+            },
+            Self::V2(attestation) => attestation,
+        }
+    }
+}
+
+impl From<Attestation> for VersionedAttestation {
+    fn from(attestation: Attestation) -> Self {
+        Self::V2(attestation)
+    }
+}
+
+/// Состояние контракта V1 (до комиссий, governance, disputes и upgrade) —
+/// только Borsh layout для `migrate_state_v1`
+#[near(serializers = [borsh])]
+pub struct TlsOracleV1 {
+    owner: AccountId,
+    trusted_notaries: IterableMap<String, NotaryInfo>,
+    attestations: IterableMap<u64, AttestationV1>,
+    attestations_by_source: LookupMap<String, Vec<u64>>,
+    used_commitments: LookupSet<String>,
+    attestation_count: u64,
+}
+
+/// ZK proof аттестации в одной из поддерживаемых систем
+enum ZkProof {
+    Groth16(Box<groth16::Proof>),
+    Plonk(Box<plonk::Proof>),
+}
+
+/// Распарсенный ZK submit — общий вход для JSON и Borsh методов
+struct ZkSubmission {
+    source_url: String,
+    server_name: String,
+    timestamp: u64,
+    response_data: String,
+    proof: ZkProof,
+    public_inputs: [Scalar; 4],
+    /// Decimal представление public signals (ключи хранилища)
+    public_signals: [String; 4],
+    notary_signature: Vec<u8>,
+    notary_sig_v: u8,
+    request_hash: Option<[u8; 32]>,
 }
 
 // ── Константы ─────────────────────────────────────────────────
@@ -71,49 +185,156 @@ pub struct NotaryInfo {
 const MAX_ATTESTATION_AGE_SECS: u64 = 600;
 /// Допуск на будущее время: 1 минута (в секундах)
 const FUTURE_TOLERANCE_SECS: u64 = 60;
+/// Тег версии сообщения, которое подписывает нотариус (см. build_sign_message)
+const SIGN_MESSAGE_TAG: &[u8] = b"tls-oracle/attestation/v2";
 
 // ── Вспомогательные функции ──────────────────────────────────
 
-/// Hex string → bytes
-fn hex_to_bytes(hex_str: &str) -> Vec<u8> {
+/// Hex string → bytes (None — нечётная длина или не hex)
+fn parse_hex(hex_str: &str) -> Option<Vec<u8>> {
+    if !hex_str.len().is_multiple_of(2) || !hex_str.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
     (0..hex_str.len())
         .step_by(2)
-        .map(|i| u8::from_str_radix(&hex_str[i..i + 2], 16).unwrap())
+        .map(|i| u8::from_str_radix(&hex_str[i..i + 2], 16).ok())
         .collect()
 }
 
+/// Hex string → bytes
+fn hex_to_bytes(hex_str: &str) -> Vec<u8> {
+    parse_hex(hex_str).unwrap_or_else(|| env::panic_str("Невалидная hex строка"))
+}
+
+/// Bytes → hex string
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Проверяет что timestamp аттестации в допустимом окне относительно блока
+fn assert_fresh_timestamp(timestamp: u64) {
+    let block_ts_secs = env::block_timestamp() / 1_000_000_000;
+    require!(
+        timestamp <= block_ts_secs + FUTURE_TOLERANCE_SECS,
+        "Timestamp аттестации в будущем"
+    );
+    require!(
+        timestamp + MAX_ATTESTATION_AGE_SECS >= block_ts_secs,
+        "Аттестация устарела (макс 10 минут)"
+    );
+}
+
 /// Формирует message hash для верификации подписи нотариуса.
-/// Формат: SHA-256(source_url || 0x00 || server_name || 0x00 || timestamp_be8 || 0x00 || response_data)
+///
+/// Формат v2: SHA-256(SIGN_MESSAGE_TAG || len_be4(source_url) || source_url
+/// || len_be4(server_name) || server_name || timestamp_be8
+/// || len_be4(response_data) || response_data || 0x00 | 0x01 || request_hash).
+/// Длины однозначно разделяют поля (0x00 внутри response_data не сдвигает
+/// границы), тег версии отделяет формат от других подписей нотариуса.
 fn build_sign_message(
     source_url: &str,
     server_name: &str,
     timestamp: u64,
     response_data: &str,
+    request_hash: Option<&[u8; 32]>,
 ) -> Vec<u8> {
     let mut data = Vec::new();
-    data.extend_from_slice(source_url.as_bytes());
-    data.push(0x00);
-    data.extend_from_slice(server_name.as_bytes());
-    data.push(0x00);
+    data.extend_from_slice(SIGN_MESSAGE_TAG);
+    for field in [source_url, server_name] {
+        data.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
     data.extend_from_slice(&timestamp.to_be_bytes());
-    data.push(0x00);
+    data.extend_from_slice(&(response_data.len() as u32).to_be_bytes());
     data.extend_from_slice(response_data.as_bytes());
+    match request_hash {
+        Some(hash) => {
+            data.push(0x01);
+            data.extend_from_slice(hash);
+        }
+        None => data.push(0x00),
+    }
     env::sha256(&data)
 }
 
 // ── Контракт ─────────────────────────────────────────────────
 
-#[near(contract_state)]
+#[near(
+    contract_state,
+    contract_metadata(
+        standard(standard = "nep330", version = "1.1.0"),
+        standard(standard = "nep297", version = "1.0.0"),
+        standard(standard = "nep171", version = "1.0.0"),
+        standard(standard = "nep177", version = "2.0.0"),
+        standard(standard = "nep181", version = "1.0.0"),
+        standard(standard = "tls-oracle-circuit", version = "attestation-v1")
+    )
+)]
 #[derive(PanicOnDefault)]
 pub struct TlsOracle {
     owner: AccountId,
     /// Ключ: Poseidon hash notary pubkey (decimal string)
     trusted_notaries: IterableMap<String, NotaryInfo>,
-    attestations: IterableMap<u64, Attestation>,
+    attestations: IterableMap<u64, VersionedAttestation>,
+    /// Аттестации V1 под префиксом старого состояния (None — не осталось)
+    legacy_attestations: Option<IterableMap<u64, AttestationV1>>,
     attestations_by_source: LookupMap<String, Vec<u64>>,
+    /// (sha256(нормализованный source_url), timestamp, id) — записи одного URL
+    /// идут подряд по возрастанию timestamp
+    attestations_by_url: TreeMap<([u8; 32], u64, u64), ()>,
     /// Poseidon data commitments для защиты от replay-атак
     used_commitments: LookupSet<String>,
     attestation_count: u64,
+    /// Внешний groth16-verifier контракт (None — верификация внутри контракта)
+    verifier: Option<VerifierConfig>,
+    /// PLONK verification key (universal setup) для встроенной верификации
+    plonk_vk: Option<plonk::VerificationKey>,
+    /// Выпускать soulbound NFT (NEP-171) на submitter при сохранении аттестации
+    soulbound_mint: bool,
+    /// token_id (= id аттестации) → владелец
+    sbt_tokens: IterableMap<u64, AccountId>,
+    sbt_by_owner: LookupMap<AccountId, Vec<u64>>,
+    /// Комиссии за аттестации (None — бесплатно)
+    fee_config: Option<FeeConfig>,
+    /// Ключ: server_name
+    domain_fees: LookupMap<String, U128>,
+    /// Ключ: Poseidon hash notary pubkey → claimable yoctoNEAR
+    notary_rewards: LookupMap<String, u128>,
+    notary_reward_accounts: LookupMap<String, AccountId>,
+    treasury_balance: u128,
+    /// Whitelist NEP-141 токенов → комиссия за аттестацию в этом токене
+    ft_tokens: IterableMap<AccountId, U128>,
+    /// (держатель, токен) → баланс: предоплаты, bounties, награды, treasury
+    ft_balances: LookupMap<(FtHolder, AccountId), u128>,
+    /// (server_name, токен) → комиссия домена в токене
+    ft_domain_fees: LookupMap<(String, AccountId), U128>,
+    /// Токен → цена подписки за SUBSCRIPTION_PERIOD
+    ft_subscription_prices: LookupMap<AccountId, U128>,
+    /// Аккаунт → конец подписки (UNIX, секунды)
+    subscriptions: LookupMap<AccountId, u64>,
+    /// Политика голосования (None — нотариусами управляет owner)
+    governance: Option<GovernancePolicy>,
+    /// Совет: аккаунт → вес голоса
+    council: IterableMap<AccountId, u32>,
+    /// История предложений (id = индекс)
+    proposals: Vector<Proposal>,
+    /// Ключ: Poseidon hash notary pubkey; переживает удаление нотариуса
+    notary_stats: LookupMap<String, NotaryStats>,
+    /// (pubkey_hash, server_name) — для подсчёта различных доменов
+    notary_domains: LookupSet<(String, String)>,
+    /// Залог и arbiter для disputes (None — disputes выключены)
+    dispute_config: Option<DisputeConfig>,
+    /// server_name → окно оспаривания, секунды
+    challenge_periods: LookupMap<String, u64>,
+    /// id аттестации → конец окна оспаривания (UNIX, секунды)
+    challenge_ends: LookupMap<u64, u64>,
+    disputes: Vector<Dispute>,
+    /// id аттестации → id открытого dispute
+    open_disputes: LookupMap<u64, u64>,
+    /// Аттестации, признанные недействительными
+    invalidated: LookupSet<u64>,
+    /// Загруженный, но ещё не развёрнутый wasm (сам код — в STAGED_CODE_KEY)
+    staged_upgrade: Option<StagedUpgrade>,
 }
 
 // ── Реализация ───────────────────────────────────────────────
@@ -125,26 +346,84 @@ impl TlsOracle {
         Self {
             owner,
             trusted_notaries: IterableMap::new(StorageKey::TrustedNotaries),
-            attestations: IterableMap::new(StorageKey::Attestations),
+            attestations: IterableMap::new(StorageKey::AttestationsVersioned),
+            legacy_attestations: None,
             attestations_by_source: LookupMap::new(StorageKey::AttestationsBySource),
+            attestations_by_url: TreeMap::new(StorageKey::AttestationsByUrl),
             used_commitments: LookupSet::new(StorageKey::UsedCommitments),
             attestation_count: 0,
+            verifier: None,
+            plonk_vk: None,
+            soulbound_mint: false,
+            sbt_tokens: IterableMap::new(StorageKey::SoulboundTokens),
+            sbt_by_owner: LookupMap::new(StorageKey::SoulboundByOwner),
+            fee_config: None,
+            domain_fees: LookupMap::new(StorageKey::DomainFees),
+            notary_rewards: LookupMap::new(StorageKey::NotaryRewards),
+            notary_reward_accounts: LookupMap::new(StorageKey::NotaryRewardAccounts),
+            treasury_balance: 0,
+            ft_tokens: IterableMap::new(StorageKey::FtTokens),
+            ft_balances: LookupMap::new(StorageKey::FtBalances),
+            ft_domain_fees: LookupMap::new(StorageKey::FtDomainFees),
+            ft_subscription_prices: LookupMap::new(StorageKey::FtSubscriptionPrices),
+            subscriptions: LookupMap::new(StorageKey::Subscriptions),
+            governance: None,
+            council: IterableMap::new(StorageKey::Council),
+            proposals: Vector::new(StorageKey::Proposals),
+            notary_stats: LookupMap::new(StorageKey::NotaryStats),
+            notary_domains: LookupSet::new(StorageKey::NotaryDomains),
+            dispute_config: None,
+            challenge_periods: LookupMap::new(StorageKey::ChallengePeriods),
+            challenge_ends: LookupMap::new(StorageKey::ChallengeEnds),
+            disputes: Vector::new(StorageKey::Disputes),
+            open_disputes: LookupMap::new(StorageKey::OpenDisputes),
+            invalidated: LookupSet::new(StorageKey::Invalidated),
+            staged_upgrade: None,
         }
     }
 
-    /// Миграция: сброс состояния при изменении Borsh схемы (testnet only)
-    /// Использует V2 storage keys чтобы не конфликтовать со старыми данными
+    /// Миграция состояния V1 без потери данных (после deploy нового кода поверх V1)
+    ///
+    /// Нотариусы, индекс по домену, commitments и счётчик переносятся как есть,
+    /// новые поля получают значения по умолчанию (комиссии, governance, disputes
+    /// выключены). Аттестации V1 остаются под своим префиксом и читаются как V1;
+    /// в индекс URL они попадают через `migrate_attestations`.
     #[private]
     #[init(ignore_state)]
-    pub fn migrate(owner: AccountId) -> Self {
-        Self {
-            owner,
-            trusted_notaries: IterableMap::new(StorageKey::TrustedNotariesV2),
-            attestations: IterableMap::new(StorageKey::AttestationsV2),
-            attestations_by_source: LookupMap::new(StorageKey::AttestationsBySourceV2),
-            used_commitments: LookupSet::new(StorageKey::UsedCommitmentsV2),
-            attestation_count: 0,
+    pub fn migrate_state_v1() -> Self {
+        let old: TlsOracleV1 = env::state_read().expect("Нет состояния V1");
+        let mut state = Self::new(old.owner);
+        state.trusted_notaries = old.trusted_notaries;
+        state.legacy_attestations = (!old.attestations.is_empty()).then_some(old.attestations);
+        state.attestations_by_source = old.attestations_by_source;
+        state.used_commitments = old.used_commitments;
+        state.attestation_count = old.attestation_count;
+        state
+    }
+
+    /// Переписать до `limit` аттестаций V1 в текущую версию и добавить их в индекс
+    /// URL (любой аккаунт). Возвращает, сколько записей V1 осталось
+    pub fn migrate_attestations(&mut self, limit: u32) -> u32 {
+        let Some(legacy) = self.legacy_attestations.as_mut() else {
+            return 0;
+        };
+        let ids: Vec<u64> = legacy.keys().take(limit as usize).copied().collect();
+        let moved: Vec<Attestation> = ids
+            .iter()
+            .filter_map(|id| legacy.remove(id))
+            .map(|a| VersionedAttestation::V1(a).into_current())
+            .collect();
+        let remaining = legacy.len();
+        if remaining == 0 {
+            self.legacy_attestations = None;
+        }
+
+        for attestation in moved {
+            self.index_attestation_url(&attestation);
+            self.attestations.insert(attestation.id, attestation.into());
         }
+        env::log_str(&format!("Аттестаций V1 осталось: {}", remaining));
+        remaining
     }
 
     // ── Управление нотариусами (admin) ───────────────────────
@@ -162,6 +441,7 @@ impl TlsOracle {
             env::predecessor_account_id() == self.owner,
             "Только owner может добавлять нотариусов"
         );
+        self.assert_owner_manages_notaries();
 
         if let Some(ref pk) = raw_pubkey {
             require!(
@@ -174,29 +454,7 @@ impl TlsOracle {
             );
         }
 
-        // Если нотариус уже есть — обновляем (позволяет добавить raw_pubkey)
-        if self.trusted_notaries.contains_key(&pubkey_hash) {
-            let mut info = self.trusted_notaries.get(&pubkey_hash).unwrap().clone();
-            if raw_pubkey.is_some() {
-                info.raw_pubkey = raw_pubkey;
-            }
-            info.name = name;
-            info.url = url;
-            self.trusted_notaries.insert(pubkey_hash.clone(), info);
-            env::log_str(&format!("Нотариус обновлён: {}", pubkey_hash));
-            return;
-        }
-
-        let info = NotaryInfo {
-            pubkey_hash: pubkey_hash.clone(),
-            raw_pubkey,
-            name,
-            url,
-            added_by: env::predecessor_account_id(),
-            added_at: env::block_height(),
-        };
-        self.trusted_notaries.insert(pubkey_hash.clone(), info);
-        env::log_str(&format!("Нотариус добавлен: {}", pubkey_hash));
+        self.upsert_notary(pubkey_hash, name, url, raw_pubkey, env::predecessor_account_id());
     }
 
     pub fn remove_notary(&mut self, pubkey_hash: String) {
@@ -204,171 +462,313 @@ impl TlsOracle {
             env::predecessor_account_id() == self.owner,
             "Только owner может удалять нотариусов"
         );
-        require!(
-            self.trusted_notaries.remove(&pubkey_hash).is_some(),
-            "Нотариус не найден"
-        );
-        env::log_str(&format!("Нотариус удалён: {}", pubkey_hash));
+        self.assert_owner_manages_notaries();
+        self.delete_notary(&pubkey_hash);
     }
 
     pub fn set_owner(&mut self, new_owner: AccountId) {
-        require!(
-            env::predecessor_account_id() == self.owner,
-            "Только owner"
-        );
+        self.assert_owner("Только owner");
         self.owner = new_owner;
     }
 
+    /// Установить PLONK verification key (snarkjs verification_key.json, protocol = "plonk")
+    ///
+    /// Circuit на universal setup (Powers of Tau) — смена circuit не требует новой церемонии.
+    pub fn set_plonk_vk(&mut self, vk: Option<SnarkjsPlonkVerificationKey>) {
+        self.assert_owner("Только owner может менять PLONK verification key");
+        if let Some(vk) = &vk {
+            require!(vk.n_public == 4, "PLONK vk: ожидается 4 public inputs");
+        }
+        self.plonk_vk = vk.map(|vk| vk.to_vk());
+    }
+
     // ── Отправка аттестации с ZK-доказательством + подпись ────
 
-    /// Submit аттестации с Groth16 ZK proof + подпись нотариуса
+    /// Submit аттестации с ZK proof (Groth16 или PLONK) + подпись нотариуса
+    ///
+    /// proof_system по умолчанию groth16 (proof_a/proof_b/proof_c);
+    /// для plonk — proof.json snarkjs в plonk_proof; fflonk отклоняется.
     ///
     /// Верификация:
-    /// 1. Groth16 ZK proof (data integrity через Poseidon commitments)
+    /// 1. ZK proof (data integrity через Poseidon commitments)
     /// 2. secp256k1 ECDSA подпись нотариуса (ecrecover)
     #[payable]
+    #[allow(clippy::too_many_arguments)]
     pub fn submit_attestation(
         &mut self,
         source_url: String,
         server_name: String,
         timestamp: u64,
         response_data: String,
+        proof_system: Option<ProofSystem>,
         // Groth16 proof
-        proof_a: [String; 2],
-        proof_b: [[String; 2]; 2],
-        proof_c: [String; 2],
+        proof_a: Option<[String; 2]>,
+        proof_b: Option<[[String; 2]; 2]>,
+        proof_c: Option<[String; 2]>,
+        // PLONK proof
+        plonk_proof: Option<SnarkjsPlonkProof>,
         public_signals: [String; 4],
         // Подпись нотариуса (secp256k1 ECDSA)
         notary_signature: String,
         notary_sig_v: u8,
-    ) -> u64 {
-        require!(response_data.len() <= 4096, "response_data макс 4KB");
-        require!(source_url.len() <= 2048, "source_url макс 2KB");
-
-        // Проверка timestamp
-        let block_ts_secs = env::block_timestamp() / 1_000_000_000;
-        require!(
-            timestamp <= block_ts_secs + FUTURE_TOLERANCE_SECS,
-            "Timestamp аттестации в будущем"
-        );
-        require!(
-            timestamp + MAX_ATTESTATION_AGE_SECS >= block_ts_secs,
-            "Аттестация устарела (макс 10 минут)"
-        );
-
-        // Проверяем что timestamp в public_signals совпадает
+        // SHA-256 описания запроса с телом (hex), если он был
+        request_hash: Option<String>,
+    ) -> PromiseOrValue<u64> {
         require!(
-            public_signals[2] == timestamp.to_string(),
-            "Timestamp в public_signals не совпадает"
+            notary_signature.len() == 128,
+            "notary_signature: 128 hex chars (64 bytes r||s)"
         );
+        let request_hash = request_hash.map(|h| {
+            let hash = parse_hex(&h).and_then(|bytes| <[u8; 32]>::try_from(bytes).ok());
+            require!(hash.is_some(), "request_hash: 64 hex chars (32 bytes)");
+            hash.unwrap_or_default()
+        });
 
-        // Проверяем что нотариус доверенный (по Poseidon hash pubkey)
-        let notary_pubkey_hash = &public_signals[3];
-        let notary_info = self
-            .trusted_notaries
-            .get(notary_pubkey_hash)
-            .expect("Нотариус не в списке доверенных")
-            .clone();
+        let proof = match proof_system.unwrap_or_default() {
+            ProofSystem::Groth16 => {
+                let (Some(proof_a), Some(proof_b), Some(proof_c)) = (proof_a, proof_b, proof_c)
+                else {
+                    env::panic_str("groth16: нужны proof_a, proof_b, proof_c")
+                };
+                ZkProof::Groth16(Box::new(groth16::Proof {
+                    a: G1Point::from_decimal(&proof_a[0], &proof_a[1]),
+                    b: G2Point {
+                        x_im: bn254_types::decimal_to_le_bytes(&proof_b[0][1]),
+                        x_re: bn254_types::decimal_to_le_bytes(&proof_b[0][0]),
+                        y_im: bn254_types::decimal_to_le_bytes(&proof_b[1][1]),
+                        y_re: bn254_types::decimal_to_le_bytes(&proof_b[1][0]),
+                    },
+                    c: G1Point::from_decimal(&proof_c[0], &proof_c[1]),
+                }))
+            }
+            ProofSystem::Plonk => ZkProof::Plonk(Box::new(
+                plonk_proof
+                    .expect("plonk: нужен plonk_proof")
+                    .to_proof(),
+            )),
+            ProofSystem::Fflonk => env::panic_str(FFLONK_UNSUPPORTED),
+        };
 
-        // Replay-защита по data commitment
-        let data_commitment = &public_signals[0];
-        require!(
-            !self.used_commitments.contains(data_commitment),
-            "Эта аттестация уже была отправлена (replay)"
-        );
+        // Парсим public signals → Scalar
+        let public_inputs = [
+            Scalar::from_decimal(&public_signals[0]),
+            Scalar::from_decimal(&public_signals[1]),
+            Scalar::from_decimal(&public_signals[2]),
+            Scalar::from_decimal(&public_signals[3]),
+        ];
 
-        // ── Верификация подписи нотариуса (ecrecover) ────────
-        let raw_pk = notary_info
-            .raw_pubkey
-            .as_ref()
-            .expect("raw_pubkey не установлен — обновите нотариуса через add_notary");
+        self.submit_zk(ZkSubmission {
+            source_url,
+            server_name,
+            timestamp,
+            response_data,
+            proof,
+            public_inputs,
+            public_signals,
+            notary_signature: hex_to_bytes(&notary_signature),
+            notary_sig_v,
+            request_hash,
+        })
+    }
 
-        require!(
-            notary_signature.len() == 128,
-            "notary_signature: 128 hex chars (64 bytes r||s)"
-        );
-        require!(notary_sig_v <= 1, "notary_sig_v: 0 или 1");
+    /// Submit аттестации с бинарными аргументами (Borsh)
+    ///
+    /// То же, что `submit_attestation`, но proof points и public signals
+    /// передаются готовыми 32-байтными LE значениями, подпись — сырыми байтами.
+    /// Нет JSON-парсинга и base-10 конвертации 16 элементов поля.
+    #[payable]
+    pub fn submit_attestation_borsh(
+        &mut self,
+        #[serializer(borsh)] args: SubmitAttestationArgs,
+    ) -> PromiseOrValue<u64> {
+        let proof = groth16::Proof {
+            a: G1Point {
+                x: args.proof_a[0],
+                y: args.proof_a[1],
+            },
+            b: G2Point {
+                x_im: args.proof_b[0][1],
+                x_re: args.proof_b[0][0],
+                y_im: args.proof_b[1][1],
+                y_re: args.proof_b[1][0],
+            },
+            c: G1Point {
+                x: args.proof_c[0],
+                y: args.proof_c[1],
+            },
+        };
 
-        // Воспроизводим message hash (SHA-256)
-        let message_hash = build_sign_message(&source_url, &server_name, timestamp, &response_data);
+        // Decimal строки нужны как ключи хранилища (нотариусы, commitments)
+        let public_signals =
+            args.public_signals.map(|s| bn254_types::le_bytes_to_decimal(&s));
 
-        // Декодируем подпись
-        let sig_bytes = hex_to_bytes(&notary_signature);
+        self.submit_zk(ZkSubmission {
+            source_url: args.source_url,
+            server_name: args.server_name,
+            timestamp: args.timestamp,
+            response_data: args.response_data,
+            proof: ZkProof::Groth16(Box::new(proof)),
+            public_inputs: args.public_signals.map(Scalar),
+            public_signals,
+            notary_signature: args.notary_signature.to_vec(),
+            notary_sig_v: args.notary_sig_v,
+            request_hash: args.request_hash,
+        })
+    }
 
-        // ecrecover: восстанавливаем pubkey из подписи
-        let recovered = env::ecrecover(&message_hash, &sig_bytes, notary_sig_v, true)
-            .expect("ecrecover: невалидная подпись");
+    /// Submit аттестации по подписанному tlsn Attestation (MPC-TLS сессия)
+    ///
+    /// Верификация:
+    /// 1. secp256k1 подпись нотариуса над BCS(Header) (ecrecover)
+    /// 2. Merkle root хешей полей Body == Header.root
+    /// 3. Подпись того же нотариуса над server identity (root + server_name)
+    /// 4. ConnectionInfo — timestamp аттестации = время TLS-сессии
+    /// 5. PlaintextHash коммитменты раскрытых диапазонов транскрипта
+    /// 6. Заголовок Host в раскрытом запросе, response_data — раскрытый диапазон ответа целиком
+    /// 7. source_url = https://{server_name}{target строки запроса}
+    ///    (секретные query-значения — tlsn::PLACEHOLDER)
+    #[payable]
+    pub fn submit_tlsn_attestation(
+        &mut self,
+        source_url: String,
+        server_name: String,
+        response_data: String,
+        presentation: TlsnPresentation,
+    ) -> u64 {
+        require!(!response_data.is_empty(), "response_data пуст");
+        require!(response_data.len() <= 4096, "response_data макс 4KB");
+        require!(source_url.len() <= 2048, "source_url макс 2KB");
+        let fee = self.require_fee(&server_name);
 
-        // Сравниваем с зарегистрированным pubkey нотариуса
-        let expected_pubkey = hex_to_bytes(raw_pk);
-        require!(
-            recovered.as_slice() == expected_pubkey.as_slice(),
-            "Подпись нотариуса не совпадает с зарегистрированным ключом"
-        );
+        // ── Подпись нотариуса над header ─────────────────────
+        let header_bytes = &presentation.header.0;
+        let sig_bytes = hex_to_bytes(&presentation.signature);
+        let candidates = tlsn::recover_signer(header_bytes, &sig_bytes);
 
-        env::log_str("Подпись нотариуса верифицирована (ecrecover)");
+        let (notary_info, notary_pubkey) = self
+            .trusted_notaries
+            .values()
+            .find_map(|n| {
+                let pk = hex_to_bytes(n.raw_pubkey.as_ref()?);
+                candidates
+                    .iter()
+                    .any(|c| c.as_slice() == pk.as_slice())
+                    .then(|| (n.clone(), pk))
+            })
+            .expect("Подпись tlsn header не принадлежит доверенному нотариусу");
 
-        // ── Groth16 ZK верификация ──────────────────────────
+        let header = tlsn::parse_header(header_bytes);
 
-        // Парсим Groth16 proof
-        let proof = groth16::Proof {
-            a: G1Point::from_decimal(&proof_a[0], &proof_a[1]),
-            b: G2Point {
-                x_im: bn254_types::decimal_to_le_bytes(&proof_b[0][1]),
-                x_re: bn254_types::decimal_to_le_bytes(&proof_b[0][0]),
-                y_im: bn254_types::decimal_to_le_bytes(&proof_b[1][1]),
-                y_re: bn254_types::decimal_to_le_bytes(&proof_b[1][0]),
-            },
-            c: G1Point::from_decimal(&proof_c[0], &proof_c[1]),
-        };
+        // Replay-защита по Merkle root attestation
+        let data_commitment = bytes_to_hex(&header.root);
+        require!(
+            !self.used_commitments.contains(&data_commitment),
+            "Эта аттестация уже была отправлена (replay)"
+        );
 
-        // Парсим public signals → Scalar
-        let pub_inputs: Vec<Scalar> = public_signals
+        // ── Merkle root полей Body ───────────────────────────
+        let leaves: Vec<[u8; 32]> = presentation
+            .field_hashes
             .iter()
-            .map(|s| Scalar::from_decimal(s))
+            .map(|h| {
+                require!(h.len() == 64, "field_hashes: 64 hex chars");
+                let mut leaf = [0u8; 32];
+                leaf.copy_from_slice(&hex_to_bytes(h));
+                leaf
+            })
             .collect();
+        require!(
+            tlsn::merkle_root(&leaves) == header.root,
+            "Merkle root полей не совпадает с tlsn header"
+        );
 
-        // ZK верификация: Groth16 через alt_bn128
-        let vk = vk_data::get_vk();
+        // ── Server identity ──────────────────────────────────
+        // Нотариус подписывает server_name только после проверки цепочки
+        // сертификатов сервера в MPC-TLS
+        let identity_sig = hex_to_bytes(&presentation.server_identity_signature);
+        let identity_message = tlsn::server_identity_message(&header.root, &server_name);
         require!(
-            groth16::verify(&vk, &proof, &pub_inputs),
-            "ZK proof невалиден"
+            tlsn::recover_signer(&identity_message, &identity_sig)
+                .iter()
+                .any(|c| c.as_slice() == notary_pubkey.as_slice()),
+            "Server identity не подписана нотариусом attestation или не совпадает с server_name"
         );
 
-        // Сохраняем commitment как использованный
-        self.used_commitments.insert(data_commitment.clone());
+        // ── Время сессии ─────────────────────────────────────
+        let connection = tlsn::verify_connection_info(
+            &presentation.connection_info.0,
+            presentation.connection_info_index,
+            &leaves,
+        );
+        let timestamp = connection.time;
+        assert_fresh_timestamp(timestamp);
 
-        // Сохраняем аттестацию
-        let id = self.attestation_count;
-        self.attestation_count += 1;
+        // ── Раскрытые диапазоны транскрипта ──────────────────
+        let mut host_revealed = false;
+        let mut response_revealed = false;
+        for reveal in &presentation.reveals {
+            tlsn::verify_reveal(reveal, &leaves);
+            match reveal.direction {
+                Direction::Sent => {
+                    require!(
+                        reveal.end <= connection.sent_len as u64,
+                        "tlsn reveal: диапазон за пределами отправленных данных"
+                    );
+                    host_revealed |= tlsn::has_host_header(&reveal.data.0, &server_name);
+                }
+                Direction::Received => {
+                    require!(
+                        reveal.end <= connection.received_len as u64,
+                        "tlsn reveal: диапазон за пределами полученных данных"
+                    );
+                    response_revealed |= reveal.data.0 == response_data.as_bytes();
+                }
+            }
+        }
+        require!(host_revealed, "Host запроса не раскрыт или не совпадает с server_name");
+        require!(response_revealed, "response_data не совпадает с раскрытым ответом");
 
-        let attestation = Attestation {
-            id,
-            source_url,
-            server_name: server_name.clone(),
-            timestamp,
-            response_data,
-            data_commitment: public_signals[0].clone(),
-            server_name_hash: public_signals[1].clone(),
-            notary_pubkey_hash: public_signals[3].clone(),
-            submitter: env::predecessor_account_id(),
-            block_height: env::block_height(),
-            sig_verified: true,
-        };
+        // source_url — тот URL, который запрошен в сессии
+        let target = tlsn::request_target(
+            presentation
+                .reveals
+                .iter()
+                .filter(|r| r.direction == Direction::Sent),
+        )
+        .expect("Строка запроса не раскрыта");
+        require!(
+            url_index::normalize_url(&source_url)
+                == url_index::normalize_url(&format!("https://{}{}", server_name, target)),
+            "source_url не совпадает с запросом сессии"
+        );
 
-        self.attestations.insert(id, attestation);
+        env::log_str(&format!(
+            "tlsn attestation v{} {} верифицирована",
+            header.version,
+            bytes_to_hex(&header.id)
+        ));
 
-        // Индекс по домену
-        let mut ids = self
-            .attestations_by_source
-            .get(&server_name)
-            .cloned()
-            .unwrap_or_default();
-        ids.push(id);
-        self.attestations_by_source.insert(server_name.clone(), ids);
+        let id = self.store_attestation(
+            Attestation {
+                id: 0,
+                source_url,
+                server_name: server_name.clone(),
+                timestamp,
+                response_data,
+                data_commitment,
+                server_name_hash: String::new(),
+                notary_pubkey_hash: notary_info.pubkey_hash,
+                submitter: env::predecessor_account_id(),
+                block_height: env::block_height(),
+                sig_verified: true,
+                // Тело запроса раскрыто в отправленном транскрипте
+                request_hash: None,
+            },
+            fee,
+        );
 
         env::log_str(&format!(
-            "Аттестация #{} сохранена: {} ({}) [sig+zk verified]",
+            "Аттестация #{} сохранена: {} ({}) [tlsn verified]",
             id, server_name, env::predecessor_account_id()
         ));
 
@@ -377,15 +777,15 @@ impl TlsOracle {
 
     // ── View методы ──────────────────────────────────────────
 
-    pub fn get_attestation(&self, id: u64) -> Option<Attestation> {
-        self.attestations.get(&id).cloned()
+    pub fn get_attestation(&self, id: u64) -> Option<AttestationView> {
+        self.attestation(id).map(|a| self.attestation_view(&a))
     }
 
     pub fn get_attestations(
         &self,
         from_index: Option<u64>,
         limit: Option<u64>,
-    ) -> Vec<Attestation> {
+    ) -> Vec<AttestationView> {
         let total = self.attestation_count;
         if total == 0 {
             return vec![];
@@ -394,11 +794,11 @@ impl TlsOracle {
         let limit = limit.unwrap_or(20).min(100);
         let from = from_index.unwrap_or(total.saturating_sub(1));
 
-        let mut result: Vec<Attestation> = Vec::new();
+        let mut result: Vec<AttestationView> = Vec::new();
         let mut i = from;
         loop {
-            if let Some(a) = self.attestations.get(&i) {
-                result.push(a.clone());
+            if let Some(a) = self.attestation(i) {
+                result.push(self.attestation_view(&a));
             }
             if result.len() as u64 >= limit || i == 0 {
                 break;
@@ -412,7 +812,7 @@ impl TlsOracle {
         &self,
         server_name: String,
         limit: Option<u64>,
-    ) -> Vec<Attestation> {
+    ) -> Vec<AttestationView> {
         let ids = self
             .attestations_by_source
             .get(&server_name)
@@ -423,7 +823,8 @@ impl TlsOracle {
         ids.iter()
             .rev()
             .take(limit)
-            .filter_map(|id| self.attestations.get(id).cloned())
+            .filter_map(|id| self.attestation(*id))
+            .map(|a| self.attestation_view(&a))
             .collect()
     }
 
@@ -442,4 +843,286 @@ impl TlsOracle {
     pub fn get_owner(&self) -> AccountId {
         self.owner.clone()
     }
+
+    /// Системы доказательств, которые контракт сейчас принимает
+    pub fn get_proof_systems(&self) -> Vec<ProofSystem> {
+        let mut systems = Vec::new();
+        if cfg!(feature = "linked-verifier") || self.verifier.is_some() {
+            systems.push(ProofSystem::Groth16);
+        }
+        let external_plonk = self
+            .verifier
+            .as_ref()
+            .is_some_and(|v| v.plonk_vk_id.is_some());
+        if self.plonk_vk.is_some() || external_plonk {
+            systems.push(ProofSystem::Plonk);
+        }
+        systems
+    }
+}
+
+// ── Внутренние методы ────────────────────────────────────────
+
+impl TlsOracle {
+    /// Аттестация по id, сконвертированная в текущую схему
+    pub(crate) fn attestation(&self, id: u64) -> Option<Attestation> {
+        if let Some(versioned) = self.attestations.get(&id) {
+            return Some(versioned.clone().into_current());
+        }
+        self.legacy_attestations
+            .as_ref()?
+            .get(&id)
+            .map(|a| VersionedAttestation::V1(a.clone()).into_current())
+    }
+
+    pub(crate) fn has_attestation(&self, id: u64) -> bool {
+        self.attestations.contains_key(&id)
+            || self
+                .legacy_attestations
+                .as_ref()
+                .is_some_and(|legacy| legacy.contains_key(&id))
+    }
+
+    /// Добавляет нотариуса или обновляет существующего (позволяет добавить raw_pubkey)
+    fn upsert_notary(
+        &mut self,
+        pubkey_hash: String,
+        name: String,
+        url: String,
+        raw_pubkey: Option<String>,
+        added_by: AccountId,
+    ) {
+        if self.trusted_notaries.contains_key(&pubkey_hash) {
+            let mut info = self.trusted_notaries.get(&pubkey_hash).unwrap().clone();
+            if raw_pubkey.is_some() {
+                info.raw_pubkey = raw_pubkey;
+            }
+            info.name = name;
+            info.url = url;
+            self.trusted_notaries.insert(pubkey_hash.clone(), info);
+            env::log_str(&format!("Нотариус обновлён: {}", pubkey_hash));
+            return;
+        }
+
+        let info = NotaryInfo {
+            pubkey_hash: pubkey_hash.clone(),
+            raw_pubkey,
+            name,
+            url,
+            added_by,
+            added_at: env::block_height(),
+        };
+        self.trusted_notaries.insert(pubkey_hash.clone(), info);
+        env::log_str(&format!("Нотариус добавлен: {}", pubkey_hash));
+    }
+
+    fn delete_notary(&mut self, pubkey_hash: &str) {
+        require!(
+            self.trusted_notaries.remove(pubkey_hash).is_some(),
+            "Нотариус не найден"
+        );
+        self.record_notary_revocation(pubkey_hash);
+        env::log_str(&format!("Нотариус удалён: {}", pubkey_hash));
+    }
+
+    /// Общая верификация ZK submit (JSON и Borsh) + сохранение
+    ///
+    /// Если настроен внешний verifier — proof проверяется через promise,
+    /// аттестация сохраняется в callback `on_proof_verified`.
+    fn submit_zk(&mut self, sub: ZkSubmission) -> PromiseOrValue<u64> {
+        let ZkSubmission {
+            source_url,
+            server_name,
+            timestamp,
+            response_data,
+            proof,
+            public_inputs,
+            public_signals,
+            notary_signature,
+            notary_sig_v,
+            request_hash,
+        } = sub;
+
+        require!(response_data.len() <= 4096, "response_data макс 4KB");
+        require!(source_url.len() <= 2048, "source_url макс 2KB");
+
+        assert_fresh_timestamp(timestamp);
+
+        // Проверяем что timestamp в public_signals совпадает
+        require!(
+            public_signals[2] == timestamp.to_string(),
+            "Timestamp в public_signals не совпадает"
+        );
+
+        // Public signals должны быть < r, иначе s и s + r дают один и тот же proof
+        // с разными decimal ключами (обход replay-защиты)
+        require!(
+            public_inputs.iter().all(Scalar::is_canonical),
+            "Public signal вне поля Fr"
+        );
+
+        // Проверяем что нотариус доверенный (по Poseidon hash pubkey)
+        let notary_pubkey_hash = &public_signals[3];
+        let notary_info = self
+            .trusted_notaries
+            .get(notary_pubkey_hash)
+            .expect("Нотариус не в списке доверенных")
+            .clone();
+
+        let fee = self.require_fee(&server_name);
+
+        // Replay-защита по data commitment
+        let data_commitment = &public_signals[0];
+        require!(
+            !self.used_commitments.contains(data_commitment),
+            "Эта аттестация уже была отправлена (replay)"
+        );
+
+        // ── Верификация подписи нотариуса (ecrecover) ────────
+        let raw_pk = notary_info
+            .raw_pubkey
+            .as_ref()
+            .expect("raw_pubkey не установлен — обновите нотариуса через add_notary");
+
+        require!(
+            notary_signature.len() == 64,
+            "notary_signature: 64 bytes r||s"
+        );
+        require!(notary_sig_v <= 1, "notary_sig_v: 0 или 1");
+
+        // Воспроизводим message hash (SHA-256)
+        let message_hash = build_sign_message(
+            &source_url,
+            &server_name,
+            timestamp,
+            &response_data,
+            request_hash.as_ref(),
+        );
+
+        // ecrecover: восстанавливаем pubkey из подписи
+        let recovered = env::ecrecover(&message_hash, &notary_signature, notary_sig_v, true)
+            .expect("ecrecover: невалидная подпись");
+
+        // Сравниваем с зарегистрированным pubkey нотариуса
+        let expected_pubkey = hex_to_bytes(raw_pk);
+        require!(
+            recovered.as_slice() == expected_pubkey.as_slice(),
+            "Подпись нотариуса не совпадает с зарегистрированным ключом"
+        );
+
+        env::log_str("Подпись нотариуса верифицирована (ecrecover)");
+
+        let [data_commitment, server_name_hash, _, notary_pubkey_hash] = public_signals;
+        let attestation = Attestation {
+            id: 0,
+            source_url,
+            server_name,
+            timestamp,
+            response_data,
+            data_commitment,
+            server_name_hash,
+            notary_pubkey_hash,
+            submitter: env::predecessor_account_id(),
+            block_height: env::block_height(),
+            sig_verified: true,
+            request_hash: request_hash.as_ref().map(|h| bytes_to_hex(h)),
+        };
+
+        // ── PLONK ZK верификация ────────────────────────────
+        let proof = match proof {
+            ZkProof::Groth16(proof) => *proof,
+            ZkProof::Plonk(proof) => {
+                // Встроенный PLONK vk приоритетнее внешнего verifier
+                if let Some(vk) = &self.plonk_vk {
+                    require!(
+                        plonk::verify(vk, &proof, &public_inputs),
+                        "ZK proof невалиден"
+                    );
+                    return PromiseOrValue::Value(self.finish_zk_attestation(attestation, fee));
+                }
+                let verifier = self
+                    .verifier
+                    .as_ref()
+                    .expect("PLONK verification key не настроен (set_plonk_vk / set_verifier)");
+                return PromiseOrValue::Promise(external_verifier::verify_plonk_then_store(
+                    verifier,
+                    *proof,
+                    public_inputs.to_vec(),
+                    attestation,
+                    fee,
+                ));
+            }
+        };
+
+        // ── Groth16 ZK верификация ──────────────────────────
+        if let Some(verifier) = &self.verifier {
+            return PromiseOrValue::Promise(external_verifier::verify_then_store(
+                verifier,
+                proof,
+                public_inputs.to_vec(),
+                attestation,
+                fee,
+            ));
+        }
+
+        #[cfg(feature = "linked-verifier")]
+        {
+            // alt_bn128 внутри контракта
+            let vk = vk_data::get_vk();
+            require!(
+                groth16::verify(&vk, &proof, &public_inputs),
+                "ZK proof невалиден"
+            );
+            PromiseOrValue::Value(self.finish_zk_attestation(attestation, fee))
+        }
+
+        #[cfg(not(feature = "linked-verifier"))]
+        {
+            env::panic_str("Внешний verifier не настроен (set_verifier)")
+        }
+    }
+
+    /// Сохраняет аттестацию после успешной ZK верификации
+    fn finish_zk_attestation(&mut self, attestation: Attestation, fee: Fee) -> u64 {
+        let server_name = attestation.server_name.clone();
+        let submitter = attestation.submitter.clone();
+        let id = self.store_attestation(attestation, fee);
+
+        env::log_str(&format!(
+            "Аттестация #{} сохранена: {} ({}) [sig+zk verified]",
+            id, server_name, submitter
+        ));
+
+        id
+    }
+
+    /// Сохраняет аттестацию: присваивает id, помечает commitment использованным,
+    /// обновляет индекс по домену, распределяет комиссию и bounties
+    fn store_attestation(&mut self, mut attestation: Attestation, fee: Fee) -> u64 {
+        let id = self.attestation_count;
+        self.attestation_count += 1;
+        attestation.id = id;
+
+        self.used_commitments
+            .insert(attestation.data_commitment.clone());
+
+        // Индекс по домену
+        let server_name = attestation.server_name.clone();
+        let mut ids = self
+            .attestations_by_source
+            .get(&server_name)
+            .cloned()
+            .unwrap_or_default();
+        ids.push(id);
+        self.attestations_by_source.insert(server_name, ids);
+        self.index_attestation_url(&attestation);
+
+        self.distribute_fee(&attestation.notary_pubkey_hash, &fee);
+        self.record_notary_activity(&attestation);
+        self.start_challenge_window(&attestation);
+        self.pay_bounties(&attestation.server_name, &attestation.submitter);
+        self.mint_soulbound(&attestation);
+        self.attestations.insert(id, attestation.into());
+        id
+    }
 }
@@ -0,0 +1,85 @@
+//! Статистика и liveness нотариусов
+//!
+//! Счётчики обновляются при каждой сохранённой аттестации и при удалении
+//! нотариуса. Статистика переживает удаление — история ключа остаётся видна
+//! потребителям и governance.
+
+use near_sdk::{env, near};
+
+pub use tls_oracle_types::{NotaryStats, NotaryWithStats};
+
+use crate::{Attestation, TlsOracle, TlsOracleExt};
+
+impl TlsOracle {
+    fn stats_of(&self, pubkey_hash: &str) -> NotaryStats {
+        self.notary_stats
+            .get(pubkey_hash)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Учитывает сохранённую аттестацию
+    pub(crate) fn record_notary_activity(&mut self, attestation: &Attestation) {
+        let hash = &attestation.notary_pubkey_hash;
+        let mut stats = self.stats_of(hash);
+        stats.attestations_signed += 1;
+        stats.last_active_block = Some(attestation.block_height);
+        stats.last_submitter = Some(attestation.submitter.clone());
+
+        if self
+            .notary_domains
+            .insert((hash.clone(), attestation.server_name.clone()))
+        {
+            stats.domains_served += 1;
+        }
+        self.notary_stats.insert(hash.clone(), stats);
+    }
+
+    pub(crate) fn record_notary_dispute(&mut self, pubkey_hash: &str) {
+        let mut stats = self.stats_of(pubkey_hash);
+        stats.disputes_upheld += 1;
+        self.notary_stats.insert(pubkey_hash.to_string(), stats);
+    }
+
+    pub(crate) fn record_notary_revocation(&mut self, pubkey_hash: &str) {
+        let mut stats = self.stats_of(pubkey_hash);
+        stats.revocations += 1;
+        self.notary_stats.insert(pubkey_hash.to_string(), stats);
+    }
+}
+
+#[near]
+impl TlsOracle {
+    /// Статистика нотариуса (в том числе удалённого)
+    pub fn get_notary_stats(&self, pubkey_hash: String) -> Option<NotaryStats> {
+        self.notary_stats.get(&pubkey_hash).cloned()
+    }
+
+    /// Доверенные нотариусы со статистикой
+    pub fn get_notaries_with_stats(&self) -> Vec<NotaryWithStats> {
+        self.trusted_notaries
+            .iter()
+            .map(|(hash, info)| NotaryWithStats {
+                info: info.clone(),
+                stats: self.stats_of(hash),
+            })
+            .collect()
+    }
+
+    /// Доверенные нотариусы без аттестаций за последние `blocks` блоков
+    pub fn get_inactive_notaries(&self, blocks: u64) -> Vec<NotaryWithStats> {
+        let threshold = env::block_height().saturating_sub(blocks);
+        self.trusted_notaries
+            .iter()
+            .filter_map(|(hash, info)| {
+                let stats = self.stats_of(hash);
+                // Никогда не активный нотариус считается с момента добавления
+                let last = stats.last_active_block.unwrap_or(info.added_at);
+                (last < threshold).then(|| NotaryWithStats {
+                    info: info.clone(),
+                    stats,
+                })
+            })
+            .collect()
+    }
+}
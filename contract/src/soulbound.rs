@@ -0,0 +1,183 @@
+//! Soulbound токены аттестаций (NEP-171 / NEP-177 / NEP-181)
+//!
+//! В режиме `soulbound_mint` каждая сохранённая аттестация выпускает
+//! непередаваемый NFT на submitter. token_id = id аттестации, метаданные
+//! строятся из самой аттестации — отдельно хранится только владелец.
+//! nft_transfer / nft_transfer_call всегда падают.
+
+use near_contract_standards::non_fungible_token::events::NftMint;
+use near_contract_standards::non_fungible_token::metadata::{
+    NFTContractMetadata, TokenMetadata, NFT_METADATA_SPEC,
+};
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near, AccountId};
+
+use crate::{Attestation, TlsOracle, TlsOracleExt};
+
+impl TlsOracle {
+    /// Выпускает soulbound токен для аттестации (если режим включён)
+    pub(crate) fn mint_soulbound(&mut self, attestation: &Attestation) {
+        if !self.soulbound_mint {
+            return;
+        }
+        let owner_id = attestation.submitter.clone();
+        self.sbt_tokens.insert(attestation.id, owner_id.clone());
+
+        let mut owned = self
+            .sbt_by_owner
+            .get(&owner_id)
+            .cloned()
+            .unwrap_or_default();
+        owned.push(attestation.id);
+        self.sbt_by_owner.insert(owner_id.clone(), owned);
+
+        NftMint {
+            owner_id: &owner_id,
+            token_ids: &[&attestation.id.to_string()],
+            memo: None,
+        }
+        .emit();
+    }
+
+    /// Token с метаданными аттестации
+    fn sbt_token(&self, id: u64, owner_id: AccountId) -> Token {
+        let metadata = self.attestation(id).map(|a| TokenMetadata {
+            title: Some(format!("TLS Oracle attestation #{}", a.id)),
+            description: Some(format!("Верифицированные данные {}", a.server_name)),
+            media: None,
+            media_hash: None,
+            copies: Some(1),
+            issued_at: Some((a.timestamp * 1000).to_string()),
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: Some(
+                serde_json::json!({
+                    "attestationId": a.id,
+                    "serverName": a.server_name,
+                    "dataCommitment": a.data_commitment,
+                })
+                .to_string(),
+            ),
+            reference: None,
+            reference_hash: None,
+        });
+        Token {
+            token_id: id.to_string(),
+            owner_id,
+            metadata,
+            approved_account_ids: None,
+        }
+    }
+}
+
+/// token_id (decimal id аттестации) → u64
+fn parse_token_id(token_id: &TokenId) -> u64 {
+    token_id
+        .parse()
+        .unwrap_or_else(|_| env::panic_str("token_id: ожидается id аттестации"))
+}
+
+#[near]
+impl TlsOracle {
+    /// Включить/выключить выпуск soulbound токенов при сохранении аттестации
+    pub fn set_soulbound_mint(&mut self, enabled: bool) {
+        self.assert_owner("Только owner может менять режим soulbound");
+        self.soulbound_mint = enabled;
+    }
+
+    pub fn get_soulbound_mint(&self) -> bool {
+        self.soulbound_mint
+    }
+
+    // ── NEP-171 ──────────────────────────────────────────────
+
+    #[payable]
+    pub fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        let _ = (receiver_id, token_id, approval_id, memo);
+        env::panic_str("Soulbound токен нельзя передать");
+    }
+
+    #[payable]
+    pub fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> bool {
+        let _ = (receiver_id, token_id, approval_id, memo, msg);
+        env::panic_str("Soulbound токен нельзя передать");
+    }
+
+    pub fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        let id = parse_token_id(&token_id);
+        self.sbt_tokens
+            .get(&id)
+            .map(|owner_id| self.sbt_token(id, owner_id.clone()))
+    }
+
+    // ── NEP-177 ──────────────────────────────────────────────
+
+    pub fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "TLS Oracle Attestations".to_string(),
+            symbol: "TLSATT".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    // ── NEP-181 ──────────────────────────────────────────────
+
+    pub fn nft_total_supply(&self) -> U128 {
+        U128(self.sbt_tokens.len() as u128)
+    }
+
+    pub fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        let from = from_index.map(|i| i.0 as usize).unwrap_or(0);
+        let limit = limit.unwrap_or(20).min(100) as usize;
+        self.sbt_tokens
+            .iter()
+            .skip(from)
+            .take(limit)
+            .map(|(id, owner_id)| self.sbt_token(*id, owner_id.clone()))
+            .collect()
+    }
+
+    pub fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        let count = self.sbt_by_owner.get(&account_id).map_or(0, Vec::len);
+        U128(count as u128)
+    }
+
+    pub fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        let from = from_index.map(|i| i.0 as usize).unwrap_or(0);
+        let limit = limit.unwrap_or(20).min(100) as usize;
+        self.sbt_by_owner
+            .get(&account_id)
+            .map(|ids| {
+                ids.iter()
+                    .skip(from)
+                    .take(limit)
+                    .map(|id| self.sbt_token(*id, account_id.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
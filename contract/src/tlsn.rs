@@ -0,0 +1,280 @@
+//! Верификация TLSNotary attestation (tlsn) on-chain
+//!
+//! tlsn Attestation = Header + Body:
+//!   - Нотариус подписывает BCS(Header) ключом secp256k1 (ECDSA, SHA-256 prehash)
+//!   - Header.root — SHA-256 Merkle root по хешам полей Body
+//!   - Поле PlaintextHash коммитит диапазон транскрипта: H(plaintext || blinder)
+//!
+//! Контракт проверяет подпись над header, пересчитывает root из хешей полей
+//! и сверяет раскрытые диапазоны транскрипта с PlaintextHash коммитментами.
+//! Время сессии берётся из открытого поля ConnectionInfo, а server_name —
+//! из подписи нотариуса над server identity (цепочку сертификатов сервера
+//! нотариус проверил в MPC-TLS, on-chain её не проверить).
+
+use near_sdk::{env, require};
+
+pub use tls_oracle_types::{Direction, TlsnPresentation, TranscriptReveal};
+
+/// HashAlgId::SHA256 в tlsn
+pub const HASH_ALG_SHA256: u8 = 1;
+/// Длина domain separator, которым tlsn префиксует хешируемое поле
+pub const FIELD_DOMAIN_LEN: usize = 16;
+/// Длина blinder в PlaintextHash секрете
+pub const BLINDER_LEN: usize = 16;
+
+/// Domain separator поля PlaintextHash: blake3("PlaintextHash")[..16]
+pub const PLAINTEXT_HASH_DOMAIN: [u8; FIELD_DOMAIN_LEN] = [
+    127, 218, 195, 233, 67, 184, 87, 64, 146, 21, 201, 77, 173, 188, 234, 212,
+];
+/// Domain separator поля ConnectionInfo: blake3("ConnectionInfo")[..16]
+pub const CONNECTION_INFO_DOMAIN: [u8; FIELD_DOMAIN_LEN] = [
+    157, 96, 121, 146, 55, 123, 212, 17, 179, 158, 111, 128, 119, 57, 115, 2,
+];
+/// Тег подписи нотариуса над server identity
+pub const SERVER_IDENTITY_TAG: &[u8] = b"tls-oracle/tlsn-server-identity/v1";
+/// Значение скрытого секретного query-параметра в source_url
+/// (secrets::PLACEHOLDER в prover)
+pub const PLACEHOLDER: &str = "REDACTED";
+
+/// Разобранный tlsn Header
+pub struct Header {
+    pub id: [u8; 16],
+    pub version: u32,
+    pub root: [u8; 32],
+}
+
+/// Открытое поле ConnectionInfo
+pub struct ConnectionInfo {
+    /// UNIX timestamp TLS-сессии, секунды
+    pub time: u64,
+    pub sent_len: u32,
+    pub received_len: u32,
+}
+
+/// Читает ULEB128 (длины векторов в BCS)
+fn read_uleb128(bytes: &[u8], pos: &mut usize) -> u64 {
+    let mut value: u64 = 0;
+    let mut shift = 0;
+    loop {
+        require!(*pos < bytes.len(), "tlsn header: неожиданный конец");
+        let byte = bytes[*pos];
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+        shift += 7;
+        require!(shift < 64, "tlsn header: ULEB128 переполнение");
+    }
+}
+
+fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Парсит BCS(Header): id [u8;16] || version u32 LE || root.alg u8 || root.value Vec<u8>
+pub fn parse_header(bytes: &[u8]) -> Header {
+    require!(bytes.len() > 16 + 4, "tlsn header: слишком короткий");
+
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&bytes[..16]);
+
+    let mut version_bytes = [0u8; 4];
+    version_bytes.copy_from_slice(&bytes[16..20]);
+    let version = u32::from_le_bytes(version_bytes);
+
+    let mut pos = 20;
+    require!(
+        bytes[pos] == HASH_ALG_SHA256,
+        "tlsn header: поддерживается только SHA-256 root"
+    );
+    pos += 1;
+
+    let root_len = read_uleb128(bytes, &mut pos) as usize;
+    require!(root_len == 32, "tlsn header: root должен быть 32 байта");
+    require!(pos + root_len == bytes.len(), "tlsn header: лишние байты");
+
+    let mut root = [0u8; 32];
+    root.copy_from_slice(&bytes[pos..]);
+
+    Header { id, version, root }
+}
+
+/// Проверяет открытие ConnectionInfo против листа Merkle дерева и разбирает его:
+/// domain || time u64 || version u8 (TlsVersion) || sent u32 || received u32
+pub fn verify_connection_info(field: &[u8], index: u32, leaves: &[[u8; 32]]) -> ConnectionInfo {
+    let leaf = leaves
+        .get(index as usize)
+        .expect("tlsn connection_info: индекс вне диапазона");
+    require!(
+        &env::sha256_array(field) == leaf,
+        "tlsn connection_info: поле не входит в attestation"
+    );
+    require!(
+        field.len() == FIELD_DOMAIN_LEN + 8 + 1 + 4 + 4
+            && field[..FIELD_DOMAIN_LEN] == CONNECTION_INFO_DOMAIN,
+        "tlsn connection_info: поле не ConnectionInfo"
+    );
+
+    let body = &field[FIELD_DOMAIN_LEN..];
+    require!(body[8] <= 1, "tlsn connection_info: неизвестная версия TLS");
+    let u32_at = |pos: usize| u32::from_le_bytes(body[pos..pos + 4].try_into().unwrap());
+    ConnectionInfo {
+        time: u64::from_le_bytes(body[..8].try_into().unwrap()),
+        sent_len: u32_at(9),
+        received_len: u32_at(13),
+    }
+}
+
+/// Сообщение, которое нотариус подписывает после проверки сертификата сервера:
+/// TAG || header.root || server_name_len u32 BE || server_name
+pub fn server_identity_message(root: &[u8; 32], server_name: &str) -> Vec<u8> {
+    let mut message = Vec::with_capacity(SERVER_IDENTITY_TAG.len() + 32 + 4 + server_name.len());
+    message.extend_from_slice(SERVER_IDENTITY_TAG);
+    message.extend_from_slice(root);
+    message.extend_from_slice(&(server_name.len() as u32).to_be_bytes());
+    message.extend_from_slice(server_name.as_bytes());
+    message
+}
+
+/// SHA-256 Merkle root (rs_merkle): parent = H(left || right), непарный узел поднимается без изменений
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    require!(!leaves.is_empty(), "tlsn: пустой список полей");
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity(level.len().div_ceil(2));
+        for pair in level.chunks(2) {
+            if pair.len() == 2 {
+                let mut data = [0u8; 64];
+                data[..32].copy_from_slice(&pair[0]);
+                data[32..].copy_from_slice(&pair[1]);
+                next.push(env::sha256_array(data));
+            } else {
+                next.push(pair[0]);
+            }
+        }
+        level = next;
+    }
+    level[0]
+}
+
+/// BCS(PlaintextHash) для одного диапазона:
+/// direction u8 || RangeSet [start u64, end u64] || alg u8 || hash Vec<u8>
+fn plaintext_hash_bcs(direction: Direction, start: u64, end: u64, hash: &[u8; 32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(1 + 1 + 16 + 1 + 1 + 32);
+    out.push(match direction {
+        Direction::Sent => 0,
+        Direction::Received => 1,
+    });
+    write_uleb128(&mut out, 1);
+    out.extend_from_slice(&start.to_le_bytes());
+    out.extend_from_slice(&end.to_le_bytes());
+    out.push(HASH_ALG_SHA256);
+    write_uleb128(&mut out, 32);
+    out.extend_from_slice(hash);
+    out
+}
+
+/// Проверяет открытие PlaintextHash коммитмента против листа Merkle дерева
+pub fn verify_reveal(reveal: &TranscriptReveal, leaves: &[[u8; 32]]) {
+    require!(reveal.end > reveal.start, "tlsn reveal: пустой диапазон");
+    require!(
+        (reveal.end - reveal.start) as usize == reveal.data.0.len(),
+        "tlsn reveal: длина данных не совпадает с диапазоном"
+    );
+    require!(
+        reveal.blinder.0.len() == BLINDER_LEN,
+        "tlsn reveal: blinder 16 bytes"
+    );
+
+    let leaf = leaves
+        .get(reveal.field_index as usize)
+        .expect("tlsn reveal: field_index вне диапазона");
+    require!(
+        &env::sha256_array(&reveal.field.0) == leaf,
+        "tlsn reveal: поле не входит в attestation"
+    );
+
+    // H(plaintext || blinder)
+    let mut preimage = reveal.data.0.clone();
+    preimage.extend_from_slice(&reveal.blinder.0);
+    let plaintext_hash = env::sha256_array(&preimage);
+
+    let expected = plaintext_hash_bcs(reveal.direction, reveal.start, reveal.end, &plaintext_hash);
+    require!(
+        reveal.field.0.len() == FIELD_DOMAIN_LEN + expected.len()
+            && reveal.field.0[..FIELD_DOMAIN_LEN] == PLAINTEXT_HASH_DOMAIN
+            && reveal.field.0[FIELD_DOMAIN_LEN..] == expected[..],
+        "tlsn reveal: коммитмент не совпадает с раскрытыми данными"
+    );
+}
+
+/// Восстанавливает pubkey нотариуса из подписи над сообщением (BCS(Header)
+/// или server identity). Подпись без recovery id — возвращаем оба кандидата (v = 0, 1).
+pub fn recover_signer(message: &[u8], signature: &[u8]) -> Vec<[u8; 64]> {
+    require!(signature.len() == 64, "tlsn signature: 64 bytes r||s");
+    let hash = env::sha256_array(message);
+    (0..2u8)
+        .filter_map(|v| env::ecrecover(&hash, signature, v, true))
+        .collect()
+}
+
+/// Есть ли в HTTP-запросе строка заголовка `Host: {server_name}` целиком
+/// (имя без учёта регистра, значение ограничено CRLF)
+pub fn has_host_header(sent: &[u8], server_name: &str) -> bool {
+    let line = format!("\r\nhost: {}\r\n", server_name.to_ascii_lowercase());
+    let sent = sent.to_ascii_lowercase();
+    sent.windows(line.len()).any(|w| w == line.as_bytes())
+}
+
+/// Target строки запроса `METHOD target HTTP/1.1` из раскрытых диапазонов
+/// отправленного транскрипта
+///
+/// Строка собирается с начала транскрипта. Нераскрытый промежуток допустим
+/// только как значение query-параметра (после `=` в query) — это секрет,
+/// на его месте подставляется PLACEHOLDER.
+pub fn request_target<'a>(sent: impl IntoIterator<Item = &'a TranscriptReveal>) -> Option<String> {
+    let mut reveals: Vec<&TranscriptReveal> = sent.into_iter().collect();
+    reveals.sort_by_key(|r| r.start);
+
+    let mut line: Vec<u8> = Vec::new();
+    let mut pos = 0u64;
+    for reveal in reveals {
+        if reveal.end <= pos {
+            continue;
+        }
+        if reveal.start > pos {
+            let in_query = line.contains(&b'?') && line.last() == Some(&b'=');
+            if !in_query {
+                return None;
+            }
+            line.extend_from_slice(PLACEHOLDER.as_bytes());
+        }
+        let skip = pos.saturating_sub(reveal.start) as usize;
+        line.extend_from_slice(&reveal.data.0[skip..]);
+        pos = reveal.end;
+        if line.windows(2).any(|w| w == b"\r\n") {
+            break;
+        }
+    }
+
+    let end = line.windows(2).position(|w| w == b"\r\n")?;
+    let line = std::str::from_utf8(&line[..end]).ok()?;
+    let mut parts = line.split(' ');
+    let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+    let valid = parts.next().is_none()
+        && !method.is_empty()
+        && method.bytes().all(|b| b.is_ascii_uppercase())
+        && target.starts_with('/')
+        && version.starts_with("HTTP/");
+    valid.then(|| target.to_string())
+}
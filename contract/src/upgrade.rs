@@ -0,0 +1,236 @@
+//! Обновление контракта: staged code + timelock + migration hook, NEP-330 метаданные
+//!
+//! 1. owner вызывает `stage_upgrade` с wasm в сыром input — код сохраняется
+//!    отдельным ключом хранилища (не в состоянии контракта), sha256 публикуется
+//!    событием `upgrade_staged`
+//! 2. после UPGRADE_TIMELOCK_SECS любой аккаунт вызывает `deploy_upgrade`:
+//!    deploy + вызов `post_upgrade` новой версии одним batch
+//!
+//! При включённом governance wasm загружает член совета (в предложение он не
+//! помещается), а разворачивается он только после `approve_upgrade` с тем же
+//! code_hash по предложению FunctionCall; timelock отсчитывается от одобрения.
+//!
+//! Хранение wasm оплачивает загрузивший его (attached deposit, излишек
+//! возвращается); депозит возвращается ему после deploy или `cancel_upgrade`.
+//! Staged код не заменяется: новый загружается только после `cancel_upgrade`.
+//! 3. `post_upgrade` — migration hook новой версии. По умолчанию читает
+//!    состояние как есть; при изменении схемы здесь читается старая структура
+//!    и конвертируется в новую
+//!
+//! NEP-330 `contract_source_metadata` генерирует near-sdk (contract_metadata в lib.rs),
+//! в standards — версия circuit. Хеши verification keys — `get_circuit_metadata`.
+
+use near_sdk::json_types::Base58CryptoHash;
+use near_sdk::{env, near, require, AccountId, Gas, NearToken, Promise};
+
+pub use tls_oracle_types::{CircuitMetadata, StagedUpgrade};
+
+use crate::fees::refund_deposit;
+use crate::{TlsOracle, TlsOracleExt};
+
+/// Задержка между stage и deploy
+const UPGRADE_TIMELOCK_SECS: u64 = 24 * 3600;
+/// Ключ хранилища staged wasm
+const STAGED_CODE_KEY: &[u8] = b"staged_code";
+/// Ключ хранилища: borsh (загрузивший wasm, его storage deposit)
+const STAGED_DEPOSIT_KEY: &[u8] = b"staged_deposit";
+/// Газ на migration hook после deploy
+const GAS_FOR_POST_UPGRADE: Gas = Gas::from_tgas(30);
+/// Версия circuit, под который собран встроенный verification key
+/// (синхронно со standard "tls-oracle-circuit" в contract_metadata)
+const CIRCUIT_VERSION: &str = "attestation-v1";
+
+fn now_secs() -> u64 {
+    env::block_timestamp() / 1_000_000_000
+}
+
+/// NEP-297 событие контракта
+fn emit_event(event: &str, data: serde_json::Value) {
+    let payload = serde_json::json!({
+        "standard": "tls-oracle",
+        "version": "1.0.0",
+        "event": event,
+        "data": [data],
+    });
+    env::log_str(&format!("EVENT_JSON:{}", payload));
+}
+
+/// Удаляет staged wasm и возвращает storage deposit загрузившему
+fn release_staged_code() {
+    env::storage_remove(STAGED_CODE_KEY);
+    let Some(record) = env::storage_read(STAGED_DEPOSIT_KEY) else {
+        return;
+    };
+    env::storage_remove(STAGED_DEPOSIT_KEY);
+    let (staged_by, deposit): (AccountId, u128) =
+        near_sdk::borsh::from_slice(&record).expect("staged deposit");
+    if deposit > 0 {
+        Promise::new(staged_by)
+            .transfer(NearToken::from_yoctonear(deposit))
+            .detach();
+    }
+}
+
+fn vk_hash<T: near_sdk::borsh::BorshSerialize>(vk: &T) -> String {
+    let bytes = near_sdk::borsh::to_vec(vk).expect("borsh(VK)");
+    crate::bytes_to_hex(&env::sha256_array(&bytes))
+}
+
+#[near]
+impl TlsOracle {
+    /// Загрузить новый wasm (сырой input)
+    ///
+    /// Attached deposit покрывает хранение wasm, излишек возвращается.
+    /// Уже staged код сначала снимается через cancel_upgrade.
+    #[payable]
+    pub fn stage_upgrade(&mut self) {
+        let caller = env::predecessor_account_id();
+        let approved = self.governance.is_none();
+        require!(
+            if approved {
+                caller == self.owner
+            } else {
+                self.is_council_member(&caller)
+            },
+            "Только owner (при governance — член совета) может загружать обновление"
+        );
+        require!(
+            self.staged_upgrade.is_none(),
+            "Обновление уже загружено — сначала cancel_upgrade"
+        );
+        let code = env::input().expect("Нет wasm в input");
+        require!(code.starts_with(b"\0asm"), "Input не wasm модуль");
+
+        let code_hash = env::sha256_array(&code);
+        let usage_before = env::storage_usage();
+        env::storage_write(STAGED_CODE_KEY, &code);
+        // Запись фиксированного размера: пишется до замера, затем с суммой
+        let mut record = (caller, 0u128);
+        env::storage_write(
+            STAGED_DEPOSIT_KEY,
+            &near_sdk::borsh::to_vec(&record).unwrap(),
+        );
+
+        let cost =
+            (env::storage_usage() - usage_before) as u128 * env::storage_byte_cost().as_yoctonear();
+        let deposit = env::attached_deposit().as_yoctonear();
+        require!(
+            deposit >= cost,
+            format!("Депозит за хранение wasm: {} yoctoNEAR", cost)
+        );
+        record.1 = cost;
+        env::storage_write(
+            STAGED_DEPOSIT_KEY,
+            &near_sdk::borsh::to_vec(&record).unwrap(),
+        );
+        refund_deposit(deposit - cost);
+
+        let staged_at = now_secs();
+        let staged = StagedUpgrade {
+            code_hash: code_hash.into(),
+            code_size: code.len() as u64,
+            staged_at,
+            deploy_after: staged_at + UPGRADE_TIMELOCK_SECS,
+            approved,
+        };
+        emit_event(
+            "upgrade_staged",
+            serde_json::json!({
+                "code_hash": staged.code_hash,
+                "code_size": staged.code_size,
+                "deploy_after": staged.deploy_after,
+                "approved": staged.approved,
+            }),
+        );
+        self.staged_upgrade = Some(staged);
+    }
+
+    /// Одобрить staged код (governance: предложение FunctionCall). Timelock заново
+    pub fn approve_upgrade(&mut self, code_hash: Base58CryptoHash) {
+        self.assert_owner("Только owner может одобрить обновление");
+        let staged = self.staged_upgrade.as_mut().expect("Нет staged обновления");
+        require!(
+            staged.code_hash == code_hash,
+            "code_hash не совпадает со staged кодом"
+        );
+        staged.approved = true;
+        staged.deploy_after = now_secs() + UPGRADE_TIMELOCK_SECS;
+        emit_event(
+            "upgrade_approved",
+            serde_json::json!({
+                "code_hash": staged.code_hash,
+                "deploy_after": staged.deploy_after,
+            }),
+        );
+    }
+
+    pub fn cancel_upgrade(&mut self) {
+        self.assert_owner("Только owner может отменить обновление");
+        let staged = self.staged_upgrade.take().expect("Нет staged обновления");
+        release_staged_code();
+        emit_event(
+            "upgrade_cancelled",
+            serde_json::json!({ "code_hash": staged.code_hash }),
+        );
+    }
+
+    /// Развернуть staged код после timelock (любой аккаунт, нужен ~300 TGas)
+    pub fn deploy_upgrade(&mut self) -> Promise {
+        let staged = self.staged_upgrade.clone().expect("Нет staged обновления");
+        require!(staged.approved, "Обновление не одобрено governance");
+        require!(
+            now_secs() >= staged.deploy_after,
+            format!("Timelock до {}", staged.deploy_after)
+        );
+        let code = env::storage_read(STAGED_CODE_KEY).expect("Staged код не найден");
+
+        emit_event(
+            "upgrade_deployed",
+            serde_json::json!({ "code_hash": staged.code_hash }),
+        );
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "post_upgrade".to_string(),
+                Vec::new(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_POST_UPGRADE,
+            )
+    }
+
+    /// Migration hook: вызывается новой версией сразу после deploy
+    #[private]
+    #[init(ignore_state)]
+    pub fn post_upgrade() -> Self {
+        let mut state: Self = env::state_read().expect("Нет состояния контракта");
+        state.staged_upgrade = None;
+        release_staged_code();
+        state
+    }
+
+    // ── View методы ──────────────────────────────────────────
+
+    pub fn get_staged_upgrade(&self) -> Option<StagedUpgrade> {
+        self.staged_upgrade.clone()
+    }
+
+    /// Версия circuit и хеши verification keys, с которыми работает контракт
+    pub fn get_circuit_metadata(&self) -> CircuitMetadata {
+        #[cfg(feature = "linked-verifier")]
+        let groth16_vk_hash = Some(vk_hash(&crate::vk_data::get_vk()));
+        #[cfg(not(feature = "linked-verifier"))]
+        let groth16_vk_hash = None;
+
+        CircuitMetadata {
+            circuit: CIRCUIT_VERSION.to_string(),
+            groth16_vk_hash,
+            plonk_vk_hash: self.plonk_vk.as_ref().map(vk_hash),
+            external_vk: self
+                .verifier
+                .as_ref()
+                .map(|v| format!("{}/{}", v.account_id, v.vk_id)),
+            proof_systems: self.get_proof_systems(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Индекс аттестаций по source_url, упорядоченный по timestamp
+//!
+//! Ключ URL — sha256 нормализованного URL: схема и host в нижнем регистре,
+//! без порта 443, без fragment, пустой path → "/". Query сохраняется —
+//! разные параметры дают разные данные.
+//!
+//! Индекс — TreeMap по (ключ URL, timestamp, id): вставка O(log n) без
+//! перезаписи истории URL, выборка по времени — range по префиксу URL.
+
+use std::ops::RangeInclusive;
+
+use near_sdk::{env, near};
+
+use crate::disputes::AttestationView;
+use crate::{Attestation, TlsOracle, TlsOracleExt};
+
+/// Нормализует URL для ключа индекса
+pub(crate) fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split_once('#').map_or(url, |(base, _)| base);
+
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let split = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(split);
+
+    let scheme = scheme.to_ascii_lowercase();
+    let mut authority = authority.to_ascii_lowercase();
+    if scheme == "https" && authority.ends_with(":443") {
+        authority.truncate(authority.len() - 4);
+    }
+    let path = if path.is_empty() || path.starts_with('?') {
+        format!("/{}", path)
+    } else {
+        path.to_string()
+    };
+    format!("{}://{}{}", scheme, authority, path)
+}
+
+fn url_key(url: &str) -> [u8; 32] {
+    env::sha256_array(normalize_url(url).as_bytes())
+}
+
+/// Ключи индекса URL с timestamp в [from_ts, to_ts]
+fn url_range(source_url: &str, from_ts: u64, to_ts: u64) -> RangeInclusive<([u8; 32], u64, u64)> {
+    let key = url_key(source_url);
+    (key, from_ts, 0)..=(key, to_ts, u64::MAX)
+}
+
+fn now_secs() -> u64 {
+    env::block_timestamp() / 1_000_000_000
+}
+
+impl TlsOracle {
+    /// Добавляет аттестацию в индекс URL
+    pub(crate) fn index_attestation_url(&mut self, attestation: &Attestation) {
+        let key = url_key(&attestation.source_url);
+        self.attestations_by_url
+            .insert((key, attestation.timestamp, attestation.id), ());
+    }
+}
+
+#[near]
+impl TlsOracle {
+    /// Самая свежая действительная аттестация URL не старше max_age_secs
+    ///
+    /// Аттестации, признанные недействительными по dispute, пропускаются.
+    /// Неокончательные (открыто окно оспаривания) возвращаются с finalized = false.
+    pub fn get_latest(
+        &self,
+        source_url: String,
+        max_age_secs: Option<u64>,
+    ) -> Option<AttestationView> {
+        let min_ts = max_age_secs.map_or(0, |age| now_secs().saturating_sub(age));
+
+        self.attestations_by_url
+            .range(url_range(&source_url, min_ts, u64::MAX))
+            .rev()
+            .map(|((_, _, id), _)| *id)
+            .filter(|id| !self.invalidated.contains(id))
+            .find_map(|id| self.attestation(id))
+            .map(|a| self.attestation_view(&a))
+    }
+
+    /// Аттестации URL с timestamp в [from_ts, to_ts], по возрастанию времени
+    pub fn get_range(
+        &self,
+        source_url: String,
+        from_ts: u64,
+        to_ts: u64,
+        limit: Option<u64>,
+    ) -> Vec<AttestationView> {
+        if from_ts > to_ts {
+            return vec![];
+        }
+        let limit = limit.unwrap_or(20).min(100) as usize;
+
+        self.attestations_by_url
+            .range(url_range(&source_url, from_ts, to_ts))
+            .take(limit)
+            .filter_map(|((_, _, id), _)| self.attestation(*id))
+            .map(|a| self.attestation_view(&a))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("HTTPS://API.Example.com:443/v1/Price?sym=NEAR#frag"),
+            "https://api.example.com/v1/Price?sym=NEAR"
+        );
+        assert_eq!(normalize_url("https://example.com"), "https://example.com/");
+        assert_eq!(
+            normalize_url("https://example.com?a=1"),
+            "https://example.com/?a=1"
+        );
+        assert_eq!(
+            normalize_url("https://example.com:8443/x"),
+            "https://example.com:8443/x"
+        );
+    }
+}
@@ -97,6 +97,7 @@ export async function submitAttestation(attestation) {
             public_signals: attestation.publicSignals,
             notary_signature: attestation.notarySignature,
             notary_sig_v: attestation.notarySigV,
+            request_hash: attestation.requestHash ?? null,
           },
           gas: "200000000000000", // 200 TGas (Groth16 + ecrecover)
           deposit: "50000000000000000000000", // 0.05 NEAR (storage)
@@ -0,0 +1,527 @@
+//! Выборочное раскрытие HTTP-ответа (selective disclosure)
+//!
+//! Правила из ProveRequest переводятся в диапазоны байт полученного
+//! транскрипта. Нотариусу и on-chain раскрываются только эти диапазоны
+//! и статусная строка; остальное покрывается hash-коммитментами и не
+//! покидает MPC-TLS сессию.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::Deserialize;
+use std::ops::Range;
+
+/// Максимум правил раскрытия в одном запросе
+pub const MAX_RULES: usize = 16;
+
+/// Байт, которым заменяются скрытые части в redacted транскрипте
+const REDACTED: u8 = b'X';
+
+/// Правило раскрытия части HTTP-ответа
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DisclosureRule {
+    /// RFC 6901 JSON pointer в теле; раскрывается `"ключ":значение`
+    /// (для элемента массива — только значение)
+    JsonPointer { pointer: String },
+    /// Байты тела [start, end) после снятия chunked-кодирования
+    Range { start: usize, end: usize },
+    /// Строка заголовка ответа целиком ("Name: value"), без учёта регистра имени
+    Header { name: String },
+}
+
+/// Результат применения правил к полученному транскрипту
+pub struct Disclosure {
+    /// Раскрываемые диапазоны транскрипта (отсортированы, без пересечений)
+    pub revealed: Vec<Range<usize>>,
+    /// Раскрытый текст по каждому правилу, в порядке правил
+    pub fragments: Vec<String>,
+}
+
+impl Disclosure {
+    /// Нераскрытые диапазоны — коммитятся хешем, но не открываются
+    pub fn hidden(&self, len: usize) -> Vec<Range<usize>> {
+        let mut hidden = Vec::new();
+        let mut pos = 0;
+        for r in &self.revealed {
+            if r.start > pos {
+                hidden.push(pos..r.start);
+            }
+            pos = r.end;
+        }
+        if pos < len {
+            hidden.push(pos..len);
+        }
+        hidden
+    }
+
+    /// Транскрипт со скрытыми байтами, заменёнными на 'X'
+    pub fn redact(&self, received: &[u8]) -> String {
+        let mut out = vec![REDACTED; received.len()];
+        for r in &self.revealed {
+            out[r.clone()].copy_from_slice(&received[r.clone()]);
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// response_data для подписи: один фрагмент как есть, несколько — JSON массив
+    pub fn response_data(&self) -> String {
+        match self.fragments.as_slice() {
+            [single] => single.clone(),
+            fragments => serde_json::to_string(fragments).unwrap_or_default(),
+        }
+    }
+}
+
+/// Применяет правила к полученному транскрипту (HTTP/1.1 ответ)
+pub fn resolve(received: &[u8], rules: &[DisclosureRule]) -> Result<Disclosure> {
+    ensure!(!rules.is_empty(), "Пустой список правил раскрытия");
+    ensure!(
+        rules.len() <= MAX_RULES,
+        "Максимум {MAX_RULES} правил раскрытия"
+    );
+
+    let response = HttpResponse::parse(received)?;
+
+    // Статусная строка раскрывается всегда — код ответа часть аттестации
+    let mut revealed = Vec::with_capacity(rules.len() + 1);
+    revealed.push(0..response.status_line_end);
+    let mut fragments = Vec::with_capacity(rules.len());
+
+    for rule in rules {
+        let ranges = match rule {
+            DisclosureRule::Header { name } => {
+                let ranges: Vec<_> = response
+                    .headers
+                    .iter()
+                    .filter(|h| h.name.eq_ignore_ascii_case(name))
+                    .map(|h| h.line.clone())
+                    .collect();
+                ensure!(!ranges.is_empty(), "Заголовок {name} отсутствует в ответе");
+                ranges
+            }
+            DisclosureRule::Range { start, end } => {
+                ensure!(
+                    start < end && *end <= response.body.len(),
+                    "Диапазон {start}..{end} вне тела ответа ({} байт)",
+                    response.body.len()
+                );
+                response.map_body_range(*start..*end)
+            }
+            DisclosureRule::JsonPointer { pointer } => {
+                let span = json_pointer_span(&response.body, pointer)
+                    .with_context(|| format!("JSON pointer {pointer}"))?;
+                response.map_body_range(span)
+            }
+        };
+
+        let text: Vec<u8> = ranges
+            .iter()
+            .flat_map(|r| received[r.clone()].iter().copied())
+            .collect();
+        fragments.push(String::from_utf8_lossy(&text).into_owned());
+        revealed.extend(ranges);
+    }
+
+    Ok(Disclosure {
+        revealed: merge_ranges(revealed),
+        fragments,
+    })
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+// ── HTTP ответ ───────────────────────────────────────────────
+
+struct HeaderLine {
+    name: String,
+    /// Строка заголовка в транскрипте без CRLF
+    line: Range<usize>,
+}
+
+struct HttpResponse {
+    status_line_end: usize,
+    headers: Vec<HeaderLine>,
+    /// Тело после снятия chunked-кодирования
+    body: Vec<u8>,
+    segments: Vec<BodySegment>,
+}
+
+/// Кусок тела: смещение в body, смещение в транскрипте, длина
+#[derive(Clone, Copy)]
+struct BodySegment {
+    body_off: usize,
+    transcript_off: usize,
+    len: usize,
+}
+
+impl HttpResponse {
+    fn parse(received: &[u8]) -> Result<Self> {
+        let head_end = find(received, b"\r\n\r\n").context("Конец заголовков ответа не найден")?;
+        let status_line_end = find(received, b"\r\n").unwrap_or(head_end);
+
+        let mut headers = Vec::new();
+        let mut pos = status_line_end + 2;
+        while pos < head_end {
+            let end = pos + find(&received[pos..], b"\r\n").unwrap_or(head_end - pos);
+            let line = &received[pos..end];
+            if let Some(colon) = line.iter().position(|b| *b == b':') {
+                headers.push(HeaderLine {
+                    name: String::from_utf8_lossy(&line[..colon]).trim().to_string(),
+                    line: pos..end,
+                });
+            }
+            pos = end + 2;
+        }
+
+        let header_value = |name: &str| {
+            headers
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case(name))
+                .map(|h| {
+                    let line = &received[h.line.clone()];
+                    let colon = line.iter().position(|b| *b == b':').unwrap_or(0);
+                    String::from_utf8_lossy(&line[colon + 1..])
+                        .trim()
+                        .to_ascii_lowercase()
+                })
+        };
+        if let Some(encoding) = header_value("content-encoding") {
+            ensure!(
+                encoding == "identity",
+                "Сжатый ответ ({encoding}) не поддерживает выборочное раскрытие"
+            );
+        }
+        let chunked = header_value("transfer-encoding").is_some_and(|te| te.contains("chunked"));
+
+        let body_start = head_end + 4;
+        let (body, segments) = if chunked {
+            dechunk(received, body_start)?
+        } else {
+            let body = received[body_start..].to_vec();
+            let len = body.len();
+            let segment = BodySegment {
+                body_off: 0,
+                transcript_off: body_start,
+                len,
+            };
+            (body, vec![segment])
+        };
+
+        Ok(Self {
+            status_line_end,
+            headers,
+            body,
+            segments,
+        })
+    }
+
+    /// Диапазон тела → диапазоны транскрипта (несколько, если пересекает chunks)
+    fn map_body_range(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        self.segments
+            .iter()
+            .filter_map(
+                |&BodySegment {
+                     body_off,
+                     transcript_off,
+                     len,
+                 }| {
+                    let start = range.start.max(body_off);
+                    let end = range.end.min(body_off + len);
+                    (start < end).then(|| {
+                        transcript_off + (start - body_off)..transcript_off + (end - body_off)
+                    })
+                },
+            )
+            .collect()
+    }
+}
+
+/// Снимает chunked-кодирование, запоминая положение кусков в транскрипте
+fn dechunk(received: &[u8], mut pos: usize) -> Result<(Vec<u8>, Vec<BodySegment>)> {
+    let mut body = Vec::new();
+    let mut segments = Vec::new();
+    loop {
+        let line_end = pos + find(&received[pos..], b"\r\n").context("Обрезанный chunk")?;
+        let size_str = String::from_utf8_lossy(&received[pos..line_end]);
+        let size_hex = size_str.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .with_context(|| format!("Неверный размер chunk: {size_hex}"))?;
+        if size == 0 {
+            break;
+        }
+        let data_start = line_end + 2;
+        ensure!(data_start + size <= received.len(), "Обрезанный chunk");
+        segments.push(BodySegment {
+            body_off: body.len(),
+            transcript_off: data_start,
+            len: size,
+        });
+        body.extend_from_slice(&received[data_start..data_start + size]);
+        pos = data_start + size + 2;
+    }
+    Ok((body, segments))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+// ── JSON pointer → диапазон байт ─────────────────────────────
+
+/// Диапазон значения по JSON pointer (для члена объекта — вместе с ключом)
+fn json_pointer_span(json: &[u8], pointer: &str) -> Result<Range<usize>> {
+    let mut s = Scanner { b: json, pos: 0 };
+    s.skip_ws();
+    let mut span_start = s.pos;
+    let mut value_start = s.pos;
+
+    if !pointer.is_empty() {
+        ensure!(
+            pointer.starts_with('/'),
+            "JSON pointer должен начинаться с '/'"
+        );
+        for token in pointer[1..].split('/') {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            s.pos = value_start;
+            match s.peek() {
+                Some(b'{') => {
+                    s.pos += 1;
+                    loop {
+                        s.skip_ws();
+                        ensure!(s.peek() == Some(b'"'), "Ключ {token} не найден");
+                        let key_start = s.pos;
+                        let key = s.string()?;
+                        s.skip_ws();
+                        s.expect(b':')?;
+                        s.skip_ws();
+                        if key == token {
+                            span_start = key_start;
+                            value_start = s.pos;
+                            break;
+                        }
+                        s.skip_value()?;
+                        s.skip_ws();
+                        ensure!(s.peek() != Some(b'}'), "Ключ {token} не найден");
+                        s.expect(b',')?;
+                    }
+                }
+                Some(b'[') => {
+                    let index: usize = token
+                        .parse()
+                        .with_context(|| format!("Индекс массива: {token}"))?;
+                    s.pos += 1;
+                    for _ in 0..index {
+                        s.skip_ws();
+                        s.skip_value()?;
+                        s.skip_ws();
+                        ensure!(s.peek() != Some(b']'), "Индекс {index} вне массива");
+                        s.expect(b',')?;
+                    }
+                    s.skip_ws();
+                    ensure!(s.peek() != Some(b']'), "Индекс {index} вне массива");
+                    span_start = s.pos;
+                    value_start = s.pos;
+                }
+                _ => bail!("{token}: родитель не объект и не массив"),
+            }
+        }
+    }
+
+    s.pos = value_start;
+    s.skip_value()?;
+    Ok(span_start..s.pos)
+}
+
+struct Scanner<'a> {
+    b: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.b.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        ensure!(
+            self.peek() == Some(byte),
+            "Ожидался '{}' на позиции {}",
+            byte as char,
+            self.pos
+        );
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Конец строкового литерала (pos на открывающей кавычке)
+    fn skip_string(&mut self) -> Result<()> {
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => bail!("Незакрытая строка"),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.skip_string()?;
+        serde_json::from_slice(&self.b[start..self.pos]).context("Невалидная JSON строка")
+    }
+
+    fn skip_value(&mut self) -> Result<()> {
+        match self.peek() {
+            Some(b'"') => self.skip_string(),
+            Some(b'{' | b'[') => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek() {
+                        Some(b'"') => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        Some(b'{' | b'[') => depth += 1,
+                        Some(b'}' | b']') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Ok(());
+                            }
+                        }
+                        Some(_) => {}
+                        None => bail!("Незакрытый объект/массив"),
+                    }
+                    self.pos += 1;
+                }
+            }
+            Some(_) => {
+                let start = self.pos;
+                while !matches!(
+                    self.peek(),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\r' | b'\n')
+                ) {
+                    self.pos += 1;
+                }
+                ensure!(self.pos > start, "Ожидалось значение на позиции {start}");
+                Ok(())
+            }
+            None => bail!("Неожиданный конец JSON"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-Secret: s3cr3t\r\n\r\n{\"user\":{\"name\":\"alice\",\"email\":\"a@x.io\"},\"prices\":[1.5, {\"usd\":3.42}]}";
+
+    fn text(received: &[u8], ranges: &[Range<usize>]) -> Vec<String> {
+        ranges
+            .iter()
+            .map(|r| String::from_utf8_lossy(&received[r.clone()]).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_json_pointer() {
+        let rules = [DisclosureRule::JsonPointer {
+            pointer: "/prices/1/usd".to_string(),
+        }];
+        let d = resolve(RESPONSE, &rules).unwrap();
+        assert_eq!(d.fragments, vec!["\"usd\":3.42"]);
+        assert_eq!(
+            text(RESPONSE, &d.revealed),
+            vec!["HTTP/1.1 200 OK", "\"usd\":3.42"]
+        );
+
+        let redacted = d.redact(RESPONSE);
+        assert!(!redacted.contains("alice"));
+        assert!(!redacted.contains("s3cr3t"));
+        assert!(redacted.contains("\"usd\":3.42"));
+    }
+
+    #[test]
+    fn test_header_and_range() {
+        let rules = [
+            DisclosureRule::Header {
+                name: "content-type".to_string(),
+            },
+            DisclosureRule::Range { start: 0, end: 7 },
+        ];
+        let d = resolve(RESPONSE, &rules).unwrap();
+        assert_eq!(
+            d.fragments,
+            vec!["Content-Type: application/json", "{\"user\""]
+        );
+        assert_eq!(
+            d.response_data(),
+            "[\"Content-Type: application/json\",\"{\\\"user\\\"\"]"
+        );
+    }
+
+    #[test]
+    fn test_hidden_complement() {
+        let rules = [DisclosureRule::JsonPointer {
+            pointer: "/user/name".to_string(),
+        }];
+        let d = resolve(RESPONSE, &rules).unwrap();
+        let hidden = d.hidden(RESPONSE.len());
+        let mut all = [d.revealed.clone(), hidden].concat();
+        all.sort_by_key(|r| r.start);
+        assert_eq!(all.first().unwrap().start, 0);
+        assert_eq!(all.last().unwrap().end, RESPONSE.len());
+        assert!(all.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[test]
+    fn test_chunked_body() {
+        let received = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n8\r\n{\"a\":\"xy\r\n6\r\nz\",\"b\"\r\n3\r\n:1}\r\n0\r\n\r\n";
+        let rules = [DisclosureRule::JsonPointer {
+            pointer: "/a".to_string(),
+        }];
+        let d = resolve(received, &rules).unwrap();
+        assert_eq!(d.fragments, vec!["\"a\":\"xyz\""]);
+        // Значение пересекает границу chunk — два диапазона транскрипта
+        assert_eq!(text(received, &d.revealed[1..]), vec!["\"a\":\"xy", "z\""]);
+    }
+
+    #[test]
+    fn test_missing_targets() {
+        let missing = |rule| resolve(RESPONSE, &[rule]).is_err();
+        assert!(missing(DisclosureRule::JsonPointer {
+            pointer: "/user/phone".to_string()
+        }));
+        assert!(missing(DisclosureRule::JsonPointer {
+            pointer: "/prices/5".to_string()
+        }));
+        assert!(missing(DisclosureRule::Header {
+            name: "Set-Cookie".to_string()
+        }));
+        assert!(missing(DisclosureRule::Range {
+            start: 10,
+            end: 1000
+        }));
+    }
+}
@@ -0,0 +1,353 @@
+//! Асинхронные задачи аттестации: POST /jobs → id, статус — GET /jobs/{id}
+//!
+//! MPC-TLS сессия и ZK proof занимают десятки секунд — дольше таймаута
+//! прокси хостинга. Задача выполняется в фоне, клиент опрашивает фазу
+//! (фазы выставляет pipeline через progress::Progress).
+//! Хранилище — в памяти процесса: завершённые задачи живут JOB_TTL,
+//! при рестарте сервиса задачи теряются. Когда хранилище заполнено, новая
+//! задача вытесняет самую старую завершённую; у клиента (caller) — не больше
+//! MAX_ACTIVE_PER_CALLER незавершённых задач. Задача видна только своему caller.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::task::AbortHandle;
+
+use crate::webhook::Delivery;
+
+/// Сколько хранится результат завершённой задачи
+const JOB_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Максимум задач в хранилище (выполняющихся и завершённых)
+pub const MAX_JOBS: usize = 256;
+
+/// Максимум незавершённых задач одного caller
+pub const MAX_ACTIVE_PER_CALLER: usize = 4;
+
+/// Почему задача не создана
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitError {
+    /// MAX_JOBS незавершённых задач — вытеснять нечего
+    Full,
+    /// У caller уже MAX_ACTIVE_PER_CALLER незавершённых задач
+    CallerBusy,
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "Выполняется максимум {MAX_JOBS} задач"),
+            Self::CallerBusy => write!(
+                f,
+                "Не больше {MAX_ACTIVE_PER_CALLER} незавершённых задач на клиента"
+            ),
+        }
+    }
+}
+
+/// Фаза задачи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// Принята, ещё не начата
+    Queued,
+    /// MPC-TLS сессия с сервером
+    Mpc,
+    /// Groth16 proof (включая ожидание в очереди пула)
+    Proving,
+    /// Подпись нотариуса
+    Signing,
+    /// Отправка submit_attestation в NEAR (только с submit)
+    Submitting,
+    Done,
+    Failed,
+}
+
+impl Phase {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+/// Время фазы
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    /// Для текущей фазы — время с её начала
+    pub duration_ms: u64,
+}
+
+/// Ошибка задачи — тот же HTTP статус, что вернул бы синхронный /prove
+#[derive(Debug, Clone, Serialize)]
+pub struct JobError {
+    pub status: u16,
+    pub message: String,
+}
+
+/// Ответ GET /jobs/{id}
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub id: String,
+    pub phase: Phase,
+    /// Unix time создания (секунды)
+    pub created_at: u64,
+    pub timings: Vec<PhaseTiming>,
+    /// Ответ /prove (только для done)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JobError>,
+    /// Доставка webhook (только с callbackUrl)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback: Option<Delivery>,
+}
+
+struct Job {
+    /// Клиент, создавший задачу
+    caller: String,
+    phase: Phase,
+    created_at: u64,
+    /// Завершённые фазы
+    timings: Vec<PhaseTiming>,
+    phase_started: Instant,
+    result: Option<serde_json::Value>,
+    error: Option<JobError>,
+    callback: Option<Delivery>,
+    abort: Option<AbortHandle>,
+}
+
+impl Job {
+    fn status(&self, id: &str) -> JobStatus {
+        let mut timings = self.timings.clone();
+        if !self.phase.is_finished() {
+            timings.push(PhaseTiming {
+                phase: self.phase,
+                duration_ms: millis(self.phase_started.elapsed()),
+            });
+        }
+        JobStatus {
+            id: id.to_string(),
+            phase: self.phase,
+            created_at: self.created_at,
+            timings,
+            result: self.result.clone(),
+            error: self.error.clone(),
+            callback: self.callback.clone(),
+        }
+    }
+
+    fn enter(&mut self, phase: Phase) {
+        if self.phase == phase || self.phase.is_finished() {
+            return;
+        }
+        self.timings.push(PhaseTiming {
+            phase: self.phase,
+            duration_ms: millis(self.phase_started.elapsed()),
+        });
+        self.phase = phase;
+        self.phase_started = Instant::now();
+    }
+
+    /// Завершённая задача старше JOB_TTL
+    fn expired(&self) -> bool {
+        self.phase.is_finished() && self.phase_started.elapsed() > JOB_TTL
+    }
+
+    /// Задача этого caller, не истёкшая
+    fn visible_to(&self, caller: &str) -> bool {
+        self.caller == caller && !self.expired()
+    }
+}
+
+#[derive(Default)]
+pub struct JobStore {
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl JobStore {
+    /// Новая задача caller в фазе queued
+    ///
+    /// Заполненное хранилище освобождает место, удаляя завершённую задачу
+    /// с самым ранним временем завершения.
+    pub fn create(&self, caller: &str) -> Result<String, LimitError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|_, job| !job.expired());
+
+        let active = jobs
+            .values()
+            .filter(|job| job.caller == caller && !job.phase.is_finished())
+            .count();
+        if active >= MAX_ACTIVE_PER_CALLER {
+            return Err(LimitError::CallerBusy);
+        }
+        if jobs.len() >= MAX_JOBS {
+            let oldest = jobs
+                .iter()
+                .filter(|(_, job)| job.phase.is_finished())
+                .min_by_key(|(_, job)| job.phase_started)
+                .map(|(id, _)| id.clone())
+                .ok_or(LimitError::Full)?;
+            jobs.remove(&oldest);
+        }
+
+        let id = hex::encode(rand::random::<[u8; 16]>());
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        jobs.insert(
+            id.clone(),
+            Job {
+                caller: caller.to_string(),
+                phase: Phase::Queued,
+                created_at,
+                timings: Vec::new(),
+                phase_started: Instant::now(),
+                result: None,
+                error: None,
+                callback: None,
+                abort: None,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn set_delivery(&self, id: &str, delivery: &Delivery) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            job.callback = Some(delivery.clone());
+        }
+    }
+
+    /// Handle фоновой задачи — для отмены через DELETE
+    /// (после finish задача ещё может доставлять webhook)
+    pub fn attach(&self, id: &str, abort: AbortHandle) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            job.abort = Some(abort);
+        }
+    }
+
+    pub fn set_phase(&self, id: &str, phase: Phase) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            job.enter(phase);
+        }
+    }
+
+    pub fn finish(&self, id: &str, outcome: Result<serde_json::Value, JobError>) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            match outcome {
+                Ok(result) => {
+                    job.enter(Phase::Done);
+                    job.result = Some(result);
+                }
+                Err(error) => {
+                    job.enter(Phase::Failed);
+                    job.error = Some(error);
+                }
+            }
+        }
+    }
+
+    pub fn status(&self, id: &str, caller: &str) -> Option<JobStatus> {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.get(id)
+            .filter(|job| job.visible_to(caller))
+            .map(|job| job.status(id))
+    }
+
+    /// Отмена: выполняющаяся задача (или доставка webhook) прерывается,
+    /// запись удаляется
+    ///
+    /// Прерывание срабатывает на ближайшем await. Native proof считается в
+    /// spawn_blocking и так не прерывается: поток досчитывает proof и держит
+    /// место пула до конца (см. zk_prover::native), результат отбрасывается.
+    pub fn cancel(&self, id: &str, caller: &str) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        if !jobs.get(id).is_some_and(|job| job.visible_to(caller)) {
+            return false;
+        }
+        let Some(job) = jobs.remove(id) else {
+            return false;
+        };
+        if let Some(abort) = job.abort {
+            abort.abort();
+        }
+        true
+    }
+}
+
+fn millis(d: Duration) -> u64 {
+    d.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_timings() {
+        let store = JobStore::default();
+        let id = store.create("a").unwrap();
+        assert_eq!(store.status(&id, "a").unwrap().phase, Phase::Queued);
+
+        store.set_phase(&id, Phase::Mpc);
+        store.set_phase(&id, Phase::Proving);
+        let status = store.status(&id, "a").unwrap();
+        let phases: Vec<Phase> = status.timings.iter().map(|t| t.phase).collect();
+        assert_eq!(phases, [Phase::Queued, Phase::Mpc, Phase::Proving]);
+
+        store.finish(
+            &id,
+            Err(JobError {
+                status: 504,
+                message: "timeout".into(),
+            }),
+        );
+        // После завершения фаза не меняется
+        store.set_phase(&id, Phase::Signing);
+        let status = store.status(&id, "a").unwrap();
+        assert_eq!(status.phase, Phase::Failed);
+        assert_eq!(status.timings.len(), 3);
+        assert_eq!(status.error.unwrap().status, 504);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_task() {
+        let store = JobStore::default();
+        let id = store.create("a").unwrap();
+        let task = tokio::spawn(std::future::pending::<()>());
+        store.attach(&id, task.abort_handle());
+
+        assert!(!store.cancel(&id, "b"));
+        assert!(store.cancel(&id, "a"));
+        assert!(task.await.unwrap_err().is_cancelled());
+        assert!(store.status(&id, "a").is_none());
+        assert!(!store.cancel(&id, "a"));
+    }
+
+    #[test]
+    fn test_limits_evict_oldest_finished() {
+        let store = JobStore::default();
+        for _ in 0..MAX_ACTIVE_PER_CALLER {
+            store.create("a").unwrap();
+        }
+        assert_eq!(store.create("a"), Err(LimitError::CallerBusy));
+
+        // Остальное место — завершённые задачи других клиентов
+        let first = store.create("b").unwrap();
+        store.finish(&first, Ok(serde_json::Value::Null));
+        while store.jobs.lock().unwrap().len() < MAX_JOBS {
+            let id = store.create("b").unwrap();
+            store.finish(&id, Ok(serde_json::Value::Null));
+        }
+
+        // Заполненное хранилище вытесняет самую старую завершённую задачу
+        let id = store.create("c").unwrap();
+        assert!(store.status(&id, "c").is_some());
+        assert!(store.status(&first, "b").is_none());
+        assert_eq!(store.jobs.lock().unwrap().len(), MAX_JOBS);
+    }
+}
@@ -6,22 +6,35 @@
 //!
 //! Порт по умолчанию: 7048
 
+mod disclosure;
+mod jobs;
 mod mpc_session;
+mod near_submit;
+mod progress;
+mod request_body;
+mod secrets;
+mod tlsn_presentation;
 mod url_validator;
+mod webhook;
 mod zk_prover;
 
 use axum::{
-    extract::State,
-    http::{HeaderValue, Method, StatusCode},
+    extract::{ConnectInfo, Path, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    response::sse::{self, KeepAlive, Sse},
     routing::{get, post},
     Json, Router,
 };
+use futures::{Stream, StreamExt};
 use k256::ecdsa::SigningKey;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
 use tower_http::cors::CorsLayer;
 use tracing::{error, info};
 
@@ -32,6 +45,31 @@ struct AppState {
     signing_key: Arc<SigningKey>,
     /// Base64 compressed secp256k1 pubkey
     notary_pubkey_b64: String,
+    /// Асинхронные задачи /jobs
+    jobs: Arc<jobs::JobStore>,
+    /// Отправка submit_attestation (None — NEAR_SIGNER_ACCOUNT не задан)
+    near: Option<near_submit::NearSubmitter>,
+    /// PROVER_API_TOKEN: общий секрет с backend
+    api_token: Option<String>,
+}
+
+/// Заголовок с PROVER_API_TOKEN — так backend подтверждает свои запросы
+const TOKEN_HEADER: &str = "x-prover-token";
+
+impl AppState {
+    /// Запрос от backend: X-Prover-Token совпадает с PROVER_API_TOKEN
+    fn is_backend(&self, headers: &HeaderMap) -> bool {
+        let (Some(token), Some(sent)) = (&self.api_token, headers.get(TOKEN_HEADER)) else {
+            return false;
+        };
+        // Сравнение без раннего выхода — время не выдаёт совпавший префикс
+        token.len() == sent.len()
+            && token
+                .bytes()
+                .zip(sent.as_bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
 }
 
 /// Запрос от backend
@@ -42,8 +80,22 @@ struct ProveRequest {
     url: String,
     /// HTTP метод (по умолчанию GET)
     method: Option<String>,
-    /// Дополнительные заголовки
+    /// Тело запроса: {"type": "text" | "json" | "base64", "value": ...}
+    body: Option<request_body::RequestBody>,
+    /// Content-Type тела (по умолчанию — по типу тела)
+    content_type: Option<String>,
+    /// Дополнительные заголовки (Authorization, Cookie и т.п. отфильтровываются)
     headers: Option<HashMap<String, String>>,
+    /// Секретные заголовки и query-параметры — не раскрываются в аттестации
+    secrets: Option<secrets::RequestSecrets>,
+    /// Правила выборочного раскрытия ответа (по умолчанию — весь ответ)
+    disclose: Option<Vec<disclosure::DisclosureRule>>,
+    /// Webhook для результата (только POST /jobs)
+    callback_url: Option<String>,
+    /// Ключ HMAC-подписи webhook
+    callback_secret: Option<String>,
+    /// Отправить аттестацию в контракт: "direct" | "meta"
+    submit: Option<near_submit::SubmitMode>,
 }
 
 /// Запрос ESPN аттестации
@@ -58,6 +110,40 @@ struct EspnProveRequest {
     league: String,
 }
 
+/// Проверенный ProveRequest — готов к MPC-TLS сессии
+struct ProveJob {
+    url: String,
+    method: String,
+    body: Option<request_body::PreparedBody>,
+    request_hash: Option<[u8; 32]>,
+    headers: Option<HashMap<String, String>>,
+    secrets: Option<secrets::RequestSecrets>,
+    disclose: Option<Vec<disclosure::DisclosureRule>>,
+    callback: Option<webhook::Callback>,
+    submit: Option<near_submit::SubmitMode>,
+}
+
+impl ProveJob {
+    /// /prove и /prove/stream отдают результат в ответе — webhook не нужен
+    fn without_callback(self) -> Result<Self, (StatusCode, String)> {
+        match self.callback {
+            Some(_) => Err((
+                StatusCode::BAD_REQUEST,
+                "callbackUrl поддерживается только в POST /jobs".to_string(),
+            )),
+            None => Ok(self),
+        }
+    }
+}
+
+/// Запрос POST /jobs: {"type": "prove", ...} или {"type": "espn", ...}
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum JobRequest {
+    Prove(Box<ProveRequest>),
+    Espn(EspnProveRequest),
+}
+
 /// Компактные данные ESPN (записываются в response_data)
 #[derive(Serialize, Deserialize)]
 struct EspnCompactData {
@@ -84,6 +170,12 @@ struct ProveResponse {
     server_name: String,
     timestamp: u64,
     response_data: String,
+    /// SHA-256 описания запроса с телом (hex) — аргумент request_hash submit_attestation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_hash: Option<String>,
+    /// Ответ со скрытыми байтами, заменёнными на 'X' (только при disclose)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redacted_response: Option<String>,
     /// Groth16 proof: A point [x, y]
     proof_a: [String; 2],
     /// Groth16 proof: B point [[x1, x2], [y1, y2]]
@@ -96,6 +188,32 @@ struct ProveResponse {
     notary_signature: String,
     /// Recovery ID для ecrecover (0 или 1)
     notary_sig_v: u8,
+    /// tlsn header + открытия коммитментов для submit_tlsn_attestation
+    /// (нет, если response_data — несколько фрагментов ответа)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tlsn_presentation: Option<tlsn_presentation::TlsnPresentation>,
+    /// Результат отправки в NEAR (только с submit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submission: Option<near_submit::Submission>,
+}
+
+impl ProveResponse {
+    /// Аргументы submit_attestation (JSON, как во frontend)
+    fn submit_args(&self) -> serde_json::Value {
+        serde_json::json!({
+            "source_url": self.source_url,
+            "server_name": self.server_name,
+            "timestamp": self.timestamp,
+            "response_data": self.response_data,
+            "proof_a": self.proof_a,
+            "proof_b": self.proof_b,
+            "proof_c": self.proof_c,
+            "public_signals": self.public_signals,
+            "notary_signature": self.notary_signature,
+            "notary_sig_v": self.notary_sig_v,
+            "request_hash": self.request_hash,
+        })
+    }
 }
 
 /// Информация о нотариусе
@@ -143,23 +261,116 @@ async fn notary_info_full(State(state): State<Arc<AppState>>) -> Json<serde_json
 /// 4. Возврат attestation data + proof + public signals
 async fn prove(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(req): Json<ProveRequest>,
 ) -> Result<Json<ProveResponse>, (StatusCode, String)> {
-    info!("Запрос MPC-TLS аттестации: {}", req.url);
+    let job = req
+        .validate(&state, state.is_backend(&headers))?
+        .without_callback()?;
+    run_prove(&state, job, &progress::Progress::none()).await.map(Json)
+}
 
-    // 1. SSRF-защита
-    url_validator::validate_url(&req.url).map_err(|e| {
-        (StatusCode::BAD_REQUEST, format!("URL невалиден: {e}"))
-    })?;
+impl ProveRequest {
+    /// Проверки до MPC-TLS сессии — ошибки сразу 400, в том числе для /jobs
+    ///
+    /// submit тратит газ и комиссию signer — только для запросов backend
+    /// (from_backend: X-Prover-Token).
+    fn validate(
+        self,
+        state: &AppState,
+        from_backend: bool,
+    ) -> Result<ProveJob, (StatusCode, String)> {
+        info!("Запрос MPC-TLS аттестации: {}", self.url);
+
+        // 1. SSRF-защита
+        url_validator::validate_url(&self.url).map_err(|e| {
+            (StatusCode::BAD_REQUEST, format!("URL невалиден: {e}"))
+        })?;
+
+        let method = self.method.unwrap_or_else(|| "GET".to_string());
+
+        if let Some(rules) = &self.disclose {
+            if rules.is_empty() || rules.len() > disclosure::MAX_RULES {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("disclose: от 1 до {} правил", disclosure::MAX_RULES),
+                ));
+            }
+        }
+
+        let body = self
+            .body
+            .as_ref()
+            .map(|b| b.prepare(self.content_type.as_deref()))
+            .transpose()
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("body: {e}")))?;
+        let request_hash = body.as_ref().map(|b| b.request_hash(&method));
+
+        let headers = self.headers.as_ref().map(url_validator::filter_headers);
+        let secrets = self.secrets.filter(|s| !s.is_empty());
+        if let Some(s) = &secrets {
+            s.validate()
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("secrets: {e}")))?;
+        }
+
+        let callback = match (self.callback_url, self.callback_secret) {
+            (Some(url), Some(secret)) => Some(
+                webhook::Callback::new(url, secret)
+                    .map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+            ),
+            (None, None) => None,
+            _ => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "callbackUrl и callbackSecret передаются вместе".to_string(),
+                ))
+            }
+        };
 
-    let method = req.method.unwrap_or_else(|| "GET".to_string());
+        if self.submit.is_some() && state.near.is_none() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "submit: отправка в NEAR не настроена (NEAR_SIGNER_ACCOUNT)".to_string(),
+            ));
+        }
+        if self.submit.is_some() && !from_backend {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "submit: только для запросов backend (X-Prover-Token)".to_string(),
+            ));
+        }
 
+        Ok(ProveJob {
+            url: self.url,
+            method,
+            body,
+            request_hash,
+            headers,
+            secrets,
+            disclose: self.disclose,
+            callback,
+            submit: self.submit,
+        })
+    }
+}
+
+/// Pipeline /prove после валидации: MPC-TLS → ZK proof → подпись
+async fn run_prove(
+    state: &AppState,
+    job: ProveJob,
+    progress: &progress::Progress,
+) -> Result<ProveResponse, (StatusCode, String)> {
     // 2. MPC-TLS сессия
+    progress.set(jobs::Phase::Mpc);
     let session_result = mpc_session::run(
         state.signing_key.clone(),
-        &req.url,
-        &method,
-        req.headers,
+        &job.url,
+        &job.method,
+        job.headers,
+        job.body.as_ref(),
+        job.secrets.as_ref(),
+        job.disclose.as_deref(),
+        progress,
     )
     .await
     .map_err(|e| {
@@ -177,15 +388,10 @@ async fn prove(
     );
 
     // 3. Генерация ZK proof
-    let zk_result = zk_prover::generate_proof(&session_result)
+    progress.set(jobs::Phase::Proving);
+    let zk_result = zk_prover::generate_proof(&session_result, progress)
         .await
-        .map_err(|e| {
-            error!("ZK proof ошибка: {e:#}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("ZK proof ошибка: {e}"),
-            )
-        })?;
+        .map_err(zk_error)?;
 
     info!(
         "ZK proof сгенерирован: dataCommitment={}...",
@@ -193,28 +399,49 @@ async fn prove(
     );
 
     // 4. Подпись нотариуса (secp256k1 ECDSA)
+    progress.set(jobs::Phase::Signing);
     let (notary_signature, notary_sig_v) = mpc_session::sign_attestation_data(
         &state.signing_key,
         &session_result.source_url,
         &session_result.server_name,
         session_result.timestamp,
         &session_result.response_data,
+        job.request_hash.as_ref(),
     );
 
     info!("Подпись нотариуса: sig={}...  v={}", &notary_signature[..16], notary_sig_v);
 
-    Ok(Json(ProveResponse {
+    let mut response = ProveResponse {
         source_url: session_result.source_url,
         server_name: session_result.server_name,
         timestamp: session_result.timestamp,
         response_data: session_result.response_data,
+        request_hash: job.request_hash.map(hex::encode),
+        redacted_response: session_result.redacted_response,
         proof_a: zk_result.proof_a,
         proof_b: zk_result.proof_b,
         proof_c: zk_result.proof_c,
         public_signals: zk_result.public_signals,
         notary_signature,
         notary_sig_v,
-    }))
+        tlsn_presentation: session_result.tlsn_presentation,
+        submission: None,
+    };
+
+    // 5. Отправка в NEAR — ошибка не отменяет аттестацию, она в submission.error
+    if let (Some(mode), Some(near)) = (job.submit, &state.near) {
+        progress.set(jobs::Phase::Submitting);
+        let submission = near.submit(&response.submit_args(), mode).await;
+        if submission.error.is_none() {
+            progress.emit(progress::Event::Submitted {
+                tx_hash: submission.tx_hash.clone(),
+                attestation_id: submission.attestation_id,
+            });
+        }
+        response.submission = Some(submission);
+    }
+
+    Ok(response)
 }
 
 /// POST /prove-espn — MPC-TLS аттестация ESPN данных с извлечением scores
@@ -227,24 +454,48 @@ async fn prove_espn(
     State(state): State<Arc<AppState>>,
     Json(req): Json<EspnProveRequest>,
 ) -> Result<Json<ProveResponse>, (StatusCode, String)> {
-    let url = format!(
-        "https://site.api.espn.com/apis/site/v2/sports/{}/{}/summary?event={}",
-        req.sport, req.league, req.espn_event_id
-    );
+    let url = req.validate()?;
+    run_prove_espn(&state, &url, &req.espn_event_id, &progress::Progress::none())
+        .await
+        .map(Json)
+}
 
-    info!("Запрос ESPN MPC-TLS аттестации: {} (event {})", url, req.espn_event_id);
+impl EspnProveRequest {
+    /// ESPN URL из параметров, прошедший SSRF-проверку
+    fn validate(&self) -> Result<String, (StatusCode, String)> {
+        let url = format!(
+            "https://site.api.espn.com/apis/site/v2/sports/{}/{}/summary?event={}",
+            self.sport, self.league, self.espn_event_id
+        );
 
-    // 1. SSRF-защита
-    url_validator::validate_url(&url).map_err(|e| {
-        (StatusCode::BAD_REQUEST, format!("URL невалиден: {e}"))
-    })?;
+        info!("Запрос ESPN MPC-TLS аттестации: {} (event {})", url, self.espn_event_id);
 
+        // 1. SSRF-защита
+        url_validator::validate_url(&url).map_err(|e| {
+            (StatusCode::BAD_REQUEST, format!("URL невалиден: {e}"))
+        })?;
+        Ok(url)
+    }
+}
+
+/// Pipeline /prove-espn после валидации
+async fn run_prove_espn(
+    state: &AppState,
+    url: &str,
+    espn_event_id: &str,
+    progress: &progress::Progress,
+) -> Result<ProveResponse, (StatusCode, String)> {
     // 2. MPC-TLS сессия
+    progress.set(jobs::Phase::Mpc);
     let session_result = mpc_session::run(
         state.signing_key.clone(),
-        &url,
+        url,
         "GET",
         None,
+        None,
+        None,
+        None,
+        progress,
     )
     .await
     .map_err(|e| {
@@ -262,7 +513,7 @@ async fn prove_espn(
     );
 
     // 3. Парсим ESPN JSON → компактный формат
-    let compact = extract_espn_scores(&session_result.response_data, &req.espn_event_id)
+    let compact = extract_espn_scores(&session_result.response_data, espn_event_id)
         .map_err(|e| {
             error!("ESPN парсинг ошибка: {e}");
             (
@@ -282,15 +533,10 @@ async fn prove_espn(
     session_for_zk.response_data = compact_json;
 
     // 5. Генерация ZK proof
-    let zk_result = zk_prover::generate_proof(&session_for_zk)
+    progress.set(jobs::Phase::Proving);
+    let zk_result = zk_prover::generate_proof(&session_for_zk, progress)
         .await
-        .map_err(|e| {
-            error!("ZK proof ошибка: {e:#}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("ZK proof ошибка: {e}"),
-            )
-        })?;
+        .map_err(zk_error)?;
 
     info!(
         "ZK proof сгенерирован: dataCommitment={}...",
@@ -298,28 +544,240 @@ async fn prove_espn(
     );
 
     // 6. Подпись нотариуса по компактным данным
+    progress.set(jobs::Phase::Signing);
     let (notary_signature, notary_sig_v) = mpc_session::sign_attestation_data(
         &state.signing_key,
         &session_for_zk.source_url,
         &session_for_zk.server_name,
         session_for_zk.timestamp,
         &session_for_zk.response_data,
+        None,
     );
 
     info!("Подпись нотариуса (ESPN): sig={}...  v={}", &notary_signature[..16], notary_sig_v);
 
-    Ok(Json(ProveResponse {
+    Ok(ProveResponse {
         source_url: session_for_zk.source_url,
         server_name: session_for_zk.server_name,
         timestamp: session_for_zk.timestamp,
         response_data: session_for_zk.response_data,
+        request_hash: None,
+        redacted_response: None,
         proof_a: zk_result.proof_a,
         proof_b: zk_result.proof_b,
         proof_c: zk_result.proof_c,
         public_signals: zk_result.public_signals,
         notary_signature,
         notary_sig_v,
-    }))
+        tlsn_presentation: session_for_zk.tlsn_presentation,
+        submission: None,
+    })
+}
+
+// ── Асинхронные задачи ───────────────────────────────────────
+
+/// Клиент, от имени которого backend создаёт задачи (аккаунт API-ключа)
+const CALLER_HEADER: &str = "x-caller-id";
+
+/// Владелец задачи: X-Caller-Id от backend (с X-Prover-Token), иначе IP клиента
+///
+/// Без токена заголовок игнорируется: иначе клиент обходил бы лимит задач
+/// сменой id и читал бы чужие задачи, подставив id владельца.
+fn caller_id(state: &AppState, headers: &HeaderMap, peer: SocketAddr) -> String {
+    state
+        .is_backend(headers)
+        .then(|| headers.get(CALLER_HEADER))
+        .flatten()
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map_or_else(|| peer.ip().to_string(), |v| format!("caller:{v}"))
+}
+
+/// POST /jobs — запустить /prove или /prove-espn в фоне
+///
+/// Валидация — сразу (400), дальше 202 с id задачи в фазе queued.
+/// С callbackUrl результат после завершения POST-ится на него (webhook).
+/// Лимиты хранилища и caller — 503 / 429.
+async fn create_job(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<JobRequest>,
+) -> Result<(StatusCode, Json<jobs::JobStatus>), (StatusCode, String)> {
+    enum Validated {
+        Prove(Box<ProveJob>),
+        Espn { url: String, event_id: String },
+    }
+    let validated = match req {
+        JobRequest::Prove(req) => Validated::Prove(Box::new(
+            req.validate(&state, state.is_backend(&headers))?,
+        )),
+        JobRequest::Espn(req) => Validated::Espn {
+            url: req.validate()?,
+            event_id: req.espn_event_id,
+        },
+    };
+
+    let caller = caller_id(&state, &headers, peer);
+    let id = state.jobs.create(&caller).map_err(|e| {
+        let status = match e {
+            jobs::LimitError::Full => StatusCode::SERVICE_UNAVAILABLE,
+            jobs::LimitError::CallerBusy => StatusCode::TOO_MANY_REQUESTS,
+        };
+        (status, e.to_string())
+    })?;
+    let progress = progress::Progress::job(state.jobs.clone(), id.clone());
+    let callback = match &validated {
+        Validated::Prove(job) => job.callback.clone(),
+        Validated::Espn { .. } => None,
+    };
+
+    let task = {
+        let state = state.clone();
+        let id = id.clone();
+        tokio::spawn(async move {
+            let result = match validated {
+                Validated::Prove(job) => run_prove(&state, *job, &progress).await,
+                Validated::Espn { url, event_id } => {
+                    run_prove_espn(&state, &url, &event_id, &progress).await
+                }
+            };
+            let outcome = result
+                .map(|resp| serde_json::to_value(resp).expect("ProveResponse сериализуется"))
+                .map_err(|(status, message)| jobs::JobError {
+                    status: status.as_u16(),
+                    message,
+                });
+            state.jobs.finish(&id, outcome.clone());
+
+            if let Some(callback) = callback {
+                let (event, payload) = match outcome {
+                    Ok(resp) => (webhook::Outcome::Done, resp),
+                    Err(error) => (
+                        webhook::Outcome::Failed,
+                        serde_json::json!({ "error": error }),
+                    ),
+                };
+                state.jobs.set_delivery(&id, &webhook::Delivery::pending());
+                webhook::deliver(&callback, &id, event, &payload, |delivery| {
+                    state.jobs.set_delivery(&id, delivery)
+                })
+                .await;
+            }
+        })
+    };
+    state.jobs.attach(&id, task.abort_handle());
+    info!("Задача {id} создана");
+
+    let status = state.jobs.status(&id, &caller).ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Задача удалена до ответа".to_string(),
+    ))?;
+    Ok((StatusCode::ACCEPTED, Json(status)))
+}
+
+/// GET /jobs/{id} — фаза, время фаз, результат или ошибка
+async fn job_status(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<jobs::JobStatus>, StatusCode> {
+    let caller = caller_id(&state, &headers, peer);
+    state
+        .jobs
+        .status(&id, &caller)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// DELETE /jobs/{id} — отменить задачу (или удалить результат)
+async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> StatusCode {
+    if state.jobs.cancel(&id, &caller_id(&state, &headers, peer)) {
+        info!("Задача {id} отменена");
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+// ── Поток прогресса ──────────────────────────────────────────
+
+/// Прерывает pipeline, если клиент закрыл SSE-соединение
+struct AbortOnDrop(tokio::task::AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// POST /prove/stream — то же, что /prove, но ответ — Server-Sent Events
+///
+/// event: progress — progress::Event по мере шагов MPC-TLS и ZK proof;
+/// последним — event: result (ProveResponse) или event: error ({status, message}).
+async fn prove_stream(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ProveRequest>,
+) -> Result<Sse<impl Stream<Item = Result<sse::Event, Infallible>>>, (StatusCode, String)> {
+    let job = req
+        .validate(&state, state.is_backend(&headers))?
+        .without_callback()?;
+
+    let (events_tx, events_rx) = mpsc::unbounded_channel();
+    let (done_tx, done_rx) = oneshot::channel();
+    let task = tokio::spawn(async move {
+        let progress = progress::Progress::stream(events_tx);
+        let result = run_prove(&state, job, &progress).await;
+        // Сначала закрывается канал событий — result всегда последний
+        drop(progress);
+        let _ = done_tx.send(result);
+    });
+    let guard = AbortOnDrop(task.abort_handle());
+
+    let progress_events = futures::stream::unfold(events_rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((sse_json("progress", &event), rx))
+    });
+    let final_event = futures::stream::once(async move {
+        let _guard = guard;
+        match done_rx.await {
+            Ok(Ok(resp)) => sse_json("result", &resp),
+            Ok(Err((status, message))) => sse_json(
+                "error",
+                &serde_json::json!({ "status": status.as_u16(), "message": message }),
+            ),
+            Err(_) => sse_json(
+                "error",
+                &serde_json::json!({ "status": 500, "message": "Pipeline прерван" }),
+            ),
+        }
+    });
+
+    Ok(Sse::new(progress_events.chain(final_event)).keep_alive(KeepAlive::default()))
+}
+
+fn sse_json(name: &str, data: &impl Serialize) -> Result<sse::Event, Infallible> {
+    Ok(sse::Event::default()
+        .event(name)
+        .data(serde_json::to_string(data).expect("SSE данные сериализуются")))
+}
+
+/// Ошибка ZK proof → HTTP: переполненная очередь — 503, таймаут — 504
+fn zk_error(e: anyhow::Error) -> (StatusCode, String) {
+    let status = match e.downcast_ref::<zk_prover::pool::PoolError>() {
+        Some(zk_prover::pool::PoolError::QueueFull) => StatusCode::SERVICE_UNAVAILABLE,
+        Some(zk_prover::pool::PoolError::Timeout(_)) => StatusCode::GATEWAY_TIMEOUT,
+        None => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    error!("ZK proof ошибка: {e:#}");
+    (status, format!("ZK proof ошибка: {e}"))
 }
 
 /// Извлекает компактные данные ESPN из полного JSON ответа summary endpoint
@@ -441,11 +899,27 @@ async fn main() {
 
     info!("Notary pubkey (secp256k1): {notary_pubkey_b64}");
 
+    let near = near_submit::NearSubmitter::from_env()
+        .expect("Невалидная конфигурация NEAR отправки");
+    let api_token = std::env::var("PROVER_API_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty());
+    // Без токена backend не смог бы запросить submit
+    assert!(
+        near.is_none() || api_token.is_some(),
+        "NEAR_SIGNER_ACCOUNT требует PROVER_API_TOKEN (общий секрет с backend)"
+    );
+
     let state = Arc::new(AppState {
         signing_key: Arc::new(signing_key),
         notary_pubkey_b64,
+        jobs: Arc::new(jobs::JobStore::default()),
+        near,
+        api_token,
     });
 
+    zk_prover::warm_up();
+
     // CORS: только разрешённый origin
     let allowed_origin = std::env::var("ALLOWED_ORIGIN")
         .unwrap_or_else(|_| "http://127.0.0.1:4001".to_string());
@@ -456,7 +930,7 @@ async fn main() {
                 .parse::<HeaderValue>()
                 .expect("ALLOWED_ORIGIN должен быть валидным"),
         )
-        .allow_methods([Method::GET, Method::POST])
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
         .allow_headers([axum::http::header::CONTENT_TYPE]);
 
     let app = Router::new()
@@ -464,7 +938,10 @@ async fn main() {
         .route("/notary-info", get(notary_info))
         .route("/notary-info-full", get(notary_info_full))
         .route("/prove", post(prove))
+        .route("/prove/stream", post(prove_stream))
         .route("/prove-espn", post(prove_espn))
+        .route("/jobs", post(create_job))
+        .route("/jobs/:id", get(job_status).delete(cancel_job))
         .layer(cors)
         .with_state(state);
 
@@ -472,7 +949,12 @@ async fn main() {
     info!("Prover Service (MPC-TLS + ZK) запущен на {addr}");
 
     let listener = TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 /// Загружает secp256k1 ключ из файла или генерирует новый
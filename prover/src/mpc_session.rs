@@ -6,11 +6,12 @@
 
 use anyhow::{Context, Result};
 use futures::AsyncWriteExt;
-use http_body_util::{BodyExt, Empty};
+use http_body_util::{BodyExt, Full};
 use hyper::{body::Bytes, Request, StatusCode};
 use hyper_util::rt::TokioIo;
 use k256::ecdsa::SigningKey;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::oneshot;
@@ -27,25 +28,50 @@ use tlsn::config::tls_commit::TlsCommitConfig;
 use tlsn::config::verifier::VerifierConfig;
 use tlsn::webpki::RootCertStore;
 use tlsn::connection::{ConnectionInfo, HandshakeData, ServerName, TranscriptLength};
+use tlsn::hash::HashAlgId;
 use tlsn::prover::ProverOutput;
-use tlsn::transcript::ContentType;
+use tlsn::transcript::{ContentType, TranscriptCommitConfig, TranscriptCommitmentKind};
 use tlsn::verifier::VerifierOutput;
 use tlsn::Session;
 
+use crate::disclosure::{self, DisclosureRule};
+use crate::progress::{Event, Progress};
+use crate::request_body::PreparedBody;
+use crate::secrets::RequestSecrets;
+use crate::tlsn_presentation::{self, TlsnPresentation};
+
+/// Лимит отправленных данных MPC-TLS (request line + заголовки + тело)
+pub const MAX_SENT_DATA: usize = 4096;
+
 /// Результат MPC-TLS сессии
 pub struct SessionResult {
-    /// URL источника
+    /// URL источника (значения секретных query-параметров — PLACEHOLDER)
     pub source_url: String,
     /// DNS-имя сервера
     pub server_name: String,
     /// UNIX timestamp (секунды)
     pub timestamp: u64,
-    /// Тело HTTP-ответа (расшифрованное)
+    /// Тело HTTP-ответа (расшифрованное); при выборочном раскрытии —
+    /// раскрытые фрагменты (Disclosure::response_data)
     pub response_data: String,
+    /// Полученный транскрипт со скрытыми частями, заменёнными на 'X'
+    /// (только при выборочном раскрытии)
+    pub redacted_response: Option<String>,
     /// Сериализованная attestation (bincode -> base64)
     pub attestation_b64: String,
     /// Публичный ключ нотариуса (secp256k1 compressed, base64)
     pub notary_pubkey_b64: String,
+    /// Header + открытия коммитментов для submit_tlsn_attestation; None —
+    /// response_data не совпадает с одним диапазоном ответа (несколько фрагментов)
+    pub tlsn_presentation: Option<TlsnPresentation>,
+}
+
+/// Подписанная нотариусом attestation + данные для on-chain presentation
+struct Notarized {
+    attestation: Attestation,
+    connection_info: ConnectionInfo,
+    /// Подпись нотариуса над server_name, проверенным в MPC-TLS
+    server_identity_signature: String,
 }
 
 /// Запускает полную MPC-TLS сессию
@@ -55,14 +81,29 @@ pub struct SessionResult {
 /// 3. Prover подключается к целевому серверу через MPC-TLS
 /// 4. Выполняет HTTP-запрос
 /// 5. Генерирует proof и получает attestation через oneshot каналы
+///
+/// body — тело запроса (POST / PUT / GraphQL);
+/// secrets — заголовки и query-параметры, скрываемые из отправленного транскрипта;
+/// disclosure — правила выборочного раскрытия ответа (None — раскрывается весь ответ);
+/// progress — события шагов сессии (Connected … AttestationSigned)
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     signing_key: Arc<SigningKey>,
     url: &str,
     method: &str,
     headers: Option<HashMap<String, String>>,
+    body: Option<&PreparedBody>,
+    secrets: Option<&RequestSecrets>,
+    disclosure: Option<&[DisclosureRule]>,
+    progress: &Progress,
 ) -> Result<SessionResult> {
-    // Парсим URL
+    // Парсим URL; секретные query-параметры есть только в запросе,
+    // в source_url — PLACEHOLDER
     let parsed_url = url::Url::parse(url).context("Неверный URL")?;
+    let (parsed_url, source_url) = match secrets {
+        Some(s) => s.apply_query(&parsed_url),
+        None => (parsed_url, url.to_string()),
+    };
     let host = parsed_url
         .host_str()
         .context("URL без хоста")?
@@ -74,14 +115,50 @@ pub async fn run(
         parsed_url.path().to_string()
     };
 
-    info!("MPC-TLS сессия: {} ({}:{}{})", url, host, port, path);
+    info!("MPC-TLS сессия: {} ({}:{})", source_url, host, port);
+
+    // HTTP-запрос собирается до MPC-TLS: размер проверяется заранее
+    let mut req_builder = Request::builder()
+        .method(method)
+        .uri(&path)
+        .header("Host", &host)
+        .header("User-Agent", "TLSOracle/1.0")
+        .header("Accept", "application/json")
+        .header("Connection", "close");
+
+    // Добавляем пользовательские заголовки (если переданы)
+    if let Some(ref hdrs) = headers {
+        for (k, v) in hdrs {
+            req_builder = req_builder.header(k.as_str(), v.as_str());
+        }
+    }
+    if let Some(s) = secrets {
+        for (k, v) in &s.headers {
+            req_builder = req_builder.header(k.as_str(), v.as_str());
+        }
+    }
+
+    let body = match body {
+        Some(b) => {
+            req_builder = req_builder.header("Content-Type", &b.content_type);
+            Bytes::from(b.bytes.clone())
+        }
+        None => Bytes::new(),
+    };
+    let request = req_builder.body(Full::new(body))?;
+
+    let sent_size = estimate_request_size(&request);
+    anyhow::ensure!(
+        sent_size <= MAX_SENT_DATA,
+        "Запрос ~{sent_size} байт превышает лимит MPC-TLS {MAX_SENT_DATA} байт"
+    );
 
     // 1. Создаём duplex канал (Prover <-> Verifier)
     let (prover_io, verifier_io) = tokio::io::duplex(1 << 16); // 64KB buffer
 
     // Каналы для attestation (вне MPC-сессии)
     let (req_tx, req_rx) = oneshot::channel::<AttestationRequest>();
-    let (att_tx, att_rx) = oneshot::channel::<Attestation>();
+    let (att_tx, att_rx) = oneshot::channel::<Notarized>();
 
     // 2. Запускаем Verifier (Notary) в фоне
     let signing_key_clone = signing_key.clone();
@@ -106,7 +183,7 @@ pub async fn run(
             TlsCommitConfig::builder()
                 .protocol(
                     MpcTlsConfig::builder()
-                        .max_sent_data(4096)
+                        .max_sent_data(MAX_SENT_DATA)
                         .max_recv_data(524288) // 512 KB — ESPN summary ~400 KB
                         .build()?,
                 )
@@ -119,6 +196,9 @@ pub async fn run(
     let target_socket = tokio::net::TcpStream::connect(format!("{host}:{port}"))
         .await
         .context(format!("Не удалось подключиться к {host}:{port}"))?;
+    progress.emit(Event::Connected {
+        server: host.clone(),
+    });
 
     let tls_config = TlsClientConfig::builder()
         .server_name(ServerName::Dns(
@@ -134,6 +214,7 @@ pub async fn run(
         .connect(tls_config, target_socket.compat())
         .await
         .context("Ошибка MPC-TLS connect")?;
+    progress.emit(Event::HandshakeDone);
 
     let tls_connection = TokioIo::new(tls_connection.compat());
 
@@ -145,23 +226,6 @@ pub async fn run(
         hyper::client::conn::http1::handshake(tls_connection).await?;
     tokio::spawn(connection);
 
-    let mut req_builder = Request::builder()
-        .method(method)
-        .uri(&path)
-        .header("Host", &host)
-        .header("User-Agent", "TLSOracle/1.0")
-        .header("Accept", "application/json")
-        .header("Connection", "close");
-
-    // Добавляем пользовательские заголовки (если переданы)
-    if let Some(ref hdrs) = headers {
-        for (k, v) in hdrs {
-            req_builder = req_builder.header(k.as_str(), v.as_str());
-        }
-    }
-
-    let request = req_builder.body(Empty::<Bytes>::new())?;
-
     let response = request_sender
         .send_request(request)
         .await
@@ -174,13 +238,12 @@ pub async fn run(
         .await
         .context("Чтение тела ответа")?
         .to_bytes();
-    let response_data = String::from_utf8_lossy(&body_bytes).to_string();
+    let mut response_data = String::from_utf8_lossy(&body_bytes).to_string();
 
     if status != StatusCode::OK {
-        warn!(
-            "HTTP ответ {status}: {}",
-            &response_data[..200.min(response_data.len())]
-        );
+        let preview: String = response_data.chars().take(200).collect();
+        let preview = secrets.map_or(preview.clone(), |s| s.mask(&preview));
+        warn!("HTTP ответ {status}: {preview}");
     }
 
     info!(
@@ -188,25 +251,92 @@ pub async fn run(
         status,
         response_data.len()
     );
+    progress.emit(Event::ResponseReceived {
+        status: status.as_u16(),
+        bytes: body_bytes.len(),
+    });
 
     // 7. Завершаем MPC-TLS и генерируем proof
     let mut prover = prover_task
         .await?
         .context("Prover MPC-TLS ошибка")?;
 
-    // Раскрываем весь транскрипт (для MVP — full disclosure)
     let transcript = prover.transcript();
     let mut prove_config = tlsn::config::prove::ProveConfig::builder(transcript);
     prove_config.server_identity();
 
-    // Раскрываем все отправленные и полученные данные
+    // Отправленные данные раскрываются целиком, кроме значений секретов;
+    // полученные — целиком или только диапазоны правил раскрытия
     let sent_len = transcript.sent().len();
     let recv_len = transcript.received().len();
-    if sent_len > 0 {
-        prove_config.reveal_sent(&(0..sent_len))?;
+    let revealed_sent = match secrets {
+        Some(s) => s.sent_reveal_ranges(transcript.sent()),
+        None if sent_len > 0 => vec![0..sent_len],
+        None => Vec::new(),
+    };
+    let disclosure = disclosure
+        .map(|rules| disclosure::resolve(transcript.received(), rules))
+        .transpose()
+        .context("Правила выборочного раскрытия")?;
+    let (revealed_recv, hidden_recv) = match &disclosure {
+        Some(d) => (d.revealed.clone(), d.hidden(recv_len)),
+        None if recv_len > 0 => (vec![0..recv_len], Vec::new()),
+        None => (Vec::new(), Vec::new()),
+    };
+    // Тело ответа коммитится отдельным диапазоном: submit_tlsn_attestation
+    // требует, чтобы response_data совпал с открытым диапазоном целиком
+    let body_range = disclosure
+        .is_none()
+        .then(|| body_range(transcript.received(), &body_bytes))
+        .flatten();
+
+    for range in &revealed_sent {
+        prove_config.reveal_sent(range)?;
+    }
+    for range in &revealed_recv {
+        prove_config.reveal_recv(range)?;
     }
-    if recv_len > 0 {
-        prove_config.reveal_recv(&(0..recv_len))?;
+
+    // SHA-256 hash-коммитменты — попадают в Body attestation. Раскрытые
+    // диапазоны открываются on-chain (submit_tlsn_attestation), скрытые
+    // части ответа остаются только хешами. Секреты не коммитятся вовсе
+    let mut commit_config = TranscriptCommitConfig::builder(transcript);
+    commit_config.default_kind(TranscriptCommitmentKind::Hash {
+        alg: HashAlgId::SHA256,
+    });
+    for range in &revealed_sent {
+        commit_config.commit_sent(range)?;
+    }
+    for range in revealed_recv.iter().chain(&hidden_recv).chain(&body_range) {
+        commit_config.commit_recv(range)?;
+    }
+    prove_config.transcript_commit(commit_config.build()?);
+
+    let redacted_response = disclosure.as_ref().map(|d| {
+        info!(
+            "Выборочное раскрытие: {} диапазонов, скрыто {} байт",
+            d.revealed.len(),
+            hidden_recv.iter().map(|r| r.len()).sum::<usize>()
+        );
+        response_data = d.response_data();
+        d.redact(transcript.received())
+    });
+
+    // Сервер может вернуть секрет в ответе (echo заголовков, redirect с
+    // api_key). Раскрытые диапазоны уходят нотариусу и on-chain как есть,
+    // а response_data должен с ними совпадать — замаскировать значение нельзя,
+    // поэтому такая аттестация не создаётся
+    if let Some(s) = secrets {
+        let echoed = revealed_recv
+            .iter()
+            .map(|r| &transcript.received()[r.clone()])
+            .chain([response_data.as_bytes()])
+            .find_map(|data| s.echoed_in(data));
+        if let Some(name) = echoed {
+            anyhow::bail!(
+                "Ответ сервера содержит значение секрета {name} — скройте его правилами disclose"
+            );
+        }
     }
 
     let ProverOutput {
@@ -214,6 +344,11 @@ pub async fn run(
         transcript_secrets,
         ..
     } = prover.prove(&prove_config.build()?).await?;
+    progress.emit(Event::TranscriptProved);
+
+    // Нужны после подписи для открытия коммитментов
+    let commitments_for_presentation = transcript_commitments.clone();
+    let secrets_for_presentation = transcript_secrets.clone();
 
     // Получаем данные для attestation request
     let transcript = prover.transcript().clone();
@@ -240,7 +375,7 @@ pub async fn run(
                 .clone(),
             binding: tls_transcript.certificate_binding().clone(),
         })
-        .transcript(transcript)
+        .transcript(transcript.clone())
         .transcript_commitments(transcript_secrets, transcript_commitments);
 
     let (att_request, _secrets) = att_builder.build(&CryptoProvider::default())?;
@@ -251,15 +386,35 @@ pub async fn run(
         .map_err(|_| anyhow::anyhow!("Verifier не принимает attestation request"))?;
 
     // Получаем подписанную attestation
-    let attestation = att_rx
+    let Notarized {
+        attestation,
+        connection_info,
+        server_identity_signature,
+    } = att_rx
         .await
         .context("Verifier не вернул attestation")?;
+    progress.emit(Event::AttestationSigned);
 
     // Закрываем сессию
     handle.close();
     let _ = driver_task.await;
     let _ = verifier_task.await;
 
+    // Presentation для on-chain верификации tlsn attestation
+    let presented_recv: Vec<_> = revealed_recv.iter().chain(&body_range).cloned().collect();
+    let tlsn_presentation = tlsn_presentation::build(
+        &attestation,
+        &connection_info,
+        server_identity_signature,
+        &transcript,
+        &commitments_for_presentation,
+        &secrets_for_presentation,
+        &presented_recv,
+        &response_data,
+    )
+    .inspect_err(|e| warn!("tlsn presentation не собрана: {e:#}"))
+    .ok();
+
     // Сериализуем attestation
     let attestation_bytes = bincode::serialize(&attestation)?;
     let attestation_b64 =
@@ -277,12 +432,14 @@ pub async fn run(
         .as_secs();
 
     Ok(SessionResult {
-        source_url: url.to_string(),
+        source_url,
         server_name: host,
         timestamp,
         response_data,
+        redacted_response,
         attestation_b64,
         notary_pubkey_b64,
+        tlsn_presentation,
     })
 }
 
@@ -291,7 +448,7 @@ async fn run_verifier<T: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
     io: T,
     signing_key: Arc<SigningKey>,
     req_rx: oneshot::Receiver<AttestationRequest>,
-    att_tx: oneshot::Sender<Attestation>,
+    att_tx: oneshot::Sender<Notarized>,
 ) -> Result<()> {
     info!("Verifier: запуск");
 
@@ -316,12 +473,19 @@ async fn run_verifier<T: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
     // Верифицируем proof от Prover
     let (
         VerifierOutput {
+            server_name,
             transcript_commitments,
             ..
         },
         verifier,
     ) = verifier.verify().await?.accept().await?;
 
+    // Prover раскрывает server identity (prove_config.server_identity()),
+    // цепочка сертификатов проверена по root_store
+    let server_name = match server_name.context("Prover не раскрыл server identity")? {
+        ServerName::Dns(name) => name.as_str().to_string(),
+    };
+
     let tls_transcript = verifier.tls_transcript().clone();
     verifier.close().await?;
 
@@ -368,24 +532,38 @@ async fn run_verifier<T: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
         .supported_signature_algs(Vec::from_iter(provider.signer.supported_algs()));
     let att_config = att_config_builder.build()?;
 
+    let connection_info = ConnectionInfo {
+        time: tls_transcript.time(),
+        version: (*tls_transcript.version()),
+        transcript_length: TranscriptLength {
+            sent: sent_len as u32,
+            received: recv_len as u32,
+        },
+    };
+
     let mut builder = Attestation::builder(&att_config).accept_request(request)?;
     builder
-        .connection_info(ConnectionInfo {
-            time: tls_transcript.time(),
-            version: (*tls_transcript.version()),
-            transcript_length: TranscriptLength {
-                sent: sent_len as u32,
-                received: recv_len as u32,
-            },
-        })
+        .connection_info(connection_info.clone())
         .server_ephemeral_key(tls_transcript.server_ephemeral_key().clone())
         .transcript_commitments(transcript_commitments);
 
     let attestation = builder.build(&provider)?;
 
+    // Нотариус подтверждает on-chain, что server_name проверен в MPC-TLS:
+    // контракт не может проверить цепочку сертификатов сам
+    let server_identity_signature = tlsn_presentation::sign_server_identity(
+        &signing_key,
+        attestation.header.root.value.as_bytes(),
+        &server_name,
+    )?;
+
     // Отправляем attestation Prover-у через канал
     att_tx
-        .send(attestation)
+        .send(Notarized {
+            attestation,
+            connection_info,
+            server_identity_signature,
+        })
         .map_err(|_| anyhow::anyhow!("Prover не принимает attestation"))?;
 
     info!("Verifier: attestation отправлена");
@@ -396,11 +574,47 @@ async fn run_verifier<T: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
     Ok(())
 }
 
+/// Диапазон тела ответа в полученном транскрипте; None — тело в транскрипте
+/// не совпадает с декодированным (chunked), коммитится только ответ целиком
+fn body_range(received: &[u8], body: &[u8]) -> Option<Range<usize>> {
+    let start = received.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+    (!body.is_empty() && &received[start..] == body).then_some(start..received.len())
+}
+
+/// Размер запроса в HTTP/1.1 (hyper пишет имена заголовков в нижнем регистре,
+/// content-length дописывается для непустого тела)
+fn estimate_request_size(request: &Request<Full<Bytes>>) -> usize {
+    use hyper::body::Body;
+
+    let body_len = request.body().size_hint().exact().unwrap_or(0) as usize;
+    let request_line = request.method().as_str().len()
+        + request.uri().to_string().len()
+        + " HTTP/1.1\r\n".len()
+        + 1;
+    let headers: usize = request
+        .headers()
+        .iter()
+        .map(|(k, v)| k.as_str().len() + v.len() + ": \r\n".len())
+        .sum();
+    let content_length = if body_len > 0 {
+        format!("content-length: {body_len}\r\n").len()
+    } else {
+        0
+    };
+    request_line + headers + content_length + "\r\n".len() + body_len
+}
+
 // ── Подпись данных нотариусом ────────────────────────────────
 
+/// Тег версии подписываемого сообщения (SIGN_MESSAGE_TAG в контракте)
+const SIGN_MESSAGE_TAG: &[u8] = b"tls-oracle/attestation/v2";
+
 /// Формирует SHA-256 хеш и подписывает ключом нотариуса (secp256k1 ECDSA).
 ///
-/// Формат сообщения: SHA-256(source_url || 0x00 || server_name || 0x00 || timestamp_be8 || 0x00 || response_data)
+/// Формат сообщения v2 (build_sign_message в контракте): SHA-256(SIGN_MESSAGE_TAG
+/// || len_be4(source_url) || source_url || len_be4(server_name) || server_name
+/// || timestamp_be8 || len_be4(response_data) || response_data
+/// || 0x00 | 0x01 || request_hash), request_hash — PreparedBody::request_hash.
 /// Вызывается ПОСЛЕ финализации response_data (для ESPN — после компактификации).
 pub fn sign_attestation_data(
     signing_key: &SigningKey,
@@ -408,19 +622,28 @@ pub fn sign_attestation_data(
     server_name: &str,
     timestamp: u64,
     response_data: &str,
+    request_hash: Option<&[u8; 32]>,
 ) -> (String, u8) {
     use sha2::{Sha256, Digest};
     use k256::ecdsa::signature::hazmat::PrehashSigner;
 
-    // Детерминированный message hash
+    // Детерминированный message hash; длины полей однозначно задают границы
     let mut hasher = Sha256::new();
-    hasher.update(source_url.as_bytes());
-    hasher.update(b"\x00");
-    hasher.update(server_name.as_bytes());
-    hasher.update(b"\x00");
-    hasher.update(&timestamp.to_be_bytes());
-    hasher.update(b"\x00");
+    hasher.update(SIGN_MESSAGE_TAG);
+    for field in [source_url, server_name] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(timestamp.to_be_bytes());
+    hasher.update((response_data.len() as u32).to_be_bytes());
     hasher.update(response_data.as_bytes());
+    match request_hash {
+        Some(request_hash) => {
+            hasher.update([0x01]);
+            hasher.update(request_hash);
+        }
+        None => hasher.update([0x00]),
+    }
     let hash: [u8; 32] = hasher.finalize().into();
 
     // ECDSA подпись с recoverable recovery_id
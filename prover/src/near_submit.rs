@@ -0,0 +1,570 @@
+//! Отправка аттестации в NEAR из prover (submit_attestation)
+//!
+//! Включается NEAR_SIGNER_ACCOUNT + NEAR_SIGNER_KEY — обычно function-call
+//! ключ на контракт оракула. Такой ключ не может прикреплять депозит:
+//! комиссия тогда списывается с prepaid баланса аккаунта в контракте.
+//!
+//! Режимы (поле submit в ProveRequest):
+//! - direct — транзакция от NEAR_SIGNER_ACCOUNT;
+//! - meta — NEP-366: signer подписывает DelegateAction, газ платит relayer
+//!   (NEAR_RELAYER_ACCOUNT + full access NEAR_RELAYER_KEY); без relayer
+//!   подписанный DelegateAction возвращается для внешнего relayer.
+//!
+//! Nonce: с сети (view_access_key) + локальный счётчик для параллельных
+//! отправок; InvalidNonce / Expired — новый nonce и повтор.
+
+mod rpc;
+mod tx;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use rpc::{Permission, RpcClient, RpcError};
+use tx::{Action, DelegateAction, FunctionCallAction, KeyPair, Transaction};
+
+/// Попыток отправки (новый nonce / повтор после таймаута RPC)
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_PAUSE: Duration = Duration::from_secs(2);
+/// Срок действия DelegateAction в блоках (~2 мин)
+const META_TTL_BLOCKS: u64 = 120;
+/// 200 TGas — Groth16 + ecrecover
+const DEFAULT_GAS: u64 = 200_000_000_000_000;
+const METHOD: &str = "submit_attestation";
+
+/// Режим отправки из ProveRequest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmitMode {
+    Direct,
+    Meta,
+}
+
+/// Результат отправки (поле submission в ProveResponse)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Submission {
+    pub mode: SubmitMode,
+    /// Аккаунт-submitter аттестации
+    pub sender_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// id, возвращённый submit_attestation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attestation_id: Option<u64>,
+    /// meta без relayer: borsh SignedDelegateAction (base64) для внешнего relayer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_delegate_action: Option<String>,
+    /// Ошибка отправки — proof в ответе остаётся валидным для повторной отправки
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Транзакция исполнена с ошибкой (status.Failure)
+#[derive(Debug)]
+struct TxFailure(Value);
+
+impl std::fmt::Display for TxFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Транзакция отклонена: {}", self.0)
+    }
+}
+
+impl std::error::Error for TxFailure {}
+
+struct Account {
+    id: String,
+    key: KeyPair,
+}
+
+pub struct NearSubmitter {
+    rpc: RpcClient,
+    contract_id: String,
+    signer: Account,
+    relayer: Option<Account>,
+    gas: u64,
+    deposit: u128,
+    /// Последний использованный nonce по public key
+    nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl NearSubmitter {
+    /// NEAR_SIGNER_ACCOUNT / NEAR_SIGNER_KEY, NEAR_RELAYER_ACCOUNT / NEAR_RELAYER_KEY,
+    /// NEAR_RPC_URL (по умолчанию — по NEAR_NETWORK), TLS_ORACLE_CONTRACT,
+    /// NEAR_GAS, NEAR_DEPOSIT (yoctoNEAR). None — отправка не настроена
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let Some(signer_id) = var("NEAR_SIGNER_ACCOUNT") else {
+            return Ok(None);
+        };
+        let account = |id: String, key_var: &str| -> Result<Account> {
+            let key = var(key_var).with_context(|| format!("{key_var} не задан"))?;
+            let key = KeyPair::from_near_str(&key).with_context(|| key_var.to_string())?;
+            Ok(Account { id, key })
+        };
+
+        let network = var("NEAR_NETWORK").unwrap_or_else(|| "testnet".to_string());
+        let rpc_url = var("NEAR_RPC_URL").unwrap_or_else(|| match network.as_str() {
+            "mainnet" => "https://free.rpc.fastnear.com".to_string(),
+            _ => "https://test.rpc.fastnear.com".to_string(),
+        });
+
+        Ok(Some(Self::new(
+            rpc_url,
+            var("TLS_ORACLE_CONTRACT")
+                .unwrap_or_else(|| "tls-oracle-v2.nearcast-oracle.testnet".to_string()),
+            account(signer_id, "NEAR_SIGNER_KEY")?,
+            var("NEAR_RELAYER_ACCOUNT")
+                .map(|id| account(id, "NEAR_RELAYER_KEY"))
+                .transpose()?,
+            var("NEAR_GAS")
+                .map(|g| g.parse().context("NEAR_GAS"))
+                .transpose()?
+                .unwrap_or(DEFAULT_GAS),
+            var("NEAR_DEPOSIT")
+                .map(|d| d.parse().context("NEAR_DEPOSIT"))
+                .transpose()?
+                .unwrap_or(0),
+        )?))
+    }
+
+    fn new(
+        rpc_url: String,
+        contract_id: String,
+        signer: Account,
+        relayer: Option<Account>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<Self> {
+        info!(
+            "NEAR отправка: {} → {} (relayer: {})",
+            signer.id,
+            contract_id,
+            relayer.as_ref().map_or("нет", |r| r.id.as_str())
+        );
+        Ok(Self {
+            rpc: RpcClient::new(rpc_url)?,
+            contract_id,
+            signer,
+            relayer,
+            gas,
+            deposit,
+            nonces: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Отправляет submit_attestation; ошибка сохраняется в Submission::error
+    pub async fn submit(&self, args: &Value, mode: SubmitMode) -> Submission {
+        let mut submission = Submission {
+            mode,
+            sender_id: self.signer.id.clone(),
+            tx_hash: None,
+            attestation_id: None,
+            signed_delegate_action: None,
+            error: None,
+        };
+
+        let mut attempt = 1;
+        loop {
+            let result = match mode {
+                SubmitMode::Direct => self.submit_direct(args, &mut submission).await,
+                SubmitMode::Meta => self.submit_meta(args, &mut submission).await,
+            };
+            match result {
+                Ok(()) => return submission,
+                Err(e) if attempt < MAX_ATTEMPTS && retriable(&e) => {
+                    warn!("NEAR отправка, попытка {attempt}: {e:#}");
+                    // Nonce с сети, а не из локального счётчика
+                    self.nonces.lock().unwrap().clear();
+                    tokio::time::sleep(RETRY_PAUSE).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    warn!("NEAR отправка не удалась: {e:#}");
+                    submission.error = Some(format!("{e:#}"));
+                    return submission;
+                }
+            }
+        }
+    }
+
+    async fn submit_direct(&self, args: &Value, submission: &mut Submission) -> Result<()> {
+        let signer = &self.signer;
+        let (nonce, block_hash, _) = self.reserve_nonce(signer, &self.contract_id).await?;
+        let signed = Transaction {
+            signer_id: signer.id.clone(),
+            public_key: signer.key.public_key(),
+            nonce,
+            receiver_id: self.contract_id.clone(),
+            block_hash,
+            actions: vec![self.call_action(args)?],
+        }
+        .sign(&signer.key);
+        submission.tx_hash = Some(signed.hash_base58());
+
+        let outcome = self.broadcast(&signed.to_base64()).await?;
+        submission.attestation_id = attestation_id(&outcome, &self.contract_id);
+        info!(
+            "submit_attestation: tx {} → #{:?}",
+            signed.hash_base58(),
+            submission.attestation_id
+        );
+        Ok(())
+    }
+
+    async fn submit_meta(&self, args: &Value, submission: &mut Submission) -> Result<()> {
+        let signer = &self.signer;
+        let (nonce, _, block_height) = self.reserve_nonce(signer, &self.contract_id).await?;
+        let delegate = DelegateAction {
+            sender_id: signer.id.clone(),
+            receiver_id: self.contract_id.clone(),
+            actions: vec![self.call_action(args)?],
+            nonce,
+            max_block_height: block_height + META_TTL_BLOCKS,
+            public_key: signer.key.public_key(),
+        }
+        .sign(&signer.key);
+
+        let Some(relayer) = &self.relayer else {
+            submission.signed_delegate_action = Some(delegate.to_base64());
+            return Ok(());
+        };
+
+        // Delegate-действие разрешено только full access ключу relayer
+        let (nonce, block_hash, _) = self.reserve_nonce(relayer, &signer.id).await?;
+        let signed = Transaction {
+            signer_id: relayer.id.clone(),
+            public_key: relayer.key.public_key(),
+            nonce,
+            receiver_id: signer.id.clone(),
+            block_hash,
+            actions: vec![Action::Delegate(Box::new(delegate))],
+        }
+        .sign(&relayer.key);
+        submission.tx_hash = Some(signed.hash_base58());
+
+        let outcome = self.broadcast(&signed.to_base64()).await?;
+        submission.attestation_id = attestation_id(&outcome, &self.contract_id);
+        info!(
+            "submit_attestation (meta, relayer {}): tx {} → #{:?}",
+            relayer.id,
+            signed.hash_base58(),
+            submission.attestation_id
+        );
+        Ok(())
+    }
+
+    fn call_action(&self, args: &Value) -> Result<Action> {
+        Ok(Action::FunctionCall(FunctionCallAction {
+            method_name: METHOD.to_string(),
+            args: serde_json::to_vec(args)?,
+            gas: self.gas,
+            deposit: self.deposit,
+        }))
+    }
+
+    /// Проверяет ключ и резервирует nonce: (nonce, block_hash, block_height)
+    async fn reserve_nonce(
+        &self,
+        account: &Account,
+        receiver_id: &str,
+    ) -> Result<(u64, [u8; 32], u64)> {
+        let public_key = account.key.public_key().to_near_string();
+        let key = self.rpc.access_key(&account.id, &public_key).await?;
+
+        let is_relayer = receiver_id != self.contract_id;
+        match &key.permission {
+            Permission::FullAccess => {}
+            Permission::FunctionCall { .. } if is_relayer => {
+                anyhow::bail!("Ключ relayer {} должен быть full access", account.id)
+            }
+            Permission::FunctionCall {
+                receiver_id: allowed,
+                method_names,
+            } => {
+                anyhow::ensure!(
+                    allowed == receiver_id,
+                    "Function-call ключ {} выдан на {allowed}, а не на {receiver_id}",
+                    account.id
+                );
+                anyhow::ensure!(
+                    method_names.is_empty() || method_names.iter().any(|m| m == METHOD),
+                    "Function-call ключ {} не разрешает {METHOD} (method_names: {})",
+                    account.id,
+                    method_names.join(", ")
+                );
+                anyhow::ensure!(
+                    self.deposit == 0,
+                    "Function-call ключ не прикрепляет депозит: NEAR_DEPOSIT=0 и prepaid баланс"
+                );
+            }
+        }
+
+        let mut nonces = self.nonces.lock().unwrap();
+        let last = nonces.entry(public_key).or_insert(0);
+        *last = (*last).max(key.nonce) + 1;
+        Ok((*last, key.block_hash, key.block_height))
+    }
+
+    /// send_tx; после таймаута RPC те же байты отправляются повторно
+    /// (хеш тот же — двойного исполнения не будет)
+    async fn broadcast(&self, signed_tx_base64: &str) -> Result<Value> {
+        let mut attempt = 1;
+        let outcome = loop {
+            match self.rpc.send_tx(signed_tx_base64).await {
+                Err(e)
+                    if attempt < MAX_ATTEMPTS
+                        && e.downcast_ref::<RpcError>()
+                            .is_some_and(RpcError::is_timeout) =>
+                {
+                    tokio::time::sleep(RETRY_PAUSE).await;
+                    attempt += 1;
+                }
+                result => break result?,
+            }
+        };
+
+        if let Some(failure) = outcome.pointer("/status/Failure") {
+            return Err(TxFailure(failure.clone()).into());
+        }
+        // Ошибка в receipt (например, в делегированном вызове)
+        if let Some(failure) =
+            receipt_outcomes(&outcome).find_map(|o| o.pointer("/outcome/status/Failure"))
+        {
+            return Err(TxFailure(failure.clone()).into());
+        }
+        Ok(outcome)
+    }
+}
+
+/// Новый nonce / свежий блок могут помочь; остальное — нет
+fn retriable(e: &anyhow::Error) -> bool {
+    if let Some(rpc) = e.downcast_ref::<RpcError>() {
+        // Таймаут здесь — broadcast уже повторял те же байты; новая подпись
+        // с другим nonce могла бы исполниться второй раз
+        return rpc.is_invalid_nonce() || rpc.is_expired();
+    }
+    if let Some(TxFailure(failure)) = e.downcast_ref::<TxFailure>() {
+        let failure = failure.to_string();
+        return failure.contains("InvalidNonce") || failure.contains("DelegateActionExpired");
+    }
+    false
+}
+
+fn receipt_outcomes(outcome: &Value) -> impl Iterator<Item = &Value> {
+    outcome
+        .get("receipts_outcome")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+/// id аттестации: возвращаемое значение receipt контракта (JSON u64),
+/// иначе — из лога "Аттестация #N сохранена"
+fn attestation_id(outcome: &Value, contract_id: &str) -> Option<u64> {
+    let from_contract = || {
+        receipt_outcomes(outcome).filter(|o| {
+            o.pointer("/outcome/executor_id").and_then(Value::as_str) == Some(contract_id)
+        })
+    };
+
+    let returned = from_contract().find_map(|o| {
+        let value = o.pointer("/outcome/status/SuccessValue")?.as_str()?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .ok()?;
+        serde_json::from_slice::<u64>(&bytes).ok()
+    });
+    returned.or_else(|| {
+        from_contract()
+            .filter_map(|o| o.pointer("/outcome/logs").and_then(Value::as_array))
+            .flatten()
+            .filter_map(Value::as_str)
+            .find_map(|log| {
+                let rest = log.strip_prefix("Аттестация #")?;
+                rest.split_whitespace().next()?.parse().ok()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, routing::post, Json, Router};
+    use ed25519_dalek::SigningKey;
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+
+    /// Локальная замена NEAR RPC: access key, send_tx с проверкой nonce
+    #[derive(Default)]
+    struct StandIn {
+        /// nonce по public key
+        nonces: Mutex<HashMap<String, u64>>,
+        /// Первый send_tx отвечает InvalidNonce
+        reject_first: bool,
+        /// method_names function-call ключа signer
+        method_names: Vec<&'static str>,
+        sent: Mutex<Vec<String>>,
+    }
+
+    async fn rpc(State(node): State<Arc<StandIn>>, Json(req): Json<Value>) -> Json<Value> {
+        let params = &req["params"];
+        let result = match req["method"].as_str().unwrap() {
+            "query" => {
+                let key = params["public_key"].as_str().unwrap();
+                let nonce = *node.nonces.lock().unwrap().get(key).unwrap_or(&10);
+                json!({
+                    "nonce": nonce,
+                    "permission": if params["account_id"] == "relayer.test" {
+                        json!("FullAccess")
+                    } else {
+                        json!({ "FunctionCall": { "allowance": null, "receiver_id": "oracle.test", "method_names": node.method_names } })
+                    },
+                    "block_hash": bs58::encode([9u8; 32]).into_string(),
+                    "block_height": 1000,
+                })
+            }
+            "send_tx" => {
+                let tx = params["signed_tx_base64"].as_str().unwrap().to_string();
+                let mut sent = node.sent.lock().unwrap();
+                sent.push(tx);
+                if node.reject_first && sent.len() == 1 {
+                    return Json(json!({
+                        "jsonrpc": "2.0", "id": req["id"],
+                        "error": {
+                            "name": "HANDLER_ERROR",
+                            "cause": { "name": "INVALID_TRANSACTION", "info": {} },
+                            "data": { "TxExecutionError": { "InvalidTxError": { "InvalidNonce": { "tx_nonce": 11, "ak_nonce": 20 } } } }
+                        }
+                    }));
+                }
+                json!({
+                    "status": { "SuccessValue": base64::engine::general_purpose::STANDARD.encode(b"42") },
+                    "receipts_outcome": [{
+                        "outcome": {
+                            "executor_id": "oracle.test",
+                            "logs": ["Аттестация #42 сохранена: example.com (alice.test) [sig+zk verified]"],
+                            "status": { "SuccessValue": base64::engine::general_purpose::STANDARD.encode(b"42") }
+                        }
+                    }]
+                })
+            }
+            m => panic!("неожиданный метод {m}"),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": req["id"], "result": result }))
+    }
+
+    async fn stand_in(node: Arc<StandIn>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/", post(rpc)).with_state(node);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}/")
+    }
+
+    fn account(id: &str, seed: u8) -> Account {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let s = format!("ed25519:{}", bs58::encode(key.to_bytes()).into_string());
+        Account {
+            id: id.to_string(),
+            key: KeyPair::from_near_str(&s).unwrap(),
+        }
+    }
+
+    fn submitter(url: String, relayer: Option<Account>, deposit: u128) -> NearSubmitter {
+        NearSubmitter::new(
+            url,
+            "oracle.test".into(),
+            account("alice.test", 1),
+            relayer,
+            DEFAULT_GAS,
+            deposit,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_direct_submit_with_nonce_retry() {
+        let node = Arc::new(StandIn {
+            reject_first: true,
+            ..Default::default()
+        });
+        let near = submitter(stand_in(node.clone()).await, None, 0);
+
+        let submission = near
+            .submit(&json!({ "timestamp": 1 }), SubmitMode::Direct)
+            .await;
+        assert_eq!(submission.error, None);
+        assert_eq!(submission.attestation_id, Some(42));
+
+        // Повтор после InvalidNonce — новая подпись, nonce тот же с сети + 1
+        let sent = node.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        let tx = base64::engine::general_purpose::STANDARD
+            .decode(&sent[1])
+            .unwrap();
+        let signed_hash = bs58::decode(submission.tx_hash.unwrap())
+            .into_vec()
+            .unwrap();
+        // SignedTransaction = tx || signature (1 + 64 байта)
+        let (body, signature) = tx.split_at(tx.len() - 65);
+        assert_eq!(Sha256::digest(body).as_slice(), signed_hash.as_slice());
+        let signature = ed25519_dalek::Signature::from_slice(&signature[1..]).unwrap();
+        SigningKey::from_bytes(&[1; 32])
+            .verifying_key()
+            .verify_strict(&signed_hash, &signature)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_key_without_submit_method() {
+        let node = Arc::new(StandIn {
+            method_names: vec!["ft_transfer_call"],
+            ..Default::default()
+        });
+        let near = submitter(stand_in(node.clone()).await, None, 0);
+
+        let submission = near
+            .submit(&json!({ "timestamp": 1 }), SubmitMode::Direct)
+            .await;
+        assert!(submission
+            .error
+            .unwrap()
+            .contains("не разрешает submit_attestation"));
+        assert!(node.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_meta_via_relayer() {
+        let node = Arc::new(StandIn::default());
+        let near = submitter(
+            stand_in(node.clone()).await,
+            Some(account("relayer.test", 2)),
+            0,
+        );
+
+        let submission = near.submit(&json!({}), SubmitMode::Meta).await;
+        assert_eq!(submission.error, None);
+        assert_eq!(submission.attestation_id, Some(42));
+        assert_eq!(submission.sender_id, "alice.test");
+    }
+
+    #[tokio::test]
+    async fn test_function_call_key_rejects_deposit() {
+        let node = Arc::new(StandIn::default());
+        let near = submitter(stand_in(node.clone()).await, None, 1);
+
+        let submission = near.submit(&json!({}), SubmitMode::Direct).await;
+        assert!(submission.error.unwrap().contains("депозит"));
+        assert!(node.sent.lock().unwrap().is_empty());
+    }
+}
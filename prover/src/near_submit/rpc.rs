@@ -0,0 +1,189 @@
+//! Минимальный NEAR JSON-RPC клиент: access key, последний блок, send_tx
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Ошибка, которую RPC вернул в поле error
+#[derive(Debug)]
+pub struct RpcError {
+    /// cause.name или name ("INVALID_TRANSACTION", "TIMEOUT_ERROR", ...)
+    pub name: String,
+    pub data: Value,
+}
+
+impl RpcError {
+    /// Nonce уже использован — нужен свежий nonce с сети
+    pub fn is_invalid_nonce(&self) -> bool {
+        self.data.to_string().contains("InvalidNonce")
+    }
+
+    /// block_hash транзакции устарел
+    pub fn is_expired(&self) -> bool {
+        self.data.to_string().contains("Expired")
+    }
+
+    /// Транзакция могла быть принята — повторная отправка тех же байт безопасна
+    pub fn is_timeout(&self) -> bool {
+        self.name == "TIMEOUT_ERROR"
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NEAR RPC {}: {}", self.name, self.data)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// Разрешения access key
+#[derive(Debug, Clone, PartialEq)]
+pub enum Permission {
+    FullAccess,
+    /// Пустой method_names — любой метод receiver_id
+    FunctionCall {
+        receiver_id: String,
+        method_names: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessKey {
+    pub nonce: u64,
+    pub permission: Permission,
+    /// Блок, на котором прочитан ключ
+    pub block_hash: [u8; 32],
+    pub block_height: u64,
+}
+
+pub struct RpcClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self { url, http })
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = self
+            .http
+            .post(&self.url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": "tls-oracle-prover",
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .with_context(|| format!("NEAR RPC {method}"))?
+            .json()
+            .await
+            .with_context(|| format!("NEAR RPC {method}: ответ не JSON"))?;
+
+        if let Some(error) = response.get("error") {
+            let name = error
+                .pointer("/cause/name")
+                .or_else(|| error.get("name"))
+                .and_then(Value::as_str)
+                .unwrap_or("UNKNOWN")
+                .to_string();
+            let data = error
+                .get("data")
+                .or_else(|| error.pointer("/cause/info"))
+                .cloned()
+                .unwrap_or_else(|| error.clone());
+            return Err(RpcError { name, data }.into());
+        }
+        response
+            .get("result")
+            .cloned()
+            .with_context(|| format!("NEAR RPC {method}: нет result"))
+    }
+
+    /// view_access_key на финализированном блоке
+    pub async fn access_key(&self, account_id: &str, public_key: &str) -> Result<AccessKey> {
+        #[derive(Deserialize)]
+        struct View {
+            nonce: u64,
+            permission: Value,
+            block_hash: String,
+            block_height: u64,
+        }
+
+        let result = self
+            .call(
+                "query",
+                json!({
+                    "request_type": "view_access_key",
+                    "finality": "final",
+                    "account_id": account_id,
+                    "public_key": public_key,
+                }),
+            )
+            .await?;
+        // Несуществующий ключ — result.error, а не error
+        if let Some(error) = result.get("error").and_then(Value::as_str) {
+            anyhow::bail!("Access key {public_key} для {account_id}: {error}");
+        }
+        let view: View = serde_json::from_value(result).context("view_access_key")?;
+
+        let permission = match &view.permission {
+            Value::String(s) if s == "FullAccess" => Permission::FullAccess,
+            p => Permission::FunctionCall {
+                receiver_id: p
+                    .pointer("/FunctionCall/receiver_id")
+                    .and_then(Value::as_str)
+                    .context("view_access_key: неизвестный permission")?
+                    .to_string(),
+                method_names: p
+                    .pointer("/FunctionCall/method_names")
+                    .and_then(Value::as_array)
+                    .map(|names| {
+                        names
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+        };
+        Ok(AccessKey {
+            nonce: view.nonce,
+            permission,
+            block_hash: decode_hash(&view.block_hash)?,
+            block_height: view.block_height,
+        })
+    }
+
+    /// send_tx: ждёт исполнения всех receipt-ов; результат — FinalExecutionOutcome
+    pub async fn send_tx(&self, signed_tx_base64: &str) -> Result<Value> {
+        self.call(
+            "send_tx",
+            json!({
+                "signed_tx_base64": signed_tx_base64,
+                "wait_until": "EXECUTED_OPTIMISTIC",
+            }),
+        )
+        .await
+    }
+}
+
+fn decode_hash(s: &str) -> Result<[u8; 32]> {
+    bs58::decode(s)
+        .into_vec()
+        .ok()
+        .and_then(|v| v.try_into().ok())
+        .with_context(|| format!("Невалидный block hash {s}"))
+}
@@ -0,0 +1,288 @@
+//! Borsh-формат NEAR: Transaction, FunctionCall, NEP-366 DelegateAction
+//!
+//! Только то, что нужно для submit_attestation; раскладка полей и номера
+//! вариантов совпадают с near-primitives (nearcore/core/primitives).
+
+use anyhow::{Context, Result};
+use borsh::{io::Write, BorshSerialize};
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+
+/// Префикс подписываемого сообщения NEP-461 для DelegateAction: 2^30 + 366
+const DELEGATE_ACTION_PREFIX: u32 = (1 << 30) + 366;
+
+/// ed25519 ключ в формате near-cli: "ed25519:<base58>"
+pub struct KeyPair {
+    key: SigningKey,
+}
+
+impl KeyPair {
+    /// Секретный ключ — 64 байта (seed || pubkey) или 32 байта seed
+    pub fn from_near_str(s: &str) -> Result<Self> {
+        let data = s
+            .strip_prefix("ed25519:")
+            .context("Поддерживаются только ed25519 ключи")?;
+        let bytes = bs58::decode(data)
+            .into_vec()
+            .context("Ключ: невалидный base58")?;
+        let key = match bytes.len() {
+            64 => SigningKey::from_keypair_bytes(&bytes.try_into().unwrap())
+                .context("Ключ: seed не соответствует pubkey")?,
+            32 => SigningKey::from_bytes(&bytes.try_into().unwrap()),
+            n => anyhow::bail!("Ключ: {n} байт, ожидается 32 или 64"),
+        };
+        Ok(Self { key })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.key.verifying_key().to_bytes())
+    }
+
+    fn sign(&self, hash: &[u8; 32]) -> Signature {
+        Signature(self.key.sign(hash).to_bytes())
+    }
+}
+
+/// PublicKey::ED25519 (key_type = 0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey(pub [u8; 32]);
+
+impl PublicKey {
+    pub fn to_near_string(self) -> String {
+        format!("ed25519:{}", bs58::encode(self.0).into_string())
+    }
+}
+
+impl BorshSerialize for PublicKey {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        0u8.serialize(writer)?;
+        writer.write_all(&self.0)
+    }
+}
+
+/// Signature::ED25519 (key_type = 0)
+#[derive(Debug, Clone, Copy)]
+pub struct Signature(pub [u8; 64]);
+
+impl BorshSerialize for Signature {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        0u8.serialize(writer)?;
+        writer.write_all(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct FunctionCallAction {
+    pub method_name: String,
+    pub args: Vec<u8>,
+    pub gas: u64,
+    pub deposit: u128,
+}
+
+/// Action; номер варианта — как в near-primitives
+#[derive(Debug, Clone)]
+pub enum Action {
+    FunctionCall(FunctionCallAction),
+    Delegate(Box<SignedDelegateAction>),
+}
+
+impl BorshSerialize for Action {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            Self::FunctionCall(action) => {
+                2u8.serialize(writer)?;
+                action.serialize(writer)
+            }
+            Self::Delegate(action) => {
+                8u8.serialize(writer)?;
+                action.serialize(writer)
+            }
+        }
+    }
+}
+
+/// Transaction V0
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct Transaction {
+    pub signer_id: String,
+    pub public_key: PublicKey,
+    pub nonce: u64,
+    pub receiver_id: String,
+    pub block_hash: [u8; 32],
+    pub actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct SignedTransaction {
+    pub transaction: Transaction,
+    pub signature: Signature,
+}
+
+impl Transaction {
+    /// Хеш транзакции = SHA-256(borsh(tx)); он же подписывается
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::digest(borsh::to_vec(self).expect("borsh в Vec не падает")).into()
+    }
+
+    pub fn sign(self, key: &KeyPair) -> SignedTransaction {
+        let signature = key.sign(&self.hash());
+        SignedTransaction {
+            transaction: self,
+            signature,
+        }
+    }
+}
+
+impl SignedTransaction {
+    /// Хеш в base58 — как в explorer и ответах RPC
+    pub fn hash_base58(&self) -> String {
+        bs58::encode(self.transaction.hash()).into_string()
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            borsh::to_vec(self).expect("borsh в Vec не падает"),
+        )
+    }
+}
+
+/// NEP-366: действия от имени sender_id, газ оплачивает relayer
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct DelegateAction {
+    pub sender_id: String,
+    pub receiver_id: String,
+    /// Без вложенных Delegate
+    pub actions: Vec<Action>,
+    pub nonce: u64,
+    pub max_block_height: u64,
+    pub public_key: PublicKey,
+}
+
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct SignedDelegateAction {
+    pub delegate_action: DelegateAction,
+    pub signature: Signature,
+}
+
+impl DelegateAction {
+    /// SHA-256(borsh(u32 префикс NEP-461) || borsh(delegate_action))
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(DELEGATE_ACTION_PREFIX.to_le_bytes());
+        hasher.update(borsh::to_vec(self).expect("borsh в Vec не падает"));
+        hasher.finalize().into()
+    }
+
+    pub fn sign(self, key: &KeyPair) -> SignedDelegateAction {
+        let signature = key.sign(&self.hash());
+        SignedDelegateAction {
+            delegate_action: self,
+            signature,
+        }
+    }
+}
+
+impl SignedDelegateAction {
+    /// Для внешнего relayer (borsh, base64)
+    pub fn to_base64(&self) -> String {
+        base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            borsh::to_vec(self).expect("borsh в Vec не падает"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> KeyPair {
+        KeyPair {
+            key: SigningKey::from_bytes(&[7; 32]),
+        }
+    }
+
+    fn call() -> Action {
+        Action::FunctionCall(FunctionCallAction {
+            method_name: "m".into(),
+            args: b"{}".to_vec(),
+            gas: 1,
+            deposit: 2,
+        })
+    }
+
+    #[test]
+    fn test_transaction_layout() {
+        let tx = Transaction {
+            signer_id: "a".into(),
+            public_key: PublicKey([1; 32]),
+            nonce: 3,
+            receiver_id: "b".into(),
+            block_hash: [4; 32],
+            actions: vec![call()],
+        };
+        let bytes = borsh::to_vec(&tx).unwrap();
+
+        let mut expected = vec![1, 0, 0, 0, b'a', 0];
+        expected.extend([1; 32]);
+        expected.extend(3u64.to_le_bytes());
+        expected.extend([1, 0, 0, 0, b'b']);
+        expected.extend([4; 32]);
+        expected.extend([1, 0, 0, 0, 2]); // 1 action, FunctionCall = 2
+        expected.extend([1, 0, 0, 0, b'm', 2, 0, 0, 0, b'{', b'}']);
+        expected.extend(1u64.to_le_bytes());
+        expected.extend(2u128.to_le_bytes());
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_signatures_verify() {
+        use ed25519_dalek::{Verifier, VerifyingKey};
+        let key = key();
+        let vk = VerifyingKey::from_bytes(&key.public_key().0).unwrap();
+
+        let signed = Transaction {
+            signer_id: "a".into(),
+            public_key: key.public_key(),
+            nonce: 1,
+            receiver_id: "b".into(),
+            block_hash: [0; 32],
+            actions: vec![call()],
+        }
+        .sign(&key);
+        let sig = ed25519_dalek::Signature::from_bytes(&signed.signature.0);
+        assert!(vk.verify(&signed.transaction.hash(), &sig).is_ok());
+
+        let delegate = DelegateAction {
+            sender_id: "a".into(),
+            receiver_id: "b".into(),
+            actions: vec![call()],
+            nonce: 1,
+            max_block_height: 100,
+            public_key: key.public_key(),
+        };
+        // Подпись делегата не совпадает с подписью тех же байт без префикса
+        let plain: [u8; 32] = Sha256::digest(borsh::to_vec(&delegate).unwrap()).into();
+        let signed = delegate.sign(&key);
+        let sig = ed25519_dalek::Signature::from_bytes(&signed.signature.0);
+        assert!(vk.verify(&signed.delegate_action.hash(), &sig).is_ok());
+        assert!(vk.verify(&plain, &sig).is_err());
+    }
+
+    #[test]
+    fn test_key_parsing() {
+        let key = key();
+        let mut pair = key.key.to_bytes().to_vec();
+        pair.extend(key.public_key().0);
+        let s = format!("ed25519:{}", bs58::encode(&pair).into_string());
+        assert_eq!(
+            KeyPair::from_near_str(&s).unwrap().public_key(),
+            key.public_key()
+        );
+        assert!(KeyPair::from_near_str("secp256k1:abc").is_err());
+        pair[63] ^= 1;
+        let bad = format!("ed25519:{}", bs58::encode(&pair).into_string());
+        assert!(KeyPair::from_near_str(&bad).is_err());
+    }
+}
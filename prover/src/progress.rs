@@ -0,0 +1,103 @@
+//! Ход аттестации: фазы задачи /jobs и события потока /prove/stream
+//!
+//! Pipeline получает один Progress и сообщает о шагах изнутри
+//! mpc_session::run и zk_prover::generate_proof; получатели — запись
+//! задачи в JobStore и/или канал SSE. Для синхронного /prove — Progress::none().
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::jobs::{JobStore, Phase};
+
+/// Событие хода аттестации (SSE event "progress")
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "camelCase")]
+pub enum Event {
+    /// TCP-соединение с целевым сервером
+    Connected { server: String },
+    /// MPC-TLS соединение установлено
+    HandshakeDone,
+    /// HTTP-ответ прочитан через MPC-TLS
+    #[serde(rename_all = "camelCase")]
+    ResponseReceived { status: u16, bytes: usize },
+    /// Транскрипт доказан нотариусу (раскрытие + коммитменты)
+    TranscriptProved,
+    /// Нотариус подписал tlsn attestation
+    AttestationSigned,
+    /// ZK proof: место в пуле получено, вычисление началось
+    ProofStarted,
+    /// ZK proof готов
+    #[serde(rename_all = "camelCase")]
+    ProofGenerated { elapsed_ms: u64 },
+    /// submit_attestation исполнена (meta без relayer — только подписана)
+    #[serde(rename_all = "camelCase")]
+    Submitted {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tx_hash: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        attestation_id: Option<u64>,
+    },
+}
+
+#[derive(Default)]
+pub struct Progress {
+    job: Option<(Arc<JobStore>, String)>,
+    events: Option<UnboundedSender<Event>>,
+}
+
+impl Progress {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn job(store: Arc<JobStore>, id: String) -> Self {
+        Self {
+            job: Some((store, id)),
+            events: None,
+        }
+    }
+
+    pub fn stream(events: UnboundedSender<Event>) -> Self {
+        Self {
+            job: None,
+            events: Some(events),
+        }
+    }
+
+    /// Фаза задачи /jobs
+    pub fn set(&self, phase: Phase) {
+        if let Some((store, id)) = &self.job {
+            store.set_phase(id, phase);
+        }
+    }
+
+    /// Событие для потока; клиент мог отключиться — не ошибка
+    pub fn emit(&self, event: Event) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_format() {
+        let event = Event::ResponseReceived {
+            status: 200,
+            bytes: 512,
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"stage":"responseReceived","status":200,"bytes":512}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Event::HandshakeDone).unwrap(),
+            r#"{"stage":"handshakeDone"}"#
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Тело HTTP-запроса (POST / PUT / GraphQL / JSON-RPC)
+//!
+//! Тело уходит на сервер внутри MPC-TLS и раскрывается в отправленном
+//! транскрипте. В подпись нотариуса входит request_hash — так аттестация
+//! ответа GraphQL привязана к конкретному запросу, а не только к URL.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Тело из ProveRequest
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum RequestBody {
+    /// Текст как есть (UTF-8)
+    Text(String),
+    /// JSON значение — сериализуется компактно
+    Json(serde_json::Value),
+    /// Бинарные данные (standard base64)
+    Base64(String),
+}
+
+/// Тело, готовое к отправке
+#[derive(Debug)]
+pub struct PreparedBody {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+impl RequestBody {
+    /// content_type: None — по типу тела
+    pub fn prepare(&self, content_type: Option<&str>) -> Result<PreparedBody> {
+        let (bytes, default_type) = match self {
+            Self::Text(text) => (text.as_bytes().to_vec(), "text/plain; charset=utf-8"),
+            Self::Json(value) => (serde_json::to_vec(value)?, "application/json"),
+            Self::Base64(b64) => (
+                base64::engine::general_purpose::STANDARD
+                    .decode(b64)
+                    .context("Тело запроса: невалидный base64")?,
+                "application/octet-stream",
+            ),
+        };
+        let content_type = content_type.unwrap_or(default_type);
+        anyhow::ensure!(
+            !content_type.is_empty() && !content_type.contains(['\r', '\n']),
+            "Невалидный Content-Type"
+        );
+        Ok(PreparedBody {
+            bytes,
+            content_type: content_type.to_string(),
+        })
+    }
+}
+
+impl PreparedBody {
+    /// SHA-256(method || 0x00 || content_type || 0x00 || body) —
+    /// дописывается к подписанному сообщению (см. sign_attestation_data)
+    pub fn request_hash(&self, method: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(method.to_uppercase().as_bytes());
+        hasher.update(b"\x00");
+        hasher.update(self.content_type.as_bytes());
+        hasher.update(b"\x00");
+        hasher.update(&self.bytes);
+        hasher.finalize().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> RequestBody {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_prepare() {
+        let body = parse(r#"{"type":"json","value":{"query":"{ me { id } }"}}"#)
+            .prepare(None)
+            .unwrap();
+        assert_eq!(body.bytes, br#"{"query":"{ me { id } }"}"#);
+        assert_eq!(body.content_type, "application/json");
+
+        let body = parse(r#"{"type":"base64","value":"AAEC"}"#)
+            .prepare(Some("application/x-protobuf"))
+            .unwrap();
+        assert_eq!(body.bytes, [0, 1, 2]);
+        assert_eq!(body.content_type, "application/x-protobuf");
+
+        assert!(parse(r#"{"type":"base64","value":"!!"}"#)
+            .prepare(None)
+            .is_err());
+        assert!(parse(r#"{"type":"text","value":"a"}"#)
+            .prepare(Some("text/plain\r\nX-Injected: 1"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_request_hash_binds_method_and_type() {
+        let body = parse(r#"{"type":"text","value":"ping"}"#)
+            .prepare(None)
+            .unwrap();
+        assert_eq!(body.request_hash("post"), body.request_hash("POST"));
+        assert_ne!(body.request_hash("POST"), body.request_hash("PUT"));
+        let other_type = parse(r#"{"type":"text","value":"ping"}"#)
+            .prepare(Some("text/csv"))
+            .unwrap();
+        assert_ne!(body.request_hash("POST"), other_type.request_hash("POST"));
+    }
+}
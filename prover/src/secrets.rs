@@ -0,0 +1,242 @@
+//! Секреты запроса: API-ключи и сессионные заголовки для приватных данных
+//!
+//! Секретные заголовки и query-параметры уходят на сервер внутри MPC-TLS,
+//! но их значения не раскрываются нотариусу и не попадают в presentation.
+//! В source_url (и, значит, в подписанное сообщение и on-chain запись)
+//! вместо значения подставляется PLACEHOLDER.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde::Deserialize;
+use url::form_urlencoded;
+use url::Url;
+
+/// Значение секрета в source_url
+pub const PLACEHOLDER: &str = "REDACTED";
+
+/// Максимум секретов в одном запросе
+pub const MAX_SECRETS: usize = 8;
+
+/// Заголовки, которые нельзя передавать даже как секрет
+/// (транспорт HTTP и подмена адреса клиента)
+const FORBIDDEN_HEADERS: &[&str] = &[
+    "host",
+    "connection",
+    "content-length",
+    "transfer-encoding",
+    "x-forwarded-for",
+    "x-forwarded-host",
+    "x-forwarded-proto",
+    "x-real-ip",
+    "cf-connecting-ip",
+];
+
+/// Секретные части запроса
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestSecrets {
+    /// Заголовки (Authorization, Cookie, X-Api-Key, ...)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Query-параметры, дописываемые к URL
+    #[serde(default)]
+    pub query: BTreeMap<String, String>,
+}
+
+impl RequestSecrets {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.query.is_empty()
+    }
+
+    /// Проверка до MPC-TLS сессии
+    pub fn validate(&self) -> Result<(), String> {
+        if self.headers.len() + self.query.len() > MAX_SECRETS {
+            return Err(format!("Максимум {MAX_SECRETS} секретов"));
+        }
+        for (name, value) in &self.headers {
+            if FORBIDDEN_HEADERS.contains(&name.to_lowercase().as_str()) {
+                return Err(format!("Заголовок {name} нельзя передавать как секрет"));
+            }
+            if value.is_empty() {
+                return Err(format!("Пустое значение секретного заголовка {name}"));
+            }
+        }
+        for (name, value) in &self.query {
+            if name.is_empty() || value.is_empty() {
+                return Err(format!("Пустой секретный query-параметр {name:?}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// URL для запроса (со значениями секретов) и публичный source_url
+    /// (с PLACEHOLDER)
+    pub fn apply_query(&self, url: &Url) -> (Url, String) {
+        let mut real = url.clone();
+        let mut public = url.clone();
+        if !self.query.is_empty() {
+            let mut real_pairs = real.query_pairs_mut();
+            let mut public_pairs = public.query_pairs_mut();
+            for (name, value) in &self.query {
+                real_pairs.append_pair(name, value);
+                public_pairs.append_pair(name, PLACEHOLDER);
+            }
+        }
+        (real, public.to_string())
+    }
+
+    /// Значения секретов в отправленном транскрипте (query — в URL-кодировке)
+    fn sent_needles(&self) -> Vec<(&str, Vec<u8>)> {
+        self.headers
+            .iter()
+            .map(|(name, v)| (name.as_str(), v.as_bytes().to_vec()))
+            .chain(self.query.iter().map(|(name, v)| {
+                let encoded: String = form_urlencoded::byte_serialize(v.as_bytes()).collect();
+                (name.as_str(), encoded.into_bytes())
+            }))
+            .collect()
+    }
+
+    /// Формы значений, в которых сервер может вернуть секрет: как отправлено
+    /// и, для query, декодированным
+    fn echo_needles(&self) -> Vec<(&str, Vec<u8>)> {
+        let mut needles = self.sent_needles();
+        needles.extend(
+            self.query
+                .iter()
+                .map(|(name, v)| (name.as_str(), v.as_bytes().to_vec())),
+        );
+        needles
+    }
+
+    /// Имя секрета, значение которого встречается в data — например, сервер
+    /// вернул заголовок или api_key в ответе
+    pub fn echoed_in(&self, data: &[u8]) -> Option<&str> {
+        self.echo_needles()
+            .into_iter()
+            .find(|(_, needle)| find(data, needle).is_some())
+            .map(|(name, _)| name)
+    }
+
+    /// Текст с PLACEHOLDER вместо значений секретов (для логов)
+    pub fn mask(&self, text: &str) -> String {
+        self.echo_needles()
+            .iter()
+            .filter_map(|(_, needle)| std::str::from_utf8(needle).ok())
+            .fold(text.to_string(), |text, value| {
+                text.replace(value, PLACEHOLDER)
+            })
+    }
+
+    /// Раскрываемые диапазоны отправленного транскрипта: всё, кроме
+    /// каждого вхождения значения секрета
+    pub fn sent_reveal_ranges(&self, sent: &[u8]) -> Vec<Range<usize>> {
+        let needles = self.sent_needles();
+
+        let mut hidden: Vec<Range<usize>> = Vec::new();
+        for (_, needle) in &needles {
+            let mut pos = 0;
+            while let Some(i) = find(&sent[pos..], needle) {
+                hidden.push(pos + i..pos + i + needle.len());
+                pos += i + needle.len();
+            }
+        }
+        hidden.sort_by_key(|r| r.start);
+
+        let mut revealed = Vec::new();
+        let mut cursor = 0;
+        for range in hidden {
+            if range.start > cursor {
+                revealed.push(cursor..range.start);
+            }
+            cursor = cursor.max(range.end);
+        }
+        if cursor < sent.len() {
+            revealed.push(cursor..sent.len());
+        }
+        revealed
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets() -> RequestSecrets {
+        RequestSecrets {
+            headers: BTreeMap::from([("Authorization".into(), "Bearer s3cr3t".into())]),
+            query: BTreeMap::from([("api_key".into(), "k/ey+1".into())]),
+        }
+    }
+
+    #[test]
+    fn test_apply_query() {
+        let url = Url::parse("https://api.example.com/me?fmt=json").unwrap();
+        let (real, public) = secrets().apply_query(&url);
+        assert_eq!(
+            real.as_str(),
+            "https://api.example.com/me?fmt=json&api_key=k%2Fey%2B1"
+        );
+        assert_eq!(
+            public,
+            format!("https://api.example.com/me?fmt=json&api_key={PLACEHOLDER}")
+        );
+    }
+
+    #[test]
+    fn test_sent_reveal_ranges_hide_secrets() {
+        let sent = b"GET /me?fmt=json&api_key=k%2Fey%2B1 HTTP/1.1\r\n\
+                     host: api.example.com\r\n\
+                     authorization: Bearer s3cr3t\r\n\r\n";
+        let ranges = secrets().sent_reveal_ranges(sent);
+
+        let revealed: Vec<u8> = ranges
+            .iter()
+            .flat_map(|r| sent[r.clone()].to_vec())
+            .collect();
+        let revealed = String::from_utf8(revealed).unwrap();
+        assert!(!revealed.contains("s3cr3t"));
+        assert!(!revealed.contains("k%2Fey"));
+        assert!(revealed.contains("host: api.example.com"));
+        assert_eq!(
+            revealed.len(),
+            sent.len() - "Bearer s3cr3t".len() - "k%2Fey%2B1".len()
+        );
+    }
+
+    #[test]
+    fn test_echoed_secrets() {
+        let echo = br#"{"headers": {"Authorization": "Bearer s3cr3t"}}"#;
+        assert_eq!(secrets().echoed_in(echo), Some("Authorization"));
+        // api_key вернулся декодированным
+        assert_eq!(secrets().echoed_in(b"key=k/ey+1"), Some("api_key"));
+        assert_eq!(secrets().echoed_in(br#"{"balance": 10}"#), None);
+        assert_eq!(
+            secrets().mask("token Bearer s3cr3t"),
+            format!("token {PLACEHOLDER}")
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(secrets().validate().is_ok());
+        let host = RequestSecrets {
+            headers: BTreeMap::from([("Host".into(), "evil.com".into())]),
+            ..Default::default()
+        };
+        assert!(host.validate().is_err());
+        let empty = RequestSecrets {
+            query: BTreeMap::from([("token".into(), String::new())]),
+            ..Default::default()
+        };
+        assert!(empty.validate().is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Сборка tlsn presentation для on-chain верификации (submit_tlsn_attestation)
+//!
+//! Контракт получает BCS(Header) + подпись нотариуса, SHA-256 хеши всех полей Body
+//! (листья Merkle дерева), открытие ConnectionInfo (время сессии), подпись
+//! нотариуса над server identity и открытия PlaintextHash коммитментов
+//! раскрытых диапазонов.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use k256::ecdsa::SigningKey;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::ops::Range;
+
+use tlsn::attestation::Attestation;
+use tlsn::connection::ConnectionInfo;
+use tlsn::hash::{DomainSeparator, Sha256 as TlsnSha256};
+use tlsn::transcript::hash::PlaintextHashSecret;
+use tlsn::transcript::{Direction, Transcript, TranscriptCommitment, TranscriptSecret};
+
+/// Подписанный header + раскрытые части транскрипта (формат аргумента контракта)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsnPresentation {
+    /// BCS(Header), base64
+    pub header: String,
+    /// Подпись нотариуса r||s (hex, 64 bytes)
+    pub signature: String,
+    /// SHA-256 хеши полей Body (hex) в порядке FieldId
+    pub field_hashes: Vec<String>,
+    /// domain separator || BCS(ConnectionInfo), base64
+    pub connection_info: String,
+    /// Индекс ConnectionInfo в field_hashes
+    pub connection_info_index: u32,
+    /// Подпись нотариуса r||s (hex) над server identity
+    pub server_identity_signature: String,
+    /// Раскрытые диапазоны транскрипта
+    pub reveals: Vec<TranscriptReveal>,
+}
+
+/// Тег подписи server identity (как SERVER_IDENTITY_TAG в контракте)
+const SERVER_IDENTITY_TAG: &[u8] = b"tls-oracle/tlsn-server-identity/v1";
+
+/// Подпись нотариуса над server_name, проверенным в MPC-TLS:
+/// SHA-256(TAG || header.root || server_name_len u32 BE || server_name), r||s hex
+pub fn sign_server_identity(
+    signing_key: &SigningKey,
+    root: &[u8],
+    server_name: &str,
+) -> Result<String> {
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+    let mut hasher = Sha256::new();
+    hasher.update(SERVER_IDENTITY_TAG);
+    hasher.update(root);
+    hasher.update((server_name.len() as u32).to_be_bytes());
+    hasher.update(server_name.as_bytes());
+    let hash = hasher.finalize();
+
+    let signature: k256::ecdsa::Signature = signing_key
+        .sign_prehash(&hash)
+        .context("Подпись server identity")?;
+    Ok(hex::encode(signature.to_bytes()))
+}
+
+/// Открытие PlaintextHash коммитмента
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptReveal {
+    /// "sent" | "received"
+    pub direction: &'static str,
+    pub start: usize,
+    pub end: usize,
+    /// Plaintext диапазона, base64
+    pub data: String,
+    /// Blinder коммитмента, base64
+    pub blinder: String,
+    /// domain separator || BCS(PlaintextHash), base64
+    pub field: String,
+    /// Индекс поля в field_hashes
+    pub field_index: u32,
+}
+
+/// Собирает presentation из подписанной attestation и секретов hash-коммитментов
+///
+/// Открываются коммитменты отправленных данных и полученных диапазонов из
+/// revealed_recv; остальные (скрытые при выборочном раскрытии) — нет.
+/// Контракт требует, чтобы response_data совпадал с одним открытым диапазоном
+/// ответа целиком — иначе presentation не собирается.
+#[allow(clippy::too_many_arguments)]
+pub fn build(
+    attestation: &Attestation,
+    connection_info: &ConnectionInfo,
+    server_identity_signature: String,
+    transcript: &Transcript,
+    commitments: &[TranscriptCommitment],
+    secrets: &[TranscriptSecret],
+    revealed_recv: &[Range<usize>],
+    response_data: &str,
+) -> Result<TlsnPresentation> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    let header = bcs::to_bytes(&attestation.header).context("BCS(Header)")?;
+    let signature = hex::encode(&attestation.signature.data);
+
+    // Листья Merkle дерева: хеши всех полей Body
+    let field_hashes: Vec<Vec<u8>> = attestation
+        .body
+        .hash_fields(&TlsnSha256::default())
+        .into_iter()
+        .map(|(_, hash)| hash.as_bytes().to_vec())
+        .collect();
+
+    let mut reveals = Vec::new();
+    for secret in secrets {
+        let TranscriptSecret::Hash(PlaintextHashSecret {
+            direction,
+            idx,
+            blinder,
+            ..
+        }) = secret
+        else {
+            continue;
+        };
+
+        // Коммитмент того же диапазона
+        let commitment = commitments
+            .iter()
+            .find_map(|c| match c {
+                TranscriptCommitment::Hash(h) if h.direction == *direction && h.idx == *idx => {
+                    Some(h)
+                }
+                _ => None,
+            })
+            .context("Нет PlaintextHash коммитмента для секрета")?;
+
+        let (field, field_index) = locate_field(commitment, &field_hashes)
+            .context("PlaintextHash коммитмент не найден среди полей attestation")?;
+
+        let range = idx
+            .iter_ranges()
+            .next()
+            .context("Пустой диапазон коммитмента")?;
+        anyhow::ensure!(
+            idx.iter_ranges().count() == 1,
+            "On-chain поддерживаются только непрерывные диапазоны"
+        );
+        if *direction == Direction::Received && !revealed_recv.contains(&range) {
+            continue;
+        }
+
+        let data = match direction {
+            Direction::Sent => &transcript.sent()[range.clone()],
+            Direction::Received => &transcript.received()[range.clone()],
+        };
+
+        reveals.push(TranscriptReveal {
+            direction: match direction {
+                Direction::Sent => "sent",
+                Direction::Received => "received",
+            },
+            start: range.start,
+            end: range.end,
+            data: b64.encode(data),
+            blinder: b64.encode(blinder.as_bytes()),
+            field: b64.encode(&field),
+            field_index,
+        });
+    }
+
+    anyhow::ensure!(
+        !response_data.is_empty()
+            && reveals.iter().any(|r| {
+                r.direction == "received" && r.data == b64.encode(response_data.as_bytes())
+            }),
+        "response_data не совпадает ни с одним раскрытым диапазоном ответа"
+    );
+
+    let (connection_info, connection_info_index) = locate_field(connection_info, &field_hashes)
+        .context("ConnectionInfo не найден среди полей attestation")?;
+
+    Ok(TlsnPresentation {
+        header: b64.encode(&header),
+        signature,
+        field_hashes: field_hashes.iter().map(hex::encode).collect(),
+        connection_info: b64.encode(&connection_info),
+        connection_info_index,
+        server_identity_signature,
+        reveals,
+    })
+}
+
+/// Hash preimage поля Body (domain separator || BCS) и его индекс среди листьев
+fn locate_field<T: DomainSeparator + Serialize>(
+    value: &T,
+    field_hashes: &[Vec<u8>],
+) -> Result<(Vec<u8>, u32)> {
+    let mut field = value.domain().to_vec();
+    field.extend_from_slice(&bcs::to_bytes(value).context("BCS поля")?);
+
+    let leaf = Sha256::digest(&field);
+    let index = field_hashes
+        .iter()
+        .position(|h| h.as_slice() == leaf.as_slice())
+        .context("Хеш поля не найден")?;
+
+    Ok((field, index as u32))
+}
@@ -3,7 +3,7 @@
 //! Блокирует запросы к приватным IP, localhost, metadata-сервисам облаков.
 //! Разрешает только HTTPS. Фильтрует опасные заголовки.
 
-use std::net::{IpAddr, ToSocketAddrs};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
 use url::Url;
 
 /// Заблокированные заголовки (нижний регистр)
@@ -24,6 +24,12 @@ const MAX_URL_LENGTH: usize = 2048;
 
 /// Проверяет, что URL безопасен для внешнего запроса
 pub fn validate_url(raw_url: &str) -> Result<Url, String> {
+    resolve_url(raw_url).map(|(url, _)| url)
+}
+
+/// validate_url + проверенные адреса — чтобы запрос шёл именно на них
+/// (без второго DNS-резолва между проверкой и соединением)
+pub fn resolve_url(raw_url: &str) -> Result<(Url, Vec<SocketAddr>), String> {
     // Ограничение длины
     if raw_url.len() > MAX_URL_LENGTH {
         return Err(format!(
@@ -61,25 +67,25 @@ pub fn validate_url(raw_url: &str) -> Result<Url, String> {
 
     // DNS-резолв + проверка IP (предотвращаем DNS rebinding)
     let port = parsed.port().unwrap_or(443);
-    let addrs: Vec<IpAddr> = format!("{host}:{port}")
+    let addrs: Vec<SocketAddr> = format!("{host}:{port}")
         .to_socket_addrs()
         .map_err(|e| format!("DNS-резолв не удался для {host}: {e}"))?
-        .map(|sa| sa.ip())
         .collect();
 
     if addrs.is_empty() {
         return Err(format!("DNS не вернул адресов для {host}"));
     }
 
-    for ip in &addrs {
-        if is_private_ip(ip) {
+    for addr in &addrs {
+        let ip = addr.ip();
+        if is_private_ip(&ip) {
             return Err(format!(
                 "Запрещённый IP-адрес {ip} для хоста {host}"
             ));
         }
     }
 
-    Ok(parsed)
+    Ok((parsed, addrs))
 }
 
 /// Проверяет, является ли IP приватным/зарезервированным
@@ -0,0 +1,216 @@
+//! Webhook: доставка результата задачи /jobs на callbackUrl
+//!
+//! POST с телом ProveResponse (или {"error": {status, message}}) и подписью
+//! X-Signature-256 = "sha256=" + hex(HMAC-SHA256(secret, timestamp + "." + body)),
+//! timestamp — в X-Signature-Timestamp. Повтор с экспоненциальной паузой;
+//! статус доставки виден в GET /jobs/{id}.
+//!
+//! SSRF: callbackUrl проходит url_validator перед каждой попыткой, запрос
+//! идёт на проверенные адреса (без повторного DNS), редиректы не следуют.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::{info, warn};
+
+use crate::url_validator;
+
+/// Попыток доставки (первая + повторы)
+const MAX_ATTEMPTS: u32 = 5;
+/// Пауза перед первым повтором, дальше удваивается
+const FIRST_RETRY: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MIN_SECRET_LEN: usize = 16;
+const MAX_SECRET_LEN: usize = 256;
+
+/// Callback из ProveRequest
+#[derive(Debug, Clone)]
+pub struct Callback {
+    pub url: String,
+    secret: String,
+}
+
+impl Callback {
+    pub fn new(url: String, secret: String) -> Result<Self, String> {
+        if !(MIN_SECRET_LEN..=MAX_SECRET_LEN).contains(&secret.len()) {
+            return Err(format!(
+                "callbackSecret: от {MIN_SECRET_LEN} до {MAX_SECRET_LEN} символов"
+            ));
+        }
+        url_validator::validate_url(&url).map_err(|e| format!("callbackUrl: {e}"))?;
+        Ok(Self { url, secret })
+    }
+}
+
+/// Исход задачи для X-Attestation-Event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryState {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// Статус доставки (поле callback в GET /jobs/{id})
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub state: DeliveryState,
+    pub attempts: u32,
+    /// HTTP статус последнего ответа получателя
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl Delivery {
+    pub fn pending() -> Self {
+        Self {
+            state: DeliveryState::Pending,
+            attempts: 0,
+            last_status: None,
+            last_error: None,
+        }
+    }
+}
+
+/// Доставляет payload; on_attempt получает статус после каждой попытки
+pub async fn deliver(
+    callback: &Callback,
+    job_id: &str,
+    outcome: Outcome,
+    payload: &serde_json::Value,
+    mut on_attempt: impl FnMut(&Delivery),
+) {
+    let body = serde_json::to_vec(payload).expect("payload сериализуется");
+    let mut delivery = Delivery::pending();
+    let mut pause = FIRST_RETRY;
+
+    loop {
+        delivery.attempts += 1;
+        let retry = match send(callback, job_id, outcome, &body).await {
+            Ok(status) if (200..300).contains(&status) => {
+                delivery.state = DeliveryState::Delivered;
+                delivery.last_status = Some(status);
+                delivery.last_error = None;
+                false
+            }
+            Ok(status) => {
+                delivery.last_status = Some(status);
+                delivery.last_error = None;
+                // Остальные 4xx — ошибка получателя, повтор не поможет
+                status >= 500 || status == 408 || status == 429
+            }
+            Err(e) => {
+                delivery.last_error = Some(e);
+                true
+            }
+        };
+
+        if !retry || delivery.attempts >= MAX_ATTEMPTS {
+            if delivery.state != DeliveryState::Delivered {
+                delivery.state = DeliveryState::Failed;
+                warn!(
+                    "Webhook задачи {job_id}: не доставлен за {} попыток",
+                    delivery.attempts
+                );
+            } else {
+                info!("Webhook задачи {job_id} доставлен");
+            }
+            on_attempt(&delivery);
+            return;
+        }
+
+        on_attempt(&delivery);
+        tokio::time::sleep(pause).await;
+        pause *= 2;
+    }
+}
+
+/// Одна попытка: HTTP статус ответа или ошибка соединения / SSRF
+async fn send(
+    callback: &Callback,
+    job_id: &str,
+    outcome: Outcome,
+    body: &[u8],
+) -> Result<u16, String> {
+    // DNS мог измениться с момента создания задачи
+    let url = callback.url.clone();
+    let (parsed, addrs) = tokio::task::spawn_blocking(move || url_validator::resolve_url(&url))
+        .await
+        .map_err(|e| e.to_string())??;
+    let host = parsed.host_str().unwrap_or_default().to_string();
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(REQUEST_TIMEOUT)
+        .resolve_to_addrs(&host, &addrs)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let outcome = match outcome {
+        Outcome::Done => "done",
+        Outcome::Failed => "failed",
+    };
+
+    let response = client
+        .post(parsed)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "TLSOracle/1.0")
+        .header("X-Job-Id", job_id)
+        .header("X-Attestation-Event", outcome)
+        .header("X-Signature-Timestamp", timestamp.to_string())
+        .header(
+            "X-Signature-256",
+            signature(&callback.secret, timestamp, body),
+        )
+        .body(body.to_vec())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response.status().as_u16())
+}
+
+/// "sha256=" + hex(HMAC-SHA256(secret, "{timestamp}." + body))
+fn signature(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC принимает любой ключ");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // echo -n '1700000000.{"ok":true}' | openssl dgst -sha256 -hmac 'whsec_0123456789abcdef'
+        assert_eq!(
+            signature("whsec_0123456789abcdef", 1_700_000_000, br#"{"ok":true}"#),
+            "sha256=c81ee1c6f73100686b8d191f277e82a6e9f768264b55a74491d7407c92347987"
+        );
+    }
+
+    #[test]
+    fn test_callback_validation() {
+        assert!(Callback::new("https://hooks.example.com/x".into(), "short".into()).is_err());
+        assert!(Callback::new("http://127.0.0.1/hook".into(), "s".repeat(32)).is_err());
+        assert!(Callback::new("https://localhost/hook".into(), "s".repeat(32)).is_err());
+    }
+}
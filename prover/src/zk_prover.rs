@@ -1,15 +1,27 @@
-//! Генерация Groth16 ZK proof через snarkjs (Node.js subprocess)
+//! Генерация Groth16 ZK proof для MPC-TLS attestation
 //!
-//! Подготавливает input.json из SessionResult,
-//! вызывает `node zk/generate_proof.js input.json`,
-//! парсит stdout → ZkProofResult.
+//! По умолчанию — в процессе (native): witness считает circom WASM,
+//! proof — arkworks, с тем же proving key, что у snarkjs.
+//! Feature "node-prover" возвращает прежний путь через Node.js + snarkjs.
+//!
+//! Задачи проходят через общий пул (pool): ограничение воркеров, очередь
+//! и таймаут одинаковы для обоих путей.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tracing::info;
 
 use crate::mpc_session::SessionResult;
+use crate::progress::{Event, Progress};
+
+#[cfg(all(feature = "native-prover", not(feature = "node-prover")))]
+mod native;
+#[cfg(feature = "node-prover")]
+mod node;
+pub mod pool;
+
+#[cfg(not(any(feature = "native-prover", feature = "node-prover")))]
+compile_error!("Нужна feature native-prover или node-prover");
 
 /// Результат ZK proof generation
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,7 +36,9 @@ pub struct ZkProofResult {
     pub public_signals: [String; 4],
 }
 
-/// Промежуточная структура для десериализации snarkjs output
+/// Промежуточная структура для десериализации snarkjs output — эталон формата
+/// ZkProofResult и для native пути (см. тест native::tests)
+#[cfg(any(feature = "node-prover", test))]
 #[derive(Deserialize)]
 struct SnarkjsOutput {
     proof: SnarkjsProof,
@@ -32,6 +46,7 @@ struct SnarkjsOutput {
     public_signals: Vec<String>,
 }
 
+#[cfg(any(feature = "node-prover", test))]
 #[derive(Deserialize)]
 struct SnarkjsProof {
     pi_a: Vec<String>,
@@ -39,90 +54,72 @@ struct SnarkjsProof {
     pi_c: Vec<String>,
 }
 
-/// Генерирует Groth16 proof для MPC-TLS attestation
-///
-/// 1. Формирует attestation data для input_generator
-/// 2. Вызывает Node.js: zk/generate_proof.js
-/// 3. Парсит proof + public signals
-pub async fn generate_proof(session: &SessionResult) -> Result<ZkProofResult> {
-    // Определяем директорию zk/ относительно исполняемого файла
-    let zk_dir = get_zk_dir()?;
-
-    // Формируем данные для input_generator
-    let attestation_data = serde_json::json!({
-        "responseData": session.response_data,
-        "serverName": session.server_name,
-        "timestamp": session.timestamp,
-        "notaryPubkey": session.notary_pubkey_b64,
-    });
-
-    // Записываем во временный файл
-    let temp_dir = std::env::temp_dir();
-    let input_path = temp_dir.join(format!("zk_input_{}.json", std::process::id()));
-    std::fs::write(&input_path, serde_json::to_string(&attestation_data)?)
-        .context("Запись zk input")?;
-
-    info!("ZK proof: input записан в {:?}", input_path);
-
-    // Вызываем Node.js generate_proof.js
-    let generate_script = zk_dir.join("generate_proof.js");
-    let output = tokio::process::Command::new("node")
-        .arg(&generate_script)
-        .arg(&input_path)
-        .output()
-        .await
-        .context("Запуск node zk/generate_proof.js")?;
-
-    // Удаляем temp файл
-    let _ = std::fs::remove_file(&input_path);
+#[cfg(any(feature = "node-prover", test))]
+impl SnarkjsOutput {
+    fn into_result(self) -> Result<ZkProofResult> {
+        let SnarkjsOutput {
+            proof,
+            public_signals,
+        } = self;
+        if proof.pi_a.len() < 2
+            || proof.pi_b.len() < 2
+            || proof.pi_b.iter().any(|row| row.len() < 2)
+            || proof.pi_c.len() < 2
+            || public_signals.len() != 4
+        {
+            anyhow::bail!("Неверный формат snarkjs proof");
+        }
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("snarkjs ошибка: {stderr}");
+        Ok(ZkProofResult {
+            proof_a: [proof.pi_a[0].clone(), proof.pi_a[1].clone()],
+            proof_b: [
+                [proof.pi_b[0][0].clone(), proof.pi_b[0][1].clone()],
+                [proof.pi_b[1][0].clone(), proof.pi_b[1][1].clone()],
+            ],
+            proof_c: [proof.pi_c[0].clone(), proof.pi_c[1].clone()],
+            public_signals: [
+                public_signals[0].clone(),
+                public_signals[1].clone(),
+                public_signals[2].clone(),
+                public_signals[3].clone(),
+            ],
+        })
     }
+}
 
-    let stdout = String::from_utf8(output.stdout)
-        .context("snarkjs вывод не UTF-8")?;
-
-    // Парсим JSON output
-    let snarkjs: SnarkjsOutput =
-        serde_json::from_str(&stdout).context("Парсинг snarkjs output")?;
-
-    // Конвертируем в наш формат
-    if snarkjs.proof.pi_a.len() < 2
-        || snarkjs.proof.pi_b.len() < 2
-        || snarkjs.proof.pi_c.len() < 2
-        || snarkjs.public_signals.len() != 4
-    {
-        anyhow::bail!("Неверный формат snarkjs proof");
+/// Запускает ZK воркеры при старте (node-prover), чтобы первый proof не ждал
+/// загрузки snarkjs и zkey. Вызывать внутри tokio runtime.
+pub fn warm_up() {
+    #[cfg(feature = "node-prover")]
+    if let Err(e) = node::start() {
+        tracing::warn!("snarkjs worker не запущены: {e:#}");
     }
+}
 
-    Ok(ZkProofResult {
-        proof_a: [
-            snarkjs.proof.pi_a[0].clone(),
-            snarkjs.proof.pi_a[1].clone(),
-        ],
-        proof_b: [
-            [
-                snarkjs.proof.pi_b[0][0].clone(),
-                snarkjs.proof.pi_b[0][1].clone(),
-            ],
-            [
-                snarkjs.proof.pi_b[1][0].clone(),
-                snarkjs.proof.pi_b[1][1].clone(),
-            ],
-        ],
-        proof_c: [
-            snarkjs.proof.pi_c[0].clone(),
-            snarkjs.proof.pi_c[1].clone(),
-        ],
-        public_signals: [
-            snarkjs.public_signals[0].clone(),
-            snarkjs.public_signals[1].clone(),
-            snarkjs.public_signals[2].clone(),
-            snarkjs.public_signals[3].clone(),
-        ],
-    })
+/// Генерирует Groth16 proof для MPC-TLS attestation
+///
+/// Ошибки пула — pool::PoolError (очередь переполнена / таймаут).
+pub async fn generate_proof(
+    session: &SessionResult,
+    progress: &Progress,
+) -> Result<ZkProofResult> {
+    let pool = pool::global();
+    let permit = pool.acquire().await?;
+    progress.emit(Event::ProofStarted);
+    let started = std::time::Instant::now();
+
+    #[cfg(feature = "node-prover")]
+    let job = node::generate_proof(session, permit);
+    #[cfg(all(feature = "native-prover", not(feature = "node-prover")))]
+    let job = native::generate_proof(session, permit);
+
+    let result = tokio::time::timeout(pool.timeout, job)
+        .await
+        .map_err(|_| pool::PoolError::Timeout(pool.timeout))??;
+    progress.emit(Event::ProofGenerated {
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    });
+    Ok(result)
 }
 
 /// Находит директорию zk/ относительно текущего рабочего каталога или executable
@@ -151,5 +148,38 @@ fn get_zk_dir() -> Result<PathBuf> {
         }
     }
 
-    anyhow::bail!("Директория zk/ не найдена. Установите ZK_DIR или запустите из директории prover/")
+    anyhow::bail!(
+        "Директория zk/ не найдена. Установите ZK_DIR или запустите из директории prover/"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_snarkjs_output_format() {
+        let output: SnarkjsOutput = serde_json::from_value(json!({
+            "proof": {
+                "pi_a": ["1", "2", "1"],
+                "pi_b": [["3", "4"], ["5", "6"], ["1", "0"]],
+                "pi_c": ["7", "8", "1"],
+                "protocol": "groth16",
+            },
+            "publicSignals": ["10", "11", "12", "13"],
+        }))
+        .unwrap();
+        let result = output.into_result().unwrap();
+        assert_eq!(result.proof_b, [["3", "4"], ["5", "6"]]);
+        assert_eq!(result.public_signals[3], "13");
+
+        let short: SnarkjsOutput = serde_json::from_value(json!({
+            "proof": { "pi_a": ["1", "2"], "pi_b": [["3"], ["5", "6"]], "pi_c": ["7", "8"] },
+            "publicSignals": ["10", "11", "12", "13"],
+        }))
+        .unwrap();
+        assert!(short.into_result().is_err());
+    }
 }
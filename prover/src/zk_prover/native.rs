@@ -0,0 +1,379 @@
+//! Groth16 proof в процессе: circom WASM witness (wasmer) + arkworks
+//!
+//! Input circuit собирается так же, как в zk/generate_proof.js:
+//! 31-байтные little-endian чанки → Fr, pubkey → (x, y) усечённые до 253 бит,
+//! Poseidon — circomlib-совместимый. Формат результата совпадает с snarkjs:
+//! десятичные координаты, proof_b = [[x_c0, x_c1], [y_c0, y_c1]].
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{Context, Result};
+use ark_bn254::{Bn254, Fq, Fr};
+use ark_circom::{read_zkey, CircomReduction, WitnessCalculator};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::{prepare_verifying_key, Groth16, Proof, ProvingKey};
+use ark_relations::r1cs::ConstraintMatrices;
+use base64::Engine;
+use light_poseidon::{Poseidon, PoseidonHasher};
+use num_bigint::{BigInt, BigUint};
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::info;
+
+use super::{get_zk_dir, ZkProofResult};
+use crate::mpc_session::SessionResult;
+
+/// Блоков response_data в circuit (17 × 31 = 527 байт)
+const DATA_BLOCKS: usize = 17;
+/// Блоков server_name (8 × 31 = 248 байт)
+const SERVER_NAME_BLOCKS: usize = 8;
+/// Байт в чанке — меньше 32, чтобы значение всегда было < r
+const CHUNK_BYTES: usize = 31;
+
+type ZkeyData = (ProvingKey<Bn254>, ConstraintMatrices<Fr>);
+
+/// Proving key читается один раз (att_final.zkey — десятки МБ)
+static ZKEY: OnceLock<Arc<ZkeyData>> = OnceLock::new();
+
+/// Input circuit — те же сигналы, что в generate_proof.js
+struct CircuitInput {
+    data_commitment: Fr,
+    server_name_hash: Fr,
+    timestamp: u64,
+    notary_pubkey_hash: Fr,
+    response_data: Vec<Fr>,
+    server_name: Vec<Fr>,
+    notary_pubkey: [Fr; 2],
+}
+
+impl CircuitInput {
+    fn new(session: &SessionResult) -> Result<Self> {
+        let response_data = string_to_chunks(&session.response_data, DATA_BLOCKS);
+        let server_name = string_to_chunks(&session.server_name, SERVER_NAME_BLOCKS);
+        let pubkey = base64::engine::general_purpose::STANDARD
+            .decode(&session.notary_pubkey_b64)
+            .context("notary pubkey: невалидный base64")?;
+        let notary_pubkey = pubkey_to_fr_pair(&pubkey)?;
+
+        // Двухуровневый Poseidon для data (17 блоков > 16 входов)
+        let data_hash1 = poseidon(&response_data[..9])?;
+        let data_hash2 = poseidon(&response_data[9..])?;
+
+        Ok(Self {
+            data_commitment: poseidon(&[data_hash1, data_hash2])?,
+            server_name_hash: poseidon(&server_name)?,
+            timestamp: session.timestamp,
+            notary_pubkey_hash: poseidon(&notary_pubkey)?,
+            response_data,
+            server_name,
+            notary_pubkey,
+        })
+    }
+
+    /// Входы для WitnessCalculator: имя сигнала → значения
+    fn signals(&self) -> Vec<(String, Vec<BigInt>)> {
+        let big = |f: &Fr| BigInt::from(BigUint::from(f.into_bigint()));
+        vec![
+            ("dataCommitment".into(), vec![big(&self.data_commitment)]),
+            ("serverNameHash".into(), vec![big(&self.server_name_hash)]),
+            ("timestamp".into(), vec![BigInt::from(self.timestamp)]),
+            (
+                "notaryPubkeyHash".into(),
+                vec![big(&self.notary_pubkey_hash)],
+            ),
+            (
+                "responseData".into(),
+                self.response_data.iter().map(big).collect(),
+            ),
+            (
+                "serverName".into(),
+                self.server_name.iter().map(big).collect(),
+            ),
+            (
+                "notaryPubkey".into(),
+                self.notary_pubkey.iter().map(big).collect(),
+            ),
+        ]
+    }
+}
+
+/// 1. Считает input circuit (Poseidon хеши) из SessionResult
+/// 2. Witness — attestation.wasm через wasmer
+/// 3. Groth16 proof с att_final.zkey, самопроверка по vk из zkey
+///
+/// Блокирующий поток нельзя прервать: при таймауте пула permit остаётся
+/// у потока до конца вычисления, и воркер не считается свободным раньше.
+pub async fn generate_proof(
+    session: &SessionResult,
+    permit: OwnedSemaphorePermit,
+) -> Result<ZkProofResult> {
+    let zk_dir = get_zk_dir()?;
+    let input = CircuitInput::new(session)?;
+
+    // CPU-bound: не блокируем runtime
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        prove(&zk_dir, &input)
+    })
+    .await
+    .context("ZK proof task")?
+}
+
+fn prove(zk_dir: &Path, input: &CircuitInput) -> Result<ZkProofResult> {
+    let started = std::time::Instant::now();
+    let zkey = load_zkey(&zk_dir.join("attestation_final.zkey"))?;
+    let (params, matrices) = zkey.as_ref();
+
+    let mut store = wasmer::Store::default();
+    let mut witness_calculator = WitnessCalculator::new(
+        &mut store,
+        zk_dir.join("attestation_js").join("attestation.wasm"),
+    )
+    .context("Загрузка attestation.wasm")?;
+    let witness = witness_calculator
+        .calculate_witness_element::<Fr, _>(&mut store, input.signals(), false)
+        .context("Расчёт witness")?;
+
+    let mut rng = rand::thread_rng();
+    let r = Fr::rand(&mut rng);
+    let s = Fr::rand(&mut rng);
+    let proof = Groth16::<Bn254, CircomReduction>::create_proof_with_reduction_and_matrices(
+        params,
+        r,
+        s,
+        matrices,
+        matrices.num_instance_variables,
+        matrices.num_constraints,
+        &witness,
+    )
+    .context("Groth16 prove")?;
+
+    // witness[0] = 1, затем public signals в порядке объявления
+    let public_inputs = &witness[1..matrices.num_instance_variables];
+    anyhow::ensure!(public_inputs.len() == 4, "Ожидается 4 public signals");
+    let verified = Groth16::<Bn254, CircomReduction>::verify_proof(
+        &prepare_verifying_key(&params.vk),
+        &proof,
+        public_inputs,
+    )
+    .context("Groth16 verify")?;
+    anyhow::ensure!(verified, "Сгенерированный proof не проходит верификацию");
+
+    info!("ZK proof (native): {:?}", started.elapsed());
+    Ok(to_result(&proof, public_inputs))
+}
+
+/// Proof и public signals в формате snarkjs: десятичные координаты,
+/// proof_b = [[x_c0, x_c1], [y_c0, y_c1]]
+fn to_result(proof: &Proof<Bn254>, public_inputs: &[Fr]) -> ZkProofResult {
+    ZkProofResult {
+        proof_a: [fq_decimal(&proof.a.x), fq_decimal(&proof.a.y)],
+        proof_b: [
+            [fq_decimal(&proof.b.x.c0), fq_decimal(&proof.b.x.c1)],
+            [fq_decimal(&proof.b.y.c0), fq_decimal(&proof.b.y.c1)],
+        ],
+        proof_c: [fq_decimal(&proof.c.x), fq_decimal(&proof.c.y)],
+        public_signals: [
+            fr_decimal(&public_inputs[0]),
+            fr_decimal(&public_inputs[1]),
+            fr_decimal(&public_inputs[2]),
+            fr_decimal(&public_inputs[3]),
+        ],
+    }
+}
+
+fn load_zkey(path: &Path) -> Result<Arc<ZkeyData>> {
+    if let Some(zkey) = ZKEY.get() {
+        return Ok(zkey.clone());
+    }
+    let file = File::open(path).with_context(|| format!("Открытие {}", path.display()))?;
+    let zkey = read_zkey(&mut BufReader::new(file)).context("Чтение zkey")?;
+    info!("ZK proof: proving key загружен из {}", path.display());
+    // При гонке двух первых запросов остаётся первый загруженный
+    Ok(ZKEY.get_or_init(|| Arc::new(zkey)).clone())
+}
+
+fn poseidon(inputs: &[Fr]) -> Result<Fr> {
+    let mut hasher = Poseidon::<Fr>::new_circom(inputs.len())?;
+    Ok(hasher.hash(inputs)?)
+}
+
+/// Bytes (little-endian, ≤ 31 байт) → Fr
+fn bytes_to_fr(bytes: &[u8]) -> Fr {
+    Fr::from_le_bytes_mod_order(bytes)
+}
+
+/// Строка → num_chunks чанков по 31 байт (недостающие — 0, лишнее отбрасывается)
+fn string_to_chunks(s: &str, num_chunks: usize) -> Vec<Fr> {
+    let bytes = s.as_bytes();
+    (0..num_chunks)
+        .map(|i| {
+            let start = i * CHUNK_BYTES;
+            if start < bytes.len() {
+                bytes_to_fr(&bytes[start..bytes.len().min(start + CHUNK_BYTES)])
+            } else {
+                Fr::from(0u64)
+            }
+        })
+        .collect()
+}
+
+/// secp256k1 pubkey → (x, y), каждая координата усечена до 253 бит
+fn pubkey_to_fr_pair(bytes: &[u8]) -> Result<[Fr; 2]> {
+    let (x, y): (&[u8], &[u8]) = match bytes.len() {
+        33 => (&bytes[1..33], &[]),
+        65 => (&bytes[1..33], &bytes[33..65]),
+        32 => (bytes, &[]),
+        n => anyhow::bail!("Неизвестный формат pubkey: {n} байт"),
+    };
+    Ok([mask_253(x), mask_253(y)])
+}
+
+/// Little-endian bytes → Fr через маску младших 253 бит (как в JS: x mod r, затем & mask)
+fn mask_253(bytes: &[u8]) -> Fr {
+    let mut le = bytes_to_fr(bytes).into_bigint().to_bytes_le();
+    le.resize(32, 0);
+    le[31] &= 0x1f;
+    Fr::from_le_bytes_mod_order(&le)
+}
+
+fn fq_decimal(f: &Fq) -> String {
+    BigUint::from(f.into_bigint()).to_string()
+}
+
+fn fr_decimal(f: &Fr) -> String {
+    BigUint::from(f.into_bigint()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ark_bn254::{Fq2, G1Affine, G2Affine};
+    use ark_groth16::VerifyingKey;
+    use serde_json::{json, Value};
+
+    use super::super::SnarkjsOutput;
+    use super::*;
+
+    // Groth16 proof с 4 public signals контракта [777, 888, 1700000000, 12345]
+    // и его vk — тот же ключ контракт регистрирует во внешнем verifier
+    // (tests::test_groth16_fixture_through_external_verifier)
+    const FIXTURE_PROOF: &str =
+        include_str!("../../../verifier/tests/fixtures/attestation_groth16_proof.json");
+    const FIXTURE_PUBLIC: &str =
+        include_str!("../../../verifier/tests/fixtures/attestation_groth16_public.json");
+    const FIXTURE_VK: &str =
+        include_str!("../../../verifier/tests/fixtures/attestation_groth16_verification_key.json");
+
+    fn fq(v: &Value) -> Fq {
+        Fq::from_str(v.as_str().unwrap()).unwrap()
+    }
+
+    /// snarkjs G1 [x, y, 1]
+    fn g1(v: &Value) -> G1Affine {
+        G1Affine::new(fq(&v[0]), fq(&v[1]))
+    }
+
+    /// snarkjs G2 [[x_c0, x_c1], [y_c0, y_c1], [1, 0]]
+    fn g2(v: &Value) -> G2Affine {
+        G2Affine::new(
+            Fq2::new(fq(&v[0][0]), fq(&v[0][1])),
+            Fq2::new(fq(&v[1][0]), fq(&v[1][1])),
+        )
+    }
+
+    fn fixture_vk() -> VerifyingKey<Bn254> {
+        let vk: Value = serde_json::from_str(FIXTURE_VK).unwrap();
+        VerifyingKey {
+            alpha_g1: g1(&vk["vk_alpha_1"]),
+            beta_g2: g2(&vk["vk_beta_2"]),
+            gamma_g2: g2(&vk["vk_gamma_2"]),
+            delta_g2: g2(&vk["vk_delta_2"]),
+            gamma_abc_g1: vk["IC"].as_array().unwrap().iter().map(g1).collect(),
+        }
+    }
+
+    #[test]
+    fn test_result_matches_snarkjs_and_verifies() {
+        let proof_json: Value = serde_json::from_str(FIXTURE_PROOF).unwrap();
+        let public_json: Value = serde_json::from_str(FIXTURE_PUBLIC).unwrap();
+        let proof = Proof::<Bn254> {
+            a: g1(&proof_json["pi_a"]),
+            b: g2(&proof_json["pi_b"]),
+            c: g1(&proof_json["pi_c"]),
+        };
+        let public_inputs: Vec<Fr> = public_json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| Fr::from_str(s.as_str().unwrap()).unwrap())
+            .collect();
+
+        // Native результат совпадает с тем, что node путь получает из snarkjs
+        let native = to_result(&proof, &public_inputs);
+        let snarkjs: SnarkjsOutput = serde_json::from_value(json!({
+            "proof": proof_json,
+            "publicSignals": public_json,
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&native).unwrap(),
+            serde_json::to_value(snarkjs.into_result().unwrap()).unwrap()
+        );
+
+        // Proof, собранный обратно из ZkProofResult, проходит vk
+        let parsed = Proof::<Bn254> {
+            a: g1(&json!(native.proof_a)),
+            b: g2(&json!(native.proof_b)),
+            c: g1(&json!(native.proof_c)),
+        };
+        let signals: Vec<Fr> = native
+            .public_signals
+            .iter()
+            .map(|s| Fr::from_str(s).unwrap())
+            .collect();
+        let pvk = prepare_verifying_key(&fixture_vk());
+        assert!(Groth16::<Bn254, CircomReduction>::verify_proof(&pvk, &parsed, &signals).unwrap());
+
+        // Другие public signals — отказ
+        let mut wrong = signals.clone();
+        wrong[0] += Fr::from(1u64);
+        assert!(!Groth16::<Bn254, CircomReduction>::verify_proof(&pvk, &parsed, &wrong).unwrap());
+    }
+
+    #[test]
+    fn test_poseidon_circomlib_vector() {
+        // circomlibjs: poseidon([1, 2])
+        let hash = poseidon(&[Fr::from(1u64), Fr::from(2u64)]).unwrap();
+        assert_eq!(
+            fr_decimal(&hash),
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+        );
+    }
+
+    #[test]
+    fn test_string_to_chunks() {
+        let chunks = string_to_chunks("ab", 3);
+        // 'a' + 'b' * 256
+        assert_eq!(chunks[0], Fr::from(0x6261u64));
+        assert_eq!(chunks[1], Fr::from(0u64));
+        assert_eq!(chunks.len(), 3);
+
+        let long = "x".repeat(40);
+        let chunks = string_to_chunks(&long, 2);
+        assert_eq!(chunks[1], bytes_to_fr(&[b'x'; 9]));
+    }
+
+    #[test]
+    fn test_pubkey_masked_to_253_bits() {
+        let mut compressed = vec![0x02];
+        compressed.extend([0xff; 32]);
+        let [x, y] = pubkey_to_fr_pair(&compressed).unwrap();
+        assert_eq!(y, Fr::from(0u64));
+        assert!(BigUint::from(x.into_bigint()).bits() <= 253);
+        assert!(pubkey_to_fr_pair(&[0; 20]).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Groth16 proof через snarkjs (feature "node-prover")
+//!
+//! Пул из ZK_WORKERS долгоживущих процессов `node zk/worker.js`, запускаемых
+//! при старте prover: snarkjs, Poseidon, WASM и zkey загружены один раз.
+//! Задачи — JSON-RPC 2.0 построчно по stdin/stdout. Worker с ошибкой
+//! ввода-вывода или вышедший по таймауту убивается и заменяется новым;
+//! простаивающие пингуются на месте раз в HEALTH_INTERVAL.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{OwnedMutexGuard, OwnedSemaphorePermit};
+use tracing::{info, warn};
+
+use super::{get_zk_dir, SnarkjsOutput, ZkProofResult};
+use crate::mpc_session::SessionResult;
+
+/// Загрузка snarkjs + zkey при старте worker
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+const HEALTH_INTERVAL: Duration = Duration::from_secs(30);
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+static WORKERS: OnceLock<NodeWorkers> = OnceLock::new();
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    id: Option<u64>,
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+/// Процесс node zk/worker.js
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: u64,
+}
+
+impl Worker {
+    async fn spawn(zk_dir: &Path) -> Result<Self> {
+        let mut child = Command::new("node")
+            .arg(zk_dir.join("worker.js"))
+            .current_dir(zk_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Запуск node zk/worker.js")?;
+        let stdin = child.stdin.take().context("stdin worker")?;
+        let stdout = BufReader::new(child.stdout.take().context("stdout worker")?).lines();
+        let mut worker = Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 0,
+        };
+
+        // Первый ping отвечает после загрузки WASM и zkey
+        tokio::time::timeout(STARTUP_TIMEOUT, worker.call("ping", Value::Null))
+            .await
+            .context("snarkjs worker не запустился вовремя")??
+            .map_err(|e| anyhow::anyhow!("snarkjs worker: {e}"))?;
+        info!("snarkjs worker запущен (pid {:?})", worker.child.id());
+        Ok(worker)
+    }
+
+    /// Внешний Err — worker неисправен (процесс, канал, протокол),
+    /// внутренний — ошибка задачи, worker можно использовать дальше
+    async fn call(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<std::result::Result<Value, String>> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut line = serde_json::to_string(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        }))?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        loop {
+            let line = self
+                .stdout
+                .next_line()
+                .await?
+                .context("snarkjs worker завершился")?;
+            let response: RpcResponse =
+                serde_json::from_str(&line).context("Ответ snarkjs worker не JSON-RPC")?;
+            // Ответ на задачу, прерванную до этого — пропускаем
+            if response.id != Some(id) {
+                continue;
+            }
+            return Ok(match (response.result, response.error) {
+                (_, Some(error)) => Err(error.message),
+                (Some(result), None) => Ok(result),
+                (None, None) => anyhow::bail!("Ответ snarkjs worker без result"),
+            });
+        }
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+/// Слот пула: worker живёт в нём между задачами; None — не запущен или упал
+type Slot = Arc<tokio::sync::Mutex<Option<Worker>>>;
+
+/// ZK_WORKERS слотов — по одному на permit пула, поэтому процессов не больше
+/// ZK_WORKERS и у каждой задачи есть свободный слот. Health-check пингует
+/// свободные слоты на месте: занятый пингом слот задача просто пропускает
+struct NodeWorkers {
+    zk_dir: PathBuf,
+    slots: Vec<Slot>,
+}
+
+impl NodeWorkers {
+    /// Первый вызов запускает все worker и health-check
+    fn global() -> Result<&'static Self> {
+        if let Some(workers) = WORKERS.get() {
+            return Ok(workers);
+        }
+        let zk_dir = get_zk_dir()?;
+        let size = super::pool::global().size();
+        let mut first = false;
+        let workers = WORKERS.get_or_init(|| {
+            first = true;
+            Self {
+                zk_dir,
+                slots: (0..size).map(|_| Slot::default()).collect(),
+            }
+        });
+        if first {
+            for slot in &workers.slots {
+                if let Ok(slot) = slot.clone().try_lock_owned() {
+                    workers.refill(slot);
+                }
+            }
+            tokio::spawn(workers.health_loop());
+        }
+        Ok(workers)
+    }
+
+    /// Свободный слот; если все заняты пингом или перезапуском — первый освободившийся
+    async fn checkout(&self) -> OwnedMutexGuard<Option<Worker>> {
+        for slot in &self.slots {
+            if let Ok(guard) = slot.clone().try_lock_owned() {
+                return guard;
+            }
+        }
+        let waits = self
+            .slots
+            .iter()
+            .map(|slot| Box::pin(slot.clone().lock_owned()));
+        futures::future::select_all(waits).await.0
+    }
+
+    /// Запуск worker в слоте в фоне, чтобы задача не ждала старта; слот
+    /// занят до конца запуска — задача, ждущая его, получит новый worker
+    fn refill(&'static self, mut slot: OwnedMutexGuard<Option<Worker>>) {
+        tokio::spawn(async move {
+            match Worker::spawn(&self.zk_dir).await {
+                Ok(worker) => *slot = Some(worker),
+                Err(e) => warn!("Запуск snarkjs worker не удался: {e:#}"),
+            }
+        });
+    }
+
+    async fn health_loop(&'static self) {
+        let mut interval = tokio::time::interval(HEALTH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            for slot in &self.slots {
+                // Занятый слот (задача, перезапуск) пропускаем
+                let Ok(mut slot) = slot.clone().try_lock_owned() else {
+                    continue;
+                };
+                let healthy = match slot.as_mut() {
+                    Some(worker) => {
+                        let ping =
+                            tokio::time::timeout(PING_TIMEOUT, worker.call("ping", Value::Null));
+                        matches!(ping.await, Ok(Ok(Ok(_))))
+                    }
+                    None => false,
+                };
+                if !healthy {
+                    if slot.take().is_some() {
+                        warn!("snarkjs worker не отвечает на ping, перезапуск");
+                    }
+                    self.refill(slot);
+                }
+            }
+        }
+    }
+}
+
+/// 1. Берёт свободный snarkjs worker (упавший в простое — перезапускает)
+/// 2. Отправляет attestation data задачей prove
+/// 3. Парсит proof + public signals
+///
+/// permit пула держится до выхода. На время задачи worker вынут из слота:
+/// при таймауте future отменяется вместе с worker (kill_on_drop), а пустой
+/// слот заполнит следующая задача или health-check.
+pub async fn generate_proof(
+    session: &SessionResult,
+    _permit: OwnedSemaphorePermit,
+) -> Result<ZkProofResult> {
+    let workers = NodeWorkers::global()?;
+    let mut slot = workers.checkout().await;
+    let mut worker = match slot.take() {
+        Some(mut worker) => {
+            if worker.is_alive() {
+                worker
+            } else {
+                warn!("snarkjs worker завершился в простое, заменяем");
+                Worker::spawn(&workers.zk_dir).await?
+            }
+        }
+        None => Worker::spawn(&workers.zk_dir).await?,
+    };
+
+    let attestation_data = json!({
+        "responseData": session.response_data,
+        "serverName": session.server_name,
+        "timestamp": session.timestamp,
+        "notaryPubkey": session.notary_pubkey_b64,
+    });
+
+    let result = match worker.call("prove", attestation_data).await {
+        Ok(result) => {
+            *slot = Some(worker);
+            result.map_err(|e| anyhow::anyhow!("snarkjs ошибка: {e}"))?
+        }
+        Err(e) => {
+            drop(worker);
+            workers.refill(slot);
+            return Err(e.context("prove в snarkjs worker"));
+        }
+    };
+
+    let snarkjs: SnarkjsOutput =
+        serde_json::from_value(result).context("Парсинг snarkjs output")?;
+    snarkjs.into_result()
+}
+
+/// Запуск ZK_WORKERS worker при старте prover
+pub fn start() -> Result<()> {
+    NodeWorkers::global().map(drop)
+}
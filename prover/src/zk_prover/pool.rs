@@ -0,0 +1,139 @@
+//! Ограниченный пул ZK proof задач: воркеры, очередь, таймаут
+//!
+//! Proof занимает ядро CPU на секунды, поэтому одновременно выполняется не
+//! больше ZK_WORKERS задач, ещё до ZK_QUEUE ждут своей очереди, остальные
+//! отклоняются сразу. Задача дольше ZK_TIMEOUT_SECS прерывается.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const DEFAULT_QUEUE: usize = 32;
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+static POOL: OnceLock<ProofPool> = OnceLock::new();
+
+/// Отказ пула — не ошибка proof как таковая (HTTP 503 / 504)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoolError {
+    QueueFull,
+    Timeout(Duration),
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QueueFull => write!(f, "Очередь ZK proof переполнена"),
+            Self::Timeout(t) => write!(f, "ZK proof не уложился в {} с", t.as_secs()),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {}
+
+pub struct ProofPool {
+    workers: Arc<Semaphore>,
+    size: usize,
+    waiting: AtomicUsize,
+    max_queue: usize,
+    pub timeout: Duration,
+}
+
+/// Уменьшает счётчик ожидающих и при отмене запроса (drop future)
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ProofPool {
+    pub fn new(workers: usize, max_queue: usize, timeout: Duration) -> Self {
+        let size = workers.max(1);
+        Self {
+            workers: Arc::new(Semaphore::new(size)),
+            size,
+            waiting: AtomicUsize::new(0),
+            max_queue,
+            timeout,
+        }
+    }
+
+    /// ZK_WORKERS (по умолчанию — число ядер), ZK_QUEUE, ZK_TIMEOUT_SECS
+    fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+        let workers = var("ZK_WORKERS")
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+        Self::new(
+            workers,
+            var("ZK_QUEUE").unwrap_or(DEFAULT_QUEUE),
+            Duration::from_secs(var("ZK_TIMEOUT_SECS").map_or(DEFAULT_TIMEOUT_SECS, |s| s as u64)),
+        )
+    }
+
+    /// Число воркеров (ZK_WORKERS)
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Место воркера; задача держит permit до своего фактического завершения
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, PoolError> {
+        if let Ok(permit) = self.workers.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+        if self.waiting.fetch_add(1, Ordering::SeqCst) >= self.max_queue {
+            self.waiting.fetch_sub(1, Ordering::SeqCst);
+            return Err(PoolError::QueueFull);
+        }
+        let _guard = WaitingGuard(&self.waiting);
+        Ok(self
+            .workers
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("семафор пула не закрывается"))
+    }
+}
+
+/// Пул процесса (настройки из env при первом proof)
+pub fn global() -> &'static ProofPool {
+    POOL.get_or_init(ProofPool::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_queue_limit() {
+        let pool = Arc::new(ProofPool::new(1, 1, Duration::from_secs(1)));
+        let busy = pool.acquire().await.unwrap();
+
+        // Второй ждёт в очереди, третий отклоняется
+        let waiter = {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.acquire().await.map(drop) })
+        };
+        while pool.waiting.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(pool.acquire().await.err(), Some(PoolError::QueueFull));
+
+        drop(busy);
+        assert_eq!(waiter.await.unwrap(), Ok(()));
+        assert_eq!(pool.waiting.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_leaves_queue() {
+        let pool = ProofPool::new(1, 1, Duration::from_secs(1));
+        let _busy = pool.acquire().await.unwrap();
+        let waiting = tokio::time::timeout(Duration::from_millis(10), pool.acquire()).await;
+        assert!(waiting.is_err());
+        assert_eq!(pool.waiting.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,67 @@
+/**
+ * Долгоживущий snarkjs worker (запускается из prover, zk_prover/node.rs)
+ *
+ * Poseidon, circuit WASM и zkey загружаются один раз при старте. Задачи —
+ * JSON-RPC 2.0, по одному объекту на строку:
+ *   stdin:  {"jsonrpc":"2.0","id":1,"method":"prove","params":{attestation}}
+ *   stdout: {"jsonrpc":"2.0","id":1,"result":{"proof":{...},"publicSignals":[...]}}
+ * Методы: ping (готовность / health-check), prove.
+ * stdout — только протокол; логи идут в stderr.
+ */
+
+import { readFileSync } from "fs";
+import { createInterface } from "readline";
+
+import {
+  buildCircuitInput,
+  buildPoseidon,
+  snarkjs,
+  wasmPath,
+  zkeyPath,
+} from "./circuit.js";
+
+console.log = console.error;
+
+const poseidon = await buildPoseidon();
+// fastfile принимает { type: "mem" } — файлы не перечитываются на каждый proof
+const wasm = { type: "mem", data: readFileSync(wasmPath) };
+const zkey = { type: "mem", data: readFileSync(zkeyPath) };
+
+const methods = {
+  ping: async () => "pong",
+  prove: async (attestation) => {
+    const input = buildCircuitInput(poseidon, attestation);
+    const { proof, publicSignals } = await snarkjs.groth16.fullProve(
+      input,
+      wasm,
+      zkey,
+    );
+    return { proof, publicSignals };
+  },
+};
+
+function reply(message) {
+  process.stdout.write(JSON.stringify({ jsonrpc: "2.0", ...message }) + "\n");
+}
+
+// Задачи выполняются по очереди: prover отдаёт worker одной задаче за раз
+const lines = createInterface({ input: process.stdin });
+for await (const line of lines) {
+  if (!line.trim()) continue;
+  let id = null;
+  try {
+    const request = JSON.parse(line);
+    id = request.id ?? null;
+    const method = methods[request.method];
+    if (!method) {
+      reply({ id, error: { code: -32601, message: `Нет метода ${request.method}` } });
+      continue;
+    }
+    reply({ id, result: await method(request.params) });
+  } catch (err) {
+    reply({ id, error: { code: -32000, message: String(err?.message ?? err) } });
+  }
+}
+
+// stdin закрыт — prover завершился
+process.exit(0);
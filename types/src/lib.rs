@@ -0,0 +1,431 @@
+//! Типы API tls-oracle — общие для контракта и контрактов-потребителей
+//!
+//! Контракт хранит их в состоянии (Borsh) и отдаёт из методов (JSON),
+//! `tls-oracle-consumer` использует их в bindings `ext_tls_oracle`. Borsh
+//! layout типов с `borsh` — часть состояния контракта: новые поля только
+//! через версию схемы (см. `VersionedAttestation` в контракте).
+
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8, U128};
+use near_sdk::{near, AccountId};
+
+pub use groth16_verifier::ProofSystem;
+
+// ── Аттестации ───────────────────────────────────────────────
+
+/// Аттестация — ZK-верифицированные данные с веб-сайта
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation {
+    pub id: u64,
+    pub source_url: String,
+    pub server_name: String,
+    /// UNIX timestamp TLS сессии, секунды
+    pub timestamp: u64,
+    pub response_data: String,
+    /// Poseidon commitment данных (public signal)
+    pub data_commitment: String,
+    /// Poseidon hash server_name (public signal)
+    pub server_name_hash: String,
+    /// Poseidon hash notary pubkey (public signal)
+    pub notary_pubkey_hash: String,
+    pub submitter: AccountId,
+    pub block_height: u64,
+    /// Подпись нотариуса верифицирована on-chain через ecrecover
+    #[serde(default)]
+    pub sig_verified: bool,
+    /// SHA-256(method || 0x00 || content_type || 0x00 || body), hex —
+    /// тело запроса (POST / GraphQL), входит в подписанное сообщение
+    #[serde(default)]
+    pub request_hash: Option<String>,
+}
+
+/// Аттестация со статусом финальности (JSON — надмножество Attestation)
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationView {
+    #[serde(flatten)]
+    pub attestation: Attestation,
+    pub finalized: bool,
+    /// Конец окна оспаривания (UNIX, секунды); None — окна нет
+    pub challenge_ends_at: Option<u64>,
+    /// Признана недействительной по итогам dispute
+    pub invalidated: bool,
+}
+
+/// Аргументы `submit_attestation_borsh`
+///
+/// Элементы поля — 32 байта little-endian, раскладка та же, что у snarkjs:
+/// proof_b = [[x_re, x_im], [y_re, y_im]]
+#[near(serializers = [borsh])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubmitAttestationArgs {
+    pub source_url: String,
+    pub server_name: String,
+    pub timestamp: u64,
+    pub response_data: String,
+    pub proof_a: [[u8; 32]; 2],
+    pub proof_b: [[[u8; 32]; 2]; 2],
+    pub proof_c: [[u8; 32]; 2],
+    /// [dataCommitment, serverNameHash, timestamp, notaryPubkeyHash]
+    pub public_signals: [[u8; 32]; 4],
+    /// Подпись нотариуса r||s
+    pub notary_signature: [u8; 64],
+    pub notary_sig_v: u8,
+    /// SHA-256 описания запроса с телом (см. Attestation::request_hash)
+    pub request_hash: Option<[u8; 32]>,
+}
+
+// ── TLSNotary presentation ───────────────────────────────────
+
+/// Направление транскрипта
+#[near(serializers = [json])]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// Подписанный tlsn header + раскрытые части транскрипта
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsnPresentation {
+    /// BCS(Header) — ровно те байты, которые подписал нотариус
+    pub header: Base64VecU8,
+    /// Подпись нотариуса r||s (hex, 64 bytes)
+    pub signature: String,
+    /// SHA-256 хеши полей Body (hex, 32 bytes) — листья Merkle дерева в порядке FieldId
+    pub field_hashes: Vec<String>,
+    /// Hash preimage поля ConnectionInfo: domain separator || BCS(ConnectionInfo)
+    pub connection_info: Base64VecU8,
+    /// Индекс ConnectionInfo в field_hashes
+    pub connection_info_index: u32,
+    /// Подпись нотариуса r||s (hex) над server identity
+    pub server_identity_signature: String,
+    /// Раскрытые диапазоны транскрипта
+    pub reveals: Vec<TranscriptReveal>,
+}
+
+/// Раскрытый диапазон транскрипта с открытием PlaintextHash коммитмента
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptReveal {
+    pub direction: Direction,
+    /// Начало диапазона в транскрипте (байты)
+    pub start: u64,
+    /// Конец диапазона (не включительно)
+    pub end: u64,
+    /// Plaintext диапазона
+    pub data: Base64VecU8,
+    /// Blinder коммитмента (16 bytes)
+    pub blinder: Base64VecU8,
+    /// Hash preimage поля PlaintextHash: domain separator || BCS(PlaintextHash)
+    pub field: Base64VecU8,
+    /// Индекс поля в field_hashes
+    pub field_index: u32,
+}
+
+// ── Нотариусы ────────────────────────────────────────────────
+
+/// Информация о доверенном нотариусе
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotaryInfo {
+    /// Poseidon hash secp256k1 pubkey
+    pub pubkey_hash: String,
+    /// Raw uncompressed secp256k1 pubkey x||y (hex, 128 chars = 64 bytes)
+    /// Нужен для ecrecover верификации подписи
+    pub raw_pubkey: Option<String>,
+    pub name: String,
+    pub url: String,
+    pub added_by: AccountId,
+    pub added_at: u64,
+}
+
+/// Счётчики нотариуса
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotaryStats {
+    /// Сохранённых аттестаций, подписанных нотариусом
+    pub attestations_signed: u64,
+    /// Количество различных доменов (server_name)
+    pub domains_served: u64,
+    /// Блок последней аттестации
+    pub last_active_block: Option<u64>,
+    /// Последний submitter
+    pub last_submitter: Option<AccountId>,
+    /// Сколько раз нотариус удалялся из доверенных
+    pub revocations: u32,
+    /// Оспоренные аттестации нотариуса (dispute признан)
+    pub disputes_upheld: u32,
+}
+
+/// Нотариус вместе со статистикой
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotaryWithStats {
+    pub info: NotaryInfo,
+    pub stats: NotaryStats,
+}
+
+// ── Комиссии ─────────────────────────────────────────────────
+
+/// Настройки комиссий
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeConfig {
+    /// Комиссия по умолчанию за аттестацию (yoctoNEAR)
+    pub fee: U128,
+    /// Доля treasury в basis points (0..=10000), остаток — нотариусу
+    pub treasury_bps: u16,
+    pub treasury: AccountId,
+}
+
+/// Оплаченная комиссия аттестации
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Fee {
+    /// Attached deposit, yoctoNEAR
+    Near(U128),
+    /// Списано из предоплаты в NEP-141 токене
+    Token { token_id: AccountId, amount: U128 },
+}
+
+/// Держатель токенового баланса в контракте
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum FtHolder {
+    /// Предоплата / выигранные bounties аккаунта
+    Account(AccountId),
+    /// Bounty за аттестацию домена (server_name)
+    Bounty(String),
+    /// Награды нотариуса (Poseidon hash pubkey)
+    Notary(String),
+    Treasury,
+}
+
+/// Токен из whitelist
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FtTokenInfo {
+    pub token_id: AccountId,
+    /// Комиссия за аттестацию в минимальных единицах токена
+    pub fee: U128,
+    /// Цена подписки за период (None — подписка в токене не продаётся)
+    pub subscription_price: Option<U128>,
+}
+
+// ── Внешний verifier ─────────────────────────────────────────
+
+/// Внешний verifier: контракт + id verification key в его реестре
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifierConfig {
+    pub account_id: AccountId,
+    pub vk_id: String,
+    /// id PLONK verification key (None — PLONK через внешний verifier не принимается)
+    #[serde(default)]
+    pub plonk_vk_id: Option<String>,
+}
+
+// ── Governance ───────────────────────────────────────────────
+
+/// Политика голосования
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernancePolicy {
+    /// Кворум: доля общего веса совета в basis points (1..=10000)
+    pub quorum_bps: u16,
+    /// Длительность голосования, секунды
+    pub voting_period_secs: u64,
+}
+
+/// Член совета с весом голоса
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CouncilMember {
+    pub account_id: AccountId,
+    pub weight: u32,
+}
+
+/// Изменение, которое вносит предложение
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ProposalKind {
+    #[serde(rename_all = "camelCase")]
+    AddNotary {
+        pubkey_hash: String,
+        name: String,
+        url: String,
+        raw_pubkey: Option<String>,
+    },
+    /// Обновить метаданные / raw_pubkey существующего нотариуса
+    #[serde(rename_all = "camelCase")]
+    UpdateNotary {
+        pubkey_hash: String,
+        name: String,
+        url: String,
+        raw_pubkey: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    RemoveNotary {
+        pubkey_hash: String,
+    },
+    SetPolicy {
+        policy: GovernancePolicy,
+    },
+    /// weight = None — исключить из совета
+    #[serde(rename_all = "camelCase")]
+    SetCouncilMember {
+        account_id: AccountId,
+        weight: Option<u32>,
+    },
+    /// Решение по открытому dispute
+    #[serde(rename_all = "camelCase")]
+    ResolveDispute {
+        dispute_id: u64,
+        upheld: bool,
+    },
+    /// Вызов owner-метода от имени контракта (args — JSON аргументы метода)
+    #[serde(rename_all = "camelCase")]
+    FunctionCall {
+        method_name: String,
+        args: Base64VecU8,
+    },
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProposalStatus {
+    Active,
+    Executed,
+    Rejected,
+    Expired,
+    /// FunctionCall исполнен, но вызванный метод упал
+    Failed,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Vote {
+    pub account_id: AccountId,
+    pub approve: bool,
+    pub weight: u32,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: AccountId,
+    pub kind: ProposalKind,
+    pub description: String,
+    pub status: ProposalStatus,
+    /// Вес, необходимый для исполнения (снимок на момент создания)
+    pub quorum_weight: u64,
+    /// Общий вес совета на момент создания
+    pub total_weight: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes: Vec<Vote>,
+    /// UNIX timestamp, секунды
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+impl Proposal {
+    /// Статус на момент now_secs: Active после expires_at — Expired
+    pub fn status_at(&self, now_secs: u64) -> ProposalStatus {
+        if self.status == ProposalStatus::Active && now_secs > self.expires_at {
+            ProposalStatus::Expired
+        } else {
+            self.status
+        }
+    }
+}
+
+// ── Disputes ─────────────────────────────────────────────────
+
+/// Настройки disputes
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeConfig {
+    /// Залог за открытие dispute, yoctoNEAR
+    pub bond: U128,
+    /// Arbiter (None — disputes решает только совет)
+    pub arbiter: Option<AccountId>,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DisputeStatus {
+    Open,
+    Upheld,
+    Rejected,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dispute {
+    pub id: u64,
+    pub attestation_id: u64,
+    pub challenger: AccountId,
+    pub bond: U128,
+    pub reason: String,
+    pub status: DisputeStatus,
+    /// UNIX timestamp, секунды
+    pub opened_at: u64,
+    pub resolved_at: Option<u64>,
+}
+
+// ── Обновление контракта ─────────────────────────────────────
+
+/// Staged обновление
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedUpgrade {
+    pub code_hash: Base58CryptoHash,
+    pub code_size: u64,
+    /// UNIX timestamp, секунды
+    pub staged_at: u64,
+    pub deploy_after: u64,
+    /// Одобрено к deploy (без governance — сразу при загрузке)
+    pub approved: bool,
+}
+
+/// Версии circuit и verification keys, с которыми работает контракт
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitMetadata {
+    pub circuit: String,
+    /// sha256(borsh(VK)) встроенного Groth16 ключа
+    pub groth16_vk_hash: Option<String>,
+    /// sha256(borsh(VK)) PLONK ключа (set_plonk_vk)
+    pub plonk_vk_hash: Option<String>,
+    /// "<account>/<vk_id>" внешнего verifier
+    pub external_vk: Option<String>,
+    pub proof_systems: Vec<ProofSystem>,
+}
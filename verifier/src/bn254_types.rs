@@ -5,10 +5,11 @@
 //! - G2: 128 байт (x_im: 32 LE + x_re: 32 LE + y_im: 32 LE + y_re: 32 LE)
 //! - Scalar (Fr): 32 байта LE
 
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::near;
 
 /// Точка на G1 (кривая E(Fq))
-#[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[near(serializers = [borsh])]
+#[derive(Clone)]
 pub struct G1Point {
     pub x: [u8; 32], // Fq, little-endian
     pub y: [u8; 32], // Fq, little-endian
@@ -16,7 +17,8 @@ pub struct G1Point {
 
 /// Точка на G2 (кривая E'(Fq2))
 /// NEAR формат: x_im, x_re, y_im, y_re (каждый 32 байта LE)
-#[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[near(serializers = [borsh])]
+#[derive(Clone)]
 pub struct G2Point {
     pub x_im: [u8; 32], // Fq (мнимая часть x)
     pub x_re: [u8; 32], // Fq (действительная часть x)
@@ -25,7 +27,8 @@ pub struct G2Point {
 }
 
 /// Скаляр поля Fr (порядок подгруппы BN254)
-#[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[near(serializers = [borsh])]
+#[derive(Clone)]
 pub struct Scalar(pub [u8; 32]); // little-endian
 
 /// Единичный скаляр (1)
@@ -61,6 +64,31 @@ pub fn decimal_to_le_bytes(s: &str) -> [u8; 32] {
     result
 }
 
+/// 32 байта little-endian → decimal string (обратное к decimal_to_le_bytes)
+pub fn le_bytes_to_decimal(bytes: &[u8; 32]) -> String {
+    let mut num = *bytes;
+    let mut digits = Vec::new();
+
+    // Делим на 10 в base-256, начиная со старшего байта
+    loop {
+        let mut rem: u16 = 0;
+        let mut is_zero = true;
+        for byte in num.iter_mut().rev() {
+            let cur = (rem << 8) | *byte as u16;
+            *byte = (cur / 10) as u8;
+            rem = cur % 10;
+            is_zero &= *byte == 0;
+        }
+        digits.push(b'0' + rem as u8);
+        if is_zero {
+            break;
+        }
+    }
+
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
 impl G1Point {
     /// Создаёт G1 точку из двух decimal strings [x, y]
     pub fn from_decimal(x: &str, y: &str) -> Self {
@@ -132,4 +160,23 @@ impl Scalar {
     pub fn to_bytes(&self) -> [u8; 32] {
         self.0
     }
+
+    /// Проверяет что скаляр < r (канонический элемент Fr)
+    /// r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+    pub fn is_canonical(&self) -> bool {
+        // r в little-endian
+        let r: [u8; 32] = [
+            0x01, 0x00, 0x00, 0xf0, 0x93, 0xf5, 0xe1, 0x43, 0x91, 0x70, 0xb9, 0x79, 0x48, 0xe8,
+            0x33, 0x28, 0x5d, 0x58, 0x81, 0x81, 0xb6, 0x45, 0x50, 0xb8, 0x29, 0xa0, 0x31, 0xe1,
+            0x72, 0x4e, 0x64, 0x30,
+        ];
+
+        // Сравнение со старшего байта
+        for i in (0..32).rev() {
+            if self.0[i] != r[i] {
+                return self.0[i] < r[i];
+            }
+        }
+        false
+    }
 }
@@ -0,0 +1,280 @@
+//! Контракт верификатора: реестр verification keys (Groth16 / PLONK) + verify()
+
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::store::{IterableMap, LookupSet};
+use near_sdk::{env, near, require, AccountId, BorshStorageKey, PanicOnDefault};
+
+use crate::snarkjs::{
+    scalars_from_decimal, SnarkjsPlonkProof, SnarkjsPlonkVerificationKey, SnarkjsProof,
+    SnarkjsVerificationKey,
+};
+use crate::{groth16, plonk, PlonkVerifyArgs, ProofSystem, VerifyArgs};
+
+// ── Ключи хранилища ─────────────────────────────────────────
+
+#[derive(BorshSerialize, BorshStorageKey)]
+#[borsh(crate = "near_sdk::borsh")]
+enum StorageKey {
+    VerificationKeys,
+    RemovedVks,
+}
+
+// ── Модели данных ────────────────────────────────────────────
+
+/// Метаданные зарегистрированного verification key
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VkInfo {
+    pub vk_id: String,
+    pub proof_system: ProofSystem,
+    /// Количество public inputs
+    pub n_public: u32,
+    pub registered_by: AccountId,
+    pub registered_at: u64,
+}
+
+#[near(serializers = [borsh])]
+enum RegisteredVk {
+    Groth16(Box<groth16::VerificationKey>),
+    Plonk(Box<plonk::VerificationKey>),
+}
+
+#[near(serializers = [borsh])]
+struct VkEntry {
+    info: VkInfo,
+    vk: RegisteredVk,
+}
+
+// ── Контракт ─────────────────────────────────────────────────
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Groth16Verifier {
+    owner: AccountId,
+    /// Ключ: vk_id (например "tls-oracle/attestation-v1")
+    vks: IterableMap<String, VkEntry>,
+    /// Удалённые vk_id — повторно не регистрируются
+    removed_vks: LookupSet<String>,
+}
+
+#[near]
+impl Groth16Verifier {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            vks: IterableMap::new(StorageKey::VerificationKeys),
+            removed_vks: LookupSet::new(StorageKey::RemovedVks),
+        }
+    }
+
+    // ── Реестр verification keys (admin) ─────────────────────
+
+    /// Регистрирует verification key в формате snarkjs (verification_key.json)
+    ///
+    /// vk_id неизменяем: новая версия circuit — новый vk_id,
+    /// чтобы потребители не получили подменённый ключ под старым id.
+    /// Удалённый vk_id тоже не переиспользуется.
+    pub fn register_vk(&mut self, vk_id: String, vk: SnarkjsVerificationKey) {
+        let n_public = vk.n_public;
+        self.insert_vk(
+            vk_id,
+            ProofSystem::Groth16,
+            n_public,
+            RegisteredVk::Groth16(Box::new(vk.to_vk())),
+        );
+    }
+
+    /// Регистрирует PLONK verification key (snarkjs plonk setup → zkey export verificationkey)
+    pub fn register_plonk_vk(&mut self, vk_id: String, vk: SnarkjsPlonkVerificationKey) {
+        let n_public = vk.n_public;
+        self.insert_vk(
+            vk_id,
+            ProofSystem::Plonk,
+            n_public,
+            RegisteredVk::Plonk(Box::new(vk.to_vk())),
+        );
+    }
+
+    pub fn remove_vk(&mut self, vk_id: String) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Только owner может удалять verification keys"
+        );
+        require!(self.vks.remove(&vk_id).is_some(), "vk_id не найден");
+        self.removed_vks.insert(vk_id.clone());
+        env::log_str(&format!("Verification key удалён: {}", vk_id));
+    }
+
+    pub fn set_owner(&mut self, new_owner: AccountId) {
+        require!(env::predecessor_account_id() == self.owner, "Только owner");
+        self.owner = new_owner;
+    }
+
+    // ── Верификация ──────────────────────────────────────────
+
+    /// Проверяет Groth16 proof в формате snarkjs (decimal strings)
+    pub fn verify(&self, vk_id: String, proof: SnarkjsProof, public_inputs: Vec<String>) -> bool {
+        groth16::verify(
+            self.groth16_vk(&vk_id),
+            &proof.to_proof(),
+            &scalars_from_decimal(&public_inputs),
+        )
+    }
+
+    /// Проверяет Groth16 proof с аргументами в Borsh (для cross-contract вызовов)
+    pub fn verify_bytes(&self, #[serializer(borsh)] args: VerifyArgs) -> bool {
+        groth16::verify(
+            self.groth16_vk(&args.vk_id),
+            &args.proof,
+            &args.public_inputs,
+        )
+    }
+
+    /// Проверяет PLONK proof в формате snarkjs (decimal strings)
+    pub fn verify_plonk(
+        &self,
+        vk_id: String,
+        proof: SnarkjsPlonkProof,
+        public_inputs: Vec<String>,
+    ) -> bool {
+        plonk::verify(
+            self.plonk_vk(&vk_id),
+            &proof.to_proof(),
+            &scalars_from_decimal(&public_inputs),
+        )
+    }
+
+    /// Проверяет PLONK proof с аргументами в Borsh (для cross-contract вызовов)
+    pub fn verify_plonk_bytes(&self, #[serializer(borsh)] args: PlonkVerifyArgs) -> bool {
+        plonk::verify(self.plonk_vk(&args.vk_id), &args.proof, &args.public_inputs)
+    }
+
+    // ── View методы ──────────────────────────────────────────
+
+    pub fn get_vk_info(&self, vk_id: String) -> Option<VkInfo> {
+        self.vks.get(&vk_id).map(|e| e.info.clone())
+    }
+
+    pub fn is_vk_removed(&self, vk_id: String) -> bool {
+        self.removed_vks.contains(&vk_id)
+    }
+
+    pub fn get_vks(&self) -> Vec<VkInfo> {
+        self.vks.values().map(|e| e.info.clone()).collect()
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+}
+
+impl Groth16Verifier {
+    fn insert_vk(
+        &mut self,
+        vk_id: String,
+        proof_system: ProofSystem,
+        n_public: u32,
+        vk: RegisteredVk,
+    ) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Только owner может регистрировать verification keys"
+        );
+        require!(
+            !vk_id.is_empty() && vk_id.len() <= 64,
+            "vk_id: 1..64 символов"
+        );
+        require!(!self.vks.contains_key(&vk_id), "vk_id уже зарегистрирован");
+        require!(
+            !self.removed_vks.contains(&vk_id),
+            "vk_id был удалён и не может быть зарегистрирован повторно"
+        );
+
+        let entry = VkEntry {
+            info: VkInfo {
+                vk_id: vk_id.clone(),
+                proof_system,
+                n_public,
+                registered_by: env::predecessor_account_id(),
+                registered_at: env::block_height(),
+            },
+            vk,
+        };
+        self.vks.insert(vk_id.clone(), entry);
+        env::log_str(&format!("Verification key зарегистрирован: {}", vk_id));
+    }
+
+    fn groth16_vk(&self, vk_id: &str) -> &groth16::VerificationKey {
+        match &self.vks.get(vk_id).expect("vk_id не найден").vk {
+            RegisteredVk::Groth16(vk) => vk,
+            RegisteredVk::Plonk(_) => env::panic_str("vk_id зарегистрирован для PLONK"),
+        }
+    }
+
+    fn plonk_vk(&self, vk_id: &str) -> &plonk::VerificationKey {
+        match &self.vks.get(vk_id).expect("vk_id не найден").vk {
+            RegisteredVk::Plonk(vk) => vk,
+            RegisteredVk::Groth16(_) => env::panic_str("vk_id зарегистрирован для Groth16"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    use super::*;
+
+    fn vk() -> SnarkjsVerificationKey {
+        // Генераторы G1/G2 — ключ только хранится, pairing не вызывается
+        let g1 = || vec!["1".to_string(), "2".to_string(), "1".to_string()];
+        let g2 = || {
+            vec![
+                vec![
+                    "10857046999023057135944570762232829481370756359578518086990519993285655852781"
+                        .to_string(),
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634"
+                        .to_string(),
+                ],
+                vec![
+                    "8495653923123431417604973247489272438418190587263600148770280649306958101930"
+                        .to_string(),
+                    "4082367875863433681332203403145435568316851327593401208105741076214120093531"
+                        .to_string(),
+                ],
+                vec!["1".to_string(), "0".to_string()],
+            ]
+        };
+        SnarkjsVerificationKey {
+            n_public: 1,
+            vk_alpha_1: g1(),
+            vk_beta_2: g2(),
+            vk_gamma_2: g2(),
+            vk_delta_2: g2(),
+            ic: vec![g1(), g1()],
+        }
+    }
+
+    fn setup() -> Groth16Verifier {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .build());
+        Groth16Verifier::new(accounts(0))
+    }
+
+    #[test]
+    #[should_panic(expected = "vk_id был удалён")]
+    fn test_removed_vk_id_not_reused() {
+        let mut contract = setup();
+        contract.register_vk("circuit-v1".to_string(), vk());
+        contract.remove_vk("circuit-v1".to_string());
+        assert!(contract.get_vk_info("circuit-v1".to_string()).is_none());
+        assert!(contract.is_vk_removed("circuit-v1".to_string()));
+
+        // Под старым id нельзя подложить другой ключ
+        contract.register_vk("circuit-v1".to_string(), vk());
+    }
+}
@@ -0,0 +1,288 @@
+//! Арифметика поля Fr (скаляры BN254) для PLONK верификатора
+//!
+//! Montgomery форма, 4 × u64 limbs (little-endian), без big integer crate.
+//! r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+
+/// Модуль r
+const MODULUS: [u64; 4] = [
+    0x43e1f593f0000001,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// R = 2^256 mod r (единица в Montgomery форме)
+const R: [u64; 4] = [
+    0xac96341c4ffffffb,
+    0x36fc76959f60cd29,
+    0x666ea36f7879462e,
+    0x0e0a77c19a07df2f,
+];
+
+/// R^2 mod r — для перевода в Montgomery форму
+const R2: [u64; 4] = [
+    0x1bb8e645ae216da7,
+    0x53fe3ab1e35c59e3,
+    0x8c49833d53bb8085,
+    0x0216d0b17f4e44a5,
+];
+
+/// -r^{-1} mod 2^64
+const INV: u64 = 0xc2e1f593efffffff;
+
+/// Элемент Fr в Montgomery форме
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Fr([u64; 4]);
+
+/// a >= b (как 256-битные числа)
+fn geq(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// a - b (без учёта модуля), возвращает borrow
+fn sub_raw(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d1, b1) = a[i].overflowing_sub(b[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow as u64);
+        out[i] = d2;
+        borrow = b1 || b2;
+    }
+    (out, borrow)
+}
+
+/// a + b (без учёта модуля), возвращает carry
+fn add_raw(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (s1, c1) = a[i].overflowing_add(b[i]);
+        let (s2, c2) = s1.overflowing_add(carry as u64);
+        out[i] = s2;
+        carry = c1 || c2;
+    }
+    (out, carry)
+}
+
+/// Montgomery умножение (CIOS): a · b · R^{-1} mod r
+fn mont_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
+    for &bi in b.iter() {
+        // t += a · b[i]
+        let mut carry: u128 = 0;
+        for j in 0..4 {
+            let v = t[j] as u128 + (a[j] as u128) * (bi as u128) + carry;
+            t[j] = v as u64;
+            carry = v >> 64;
+        }
+        let v = t[4] as u128 + carry;
+        t[4] = v as u64;
+        t[5] = (v >> 64) as u64;
+
+        // t = (t + m · r) / 2^64
+        let m = t[0].wrapping_mul(INV);
+        let v = t[0] as u128 + (m as u128) * (MODULUS[0] as u128);
+        let mut carry = v >> 64;
+        for j in 1..4 {
+            let v = t[j] as u128 + (m as u128) * (MODULUS[j] as u128) + carry;
+            t[j - 1] = v as u64;
+            carry = v >> 64;
+        }
+        let v = t[4] as u128 + carry;
+        t[3] = v as u64;
+        t[4] = t[5] + (v >> 64) as u64;
+        t[5] = 0;
+    }
+
+    let mut out = [t[0], t[1], t[2], t[3]];
+    if t[4] != 0 || geq(&out, &MODULUS) {
+        out = sub_raw(&out, &MODULUS).0;
+    }
+    out
+}
+
+impl Fr {
+    pub const ZERO: Fr = Fr([0; 4]);
+
+    pub fn one() -> Fr {
+        Fr(R)
+    }
+
+    pub fn from_u64(v: u64) -> Fr {
+        Fr(mont_mul(&[v, 0, 0, 0], &R2))
+    }
+
+    /// 32 байта LE → Fr. None если значение >= r
+    pub fn from_le_bytes(bytes: &[u8; 32]) -> Option<Fr> {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut chunk = [0u8; 8];
+            chunk.copy_from_slice(&bytes[i * 8..i * 8 + 8]);
+            *limb = u64::from_le_bytes(chunk);
+        }
+        if geq(&limbs, &MODULUS) {
+            return None;
+        }
+        Some(Fr(mont_mul(&limbs, &R2)))
+    }
+
+    /// 32 байта BE → Fr с приведением по модулю r (для Fiat-Shamir challenge)
+    pub fn from_be_bytes_reduced(bytes: &[u8; 32]) -> Fr {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut chunk = [0u8; 8];
+            chunk.copy_from_slice(&bytes[32 - (i + 1) * 8..32 - i * 8]);
+            *limb = u64::from_be_bytes(chunk);
+        }
+        // 2^256 < 6r — не более 5 вычитаний
+        while geq(&limbs, &MODULUS) {
+            limbs = sub_raw(&limbs, &MODULUS).0;
+        }
+        Fr(mont_mul(&limbs, &R2))
+    }
+
+    /// Каноническое значение, 32 байта LE
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        let limbs = mont_mul(&self.0, &[1, 0, 0, 0]);
+        let mut out = [0u8; 32];
+        for (i, limb) in limbs.iter().enumerate() {
+            out[i * 8..i * 8 + 8].copy_from_slice(&limb.to_le_bytes());
+        }
+        out
+    }
+
+    /// Каноническое значение, 32 байта BE
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut out = self.to_le_bytes();
+        out.reverse();
+        out
+    }
+
+    pub fn add(&self, other: &Fr) -> Fr {
+        let (sum, carry) = add_raw(&self.0, &other.0);
+        if carry || geq(&sum, &MODULUS) {
+            Fr(sub_raw(&sum, &MODULUS).0)
+        } else {
+            Fr(sum)
+        }
+    }
+
+    pub fn sub(&self, other: &Fr) -> Fr {
+        let (diff, borrow) = sub_raw(&self.0, &other.0);
+        if borrow {
+            Fr(add_raw(&diff, &MODULUS).0)
+        } else {
+            Fr(diff)
+        }
+    }
+
+    pub fn neg(&self) -> Fr {
+        Fr::ZERO.sub(self)
+    }
+
+    pub fn mul(&self, other: &Fr) -> Fr {
+        Fr(mont_mul(&self.0, &other.0))
+    }
+
+    pub fn square(&self) -> Fr {
+        self.mul(self)
+    }
+
+    /// self^exp, exp — 256-битное число (LE limbs)
+    fn pow(&self, exp: &[u64; 4]) -> Fr {
+        let mut result = Fr::one();
+        for i in (0..4).rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (exp[i] >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+
+    /// Обратный элемент через малую теорему Ферма: a^(r-2). Для нуля — None
+    pub fn inverse(&self) -> Option<Fr> {
+        if *self == Fr::ZERO {
+            return None;
+        }
+        let exp = sub_raw(&MODULUS, &[2, 0, 0, 0]).0;
+        Some(self.pow(&exp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254_types::decimal_to_le_bytes;
+
+    // Векторы посчитаны независимо (Python, целые числа по модулю r)
+    const A: &str = "21449723829856884642702851357837870289260781448867437806377668921947437493339";
+    const B: &str = "8234104122482341265491137074636836252947884782870784360943022469005013929455";
+
+    fn fr(s: &str) -> Fr {
+        Fr::from_le_bytes(&decimal_to_le_bytes(s)).unwrap()
+    }
+
+    #[test]
+    fn test_add_sub_vectors() {
+        assert_eq!(
+            fr(A).sub(&fr(B)),
+            fr("13215619707374543377211714283201034036312896665996653445434646452942423563884")
+        );
+        assert_eq!(
+            fr(B).sub(&fr(A)),
+            fr("8672623164464731845034691462056241052235467734419380898263557733633384931733")
+        );
+        assert_eq!(fr(A).add(&fr(B)).sub(&fr(B)), fr(A));
+        assert_eq!(fr(A).add(&fr(A).neg()), Fr::ZERO);
+    }
+
+    #[test]
+    fn test_mul_vectors() {
+        assert_eq!(
+            fr(A).mul(&fr(B)),
+            fr("2344731013177268758506371698317129093170807857115276368452827920304306429487")
+        );
+        // (-1)² = 1
+        assert_eq!(Fr::one().neg().square(), Fr::one());
+        assert_eq!(Fr::from_u64(6).mul(&Fr::from_u64(7)), Fr::from_u64(42));
+    }
+
+    #[test]
+    fn test_inverse_and_pow_vectors() {
+        assert_eq!(
+            fr(A).inverse().unwrap(),
+            fr("17144768963806910274710866231934755909911635671459610427075306129489703229291")
+        );
+        assert_eq!(fr(A).mul(&fr(A).inverse().unwrap()), Fr::one());
+        assert!(Fr::ZERO.inverse().is_none());
+        assert_eq!(
+            fr(A).pow(&[65537, 0, 0, 0]),
+            fr("6685012153040149977319080234974063629935652829699044727907038422224480220190")
+        );
+    }
+
+    #[test]
+    fn test_byte_conversions() {
+        // r не канонично, r - 1 — канонично
+        let r_minus_1 =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        assert!(Fr::from_le_bytes(&decimal_to_le_bytes(r_minus_1)).is_some());
+        let mut r = decimal_to_le_bytes(r_minus_1);
+        r[0] += 1;
+        assert!(Fr::from_le_bytes(&r).is_none());
+
+        // BE с приведением: r ≡ 0
+        r.reverse();
+        assert_eq!(Fr::from_be_bytes_reduced(&r), Fr::ZERO);
+        assert_eq!(fr(A).to_le_bytes(), decimal_to_le_bytes(A));
+    }
+}
@@ -0,0 +1,295 @@
+//! Groth16 ZK-верификатор для NEAR через alt_bn128 host functions
+//!
+//! Портировано из zeropoolnetwork/near-groth16-verifier,
+//! адаптировано под near-sdk 5.6.
+//!
+//! Верификационное уравнение:
+//!   e(A, B) == e(α, β) · e(vk_x, γ) · e(C, δ)
+//!
+//! Где vk_x = IC[0] + Σ(pub_input[i] · IC[i+1])
+//!
+//! NEAR host functions:
+//!   - alt_bn128_g1_multiexp: multi-scalar multiplication на G1
+//!   - alt_bn128_g1_sum: сумма точек G1
+//!   - alt_bn128_pairing_check: проверка pairing equation
+
+use near_sdk::{env, near};
+
+use crate::bn254_types::{G1Point, G2Point, Scalar};
+
+/// Verification key для Groth16
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct VerificationKey {
+    pub alpha_g1: G1Point,
+    pub beta_g2: G2Point,
+    pub gamma_g2: G2Point,
+    pub delta_g2: G2Point,
+    /// IC[0..=n] где n = количество public inputs
+    pub ic: Vec<G1Point>,
+}
+
+/// Groth16 proof
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct Proof {
+    pub a: G1Point,
+    pub b: G2Point,
+    pub c: G1Point,
+}
+
+/// Вычисляет multi-scalar multiplication: Σ(scalar[i] · point[i])
+/// через env::alt_bn128_g1_multiexp
+fn g1_multiexp(pairs: &[(Scalar, G1Point)]) -> G1Point {
+    // Формат для NEAR (NEP-381): [(g1_point_64bytes, scalar_32bytes), ...]
+    // Каждый элемент: 64 (G1) + 32 (scalar) = 96 байт
+    // ВАЖНО: точка ПЕРВАЯ, скаляр ВТОРОЙ
+    let mut data = Vec::with_capacity(pairs.len() * 96);
+    for (scalar, point) in pairs {
+        data.extend_from_slice(&point.to_bytes());
+        data.extend_from_slice(&scalar.to_bytes());
+    }
+
+    let result = env::alt_bn128_g1_multiexp(&data);
+    assert!(
+        result.len() == 64,
+        "alt_bn128_g1_multiexp: ожидалось 64 байт"
+    );
+
+    let mut x = [0u8; 32];
+    let mut y = [0u8; 32];
+    x.copy_from_slice(&result[..32]);
+    y.copy_from_slice(&result[32..]);
+    G1Point { x, y }
+}
+
+/// Сумма точек G1 через env::alt_bn128_g1_sum
+/// Знак: false = сложение, true = вычитание
+fn g1_sum(points: &[(bool, G1Point)]) -> G1Point {
+    // Формат для NEAR: [(sign_byte, g1_point_64bytes), ...]
+    // sign: 0 = add, 1 = subtract
+    let mut data = Vec::with_capacity(points.len() * 65);
+    for (negate, point) in points {
+        data.push(if *negate { 1u8 } else { 0u8 });
+        data.extend_from_slice(&point.to_bytes());
+    }
+
+    let result = env::alt_bn128_g1_sum(&data);
+    assert!(result.len() == 64, "alt_bn128_g1_sum: ожидалось 64 байт");
+
+    let mut x = [0u8; 32];
+    let mut y = [0u8; 32];
+    x.copy_from_slice(&result[..32]);
+    y.copy_from_slice(&result[32..]);
+    G1Point { x, y }
+}
+
+/// Проверяет Groth16 proof
+///
+/// Верификационное уравнение:
+///   e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) == 1
+///
+/// Где:
+///   vk_x = IC[0] + Σ(pub_input[i] · IC[i+1])
+///
+/// Возвращает true если proof корректен
+pub fn verify(vk: &VerificationKey, proof: &Proof, public_inputs: &[Scalar]) -> bool {
+    assert!(
+        vk.ic.len() == public_inputs.len() + 1,
+        "Неверное количество public inputs: ожидалось {}, получено {}",
+        vk.ic.len() - 1,
+        public_inputs.len()
+    );
+
+    // 1. Вычисляем vk_x = IC[0] + Σ(pub_input[i] · IC[i+1])
+    //    Сначала multi-scalar multiplication для IC[1..] * pub_inputs
+    let msm_pairs: Vec<(Scalar, G1Point)> = public_inputs
+        .iter()
+        .zip(vk.ic[1..].iter())
+        .map(|(scalar, point)| (scalar.clone(), point.clone()))
+        .collect();
+
+    let msm_result = g1_multiexp(&msm_pairs);
+
+    // vk_x = IC[0] + msm_result
+    let vk_x = g1_sum(&[(false, vk.ic[0].clone()), (false, msm_result)]);
+
+    // 2. Отрицание A
+    let neg_a = proof.a.neg();
+
+    // 3. Pairing check: e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) == 1
+    //
+    // Формат для NEAR alt_bn128_pairing_check:
+    // [(G1_64bytes, G2_128bytes), ...] = 192 байт на пару, 4 пары = 768 байт
+    let mut pairing_data = Vec::with_capacity(4 * 192);
+
+    // Пара 1: e(-A, B)
+    pairing_data.extend_from_slice(&neg_a.to_bytes());
+    pairing_data.extend_from_slice(&proof.b.to_bytes());
+
+    // Пара 2: e(α, β)
+    pairing_data.extend_from_slice(&vk.alpha_g1.to_bytes());
+    pairing_data.extend_from_slice(&vk.beta_g2.to_bytes());
+
+    // Пара 3: e(vk_x, γ)
+    pairing_data.extend_from_slice(&vk_x.to_bytes());
+    pairing_data.extend_from_slice(&vk.gamma_g2.to_bytes());
+
+    // Пара 4: e(C, δ)
+    pairing_data.extend_from_slice(&proof.c.to_bytes());
+    pairing_data.extend_from_slice(&vk.delta_g2.to_bytes());
+
+    env::alt_bn128_pairing_check(&pairing_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254_types::decimal_to_le_bytes;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    // Генератор G2 в формате snarkjs: [[x_re, x_im], [y_re, y_im]]
+    const G2_SNARKJS: [[&str; 2]; 2] = [
+        [
+            "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+            "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+        ],
+        [
+            "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+            "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+        ],
+    ];
+
+    fn g1_gen() -> G1Point {
+        G1Point::from_decimal("1", "2")
+    }
+
+    fn g2_gen() -> G2Point {
+        G2Point {
+            x_im: decimal_to_le_bytes(G2_SNARKJS[0][1]),
+            x_re: decimal_to_le_bytes(G2_SNARKJS[0][0]),
+            y_im: decimal_to_le_bytes(G2_SNARKJS[1][1]),
+            y_re: decimal_to_le_bytes(G2_SNARKJS[1][0]),
+        }
+    }
+
+    fn scalar(n: u64) -> Scalar {
+        Scalar::from_decimal(&n.to_string())
+    }
+
+    /// k · G1 через alt_bn128_g1_multiexp
+    fn g1_mul(k: u64) -> G1Point {
+        g1_multiexp(&[(scalar(k), g1_gen())])
+    }
+
+    /// Синтетический VK с известными дискретными логарифмами:
+    /// α = a·G1, β = γ = δ = G2, IC = [k0·G1, k1·G1]
+    ///
+    /// Для public input s proof (A = x·G1, B = G2, C = c·G1) валиден
+    /// тогда и только тогда, когда x = a + k0 + s·k1 + c.
+    fn synthetic_vk() -> VerificationKey {
+        VerificationKey {
+            alpha_g1: g1_mul(3),
+            beta_g2: g2_gen(),
+            gamma_g2: g2_gen(),
+            delta_g2: g2_gen(),
+            ic: vec![g1_mul(5), g1_mul(7)],
+        }
+    }
+
+    fn synthetic_proof(x: u64) -> Proof {
+        Proof {
+            a: g1_mul(x),
+            b: g2_gen(),
+            c: g1_mul(13),
+        }
+    }
+
+    #[test]
+    fn test_g1_neg() {
+        let neg = g1_gen().neg();
+        assert_eq!(neg.x, g1_gen().x);
+        assert_eq!(
+            neg.y,
+            decimal_to_le_bytes(
+                "21888242871839275222246405745257275088696311157297823662689037894645226208581"
+            )
+        );
+        // -(-P) == P
+        assert_eq!(neg.neg().y, g1_gen().y);
+    }
+
+    #[test]
+    fn test_g2_to_bytes_real_first() {
+        let bytes = g2_gen().to_bytes();
+        assert_eq!(bytes[..32], decimal_to_le_bytes(G2_SNARKJS[0][0]));
+        assert_eq!(bytes[32..64], decimal_to_le_bytes(G2_SNARKJS[0][1]));
+        assert_eq!(bytes[64..96], decimal_to_le_bytes(G2_SNARKJS[1][0]));
+        assert_eq!(bytes[96..], decimal_to_le_bytes(G2_SNARKJS[1][1]));
+    }
+
+    #[test]
+    fn test_g2_ordering_accepted_by_host() {
+        testing_env!(VMContextBuilder::new().build());
+        // e(G1, G2) · e(-G1, G2) == 1 — точка G2 из snarkjs раскладки лежит на кривой
+        let mut data = Vec::with_capacity(2 * 192);
+        data.extend_from_slice(&g1_gen().to_bytes());
+        data.extend_from_slice(&g2_gen().to_bytes());
+        data.extend_from_slice(&g1_gen().neg().to_bytes());
+        data.extend_from_slice(&g2_gen().to_bytes());
+        assert!(env::alt_bn128_pairing_check(&data));
+    }
+
+    #[test]
+    fn test_verify_synthetic_proof() {
+        testing_env!(VMContextBuilder::new().build());
+        let vk = synthetic_vk();
+        // x = 3 + 5 + 11·7 + 13
+        assert!(verify(&vk, &synthetic_proof(98), &[scalar(11)]));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_input() {
+        testing_env!(VMContextBuilder::new().build());
+        let vk = synthetic_vk();
+        assert!(!verify(&vk, &synthetic_proof(98), &[scalar(12)]));
+        assert!(!verify(&vk, &synthetic_proof(99), &[scalar(11)]));
+    }
+
+    #[test]
+    fn test_verify_snarkjs_fixture() {
+        use crate::snarkjs::{scalars_from_decimal, SnarkjsProof, SnarkjsVerificationKey};
+        use near_sdk::serde_json;
+
+        // Proof snarkjs, см. tests/fixtures/README.md
+        testing_env!(VMContextBuilder::new().build());
+        let vk: SnarkjsVerificationKey = serde_json::from_str(include_str!(
+            "../tests/fixtures/groth16_verification_key.json"
+        ))
+        .unwrap();
+        let proof: SnarkjsProof =
+            serde_json::from_str(include_str!("../tests/fixtures/groth16_proof.json")).unwrap();
+        let public: Vec<String> =
+            serde_json::from_str(include_str!("../tests/fixtures/groth16_public.json")).unwrap();
+
+        let vk = vk.to_vk();
+        let proof = proof.to_proof();
+        assert!(verify(&vk, &proof, &scalars_from_decimal(&public)));
+
+        // Подменённый public signal и proof с A = -A отклоняются
+        assert!(!verify(&vk, &proof, &[scalar(1)]));
+        let tampered = Proof {
+            a: proof.a.neg(),
+            ..proof
+        };
+        assert!(!verify(&vk, &tampered, &scalars_from_decimal(&public)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Неверное количество public inputs")]
+    fn test_verify_input_count() {
+        testing_env!(VMContextBuilder::new().build());
+        verify(&synthetic_vk(), &synthetic_proof(98), &[]);
+    }
+}
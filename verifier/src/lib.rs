@@ -0,0 +1,63 @@
+//! Groth16 / PLONK верификатор (BN254) для NEAR
+//!
+//! Библиотека: типы BN254 + verify() через alt_bn128 host functions.
+//! PLONK (snarkjs) использует universal setup — новый circuit без отдельной церемонии.
+//! Контракт (feature "contract"): реестр verification keys и
+//! `verify(vk_id, proof, public_inputs) -> bool` для нескольких circuits и потребителей.
+//!
+//! Контракты, линкующие библиотеку, подключают crate с `default-features = false`,
+//! чтобы не экспортировать методы верификатора.
+
+pub mod bn254_types;
+pub mod fr;
+pub mod groth16;
+pub mod plonk;
+pub mod snarkjs;
+
+#[cfg(feature = "contract")]
+mod contract;
+
+#[cfg(feature = "contract")]
+pub use contract::*;
+
+use near_sdk::near;
+
+use bn254_types::Scalar;
+use groth16::Proof;
+
+/// Аргументы `verify_bytes` — бинарный вызов для cross-contract (Borsh)
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct VerifyArgs {
+    pub vk_id: String,
+    pub proof: Proof,
+    pub public_inputs: Vec<Scalar>,
+}
+
+/// Аргументы `verify_plonk_bytes` — бинарный вызов для cross-contract (Borsh)
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct PlonkVerifyArgs {
+    pub vk_id: String,
+    pub proof: plonk::Proof,
+    pub public_inputs: Vec<Scalar>,
+}
+
+/// Сообщение отказа для тега `fflonk`
+pub const FFLONK_UNSUPPORTED: &str = "fflonk не поддерживается: proof_system groth16 или plonk";
+
+/// Система доказательств
+///
+/// Поддерживаются `groth16` и `plonk` (snarkjs). `fflonk` распознаётся
+/// только для того, чтобы отклонить его с понятной ошибкой
+/// ([`FFLONK_UNSUPPORTED`]): верификатора fflonk нет, ключ и proof
+/// под этим тегом не принимаются.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofSystem {
+    #[default]
+    Groth16,
+    Plonk,
+    Fflonk,
+}
@@ -0,0 +1,399 @@
+//! PLONK ZK-верификатор для NEAR через alt_bn128 host functions
+//!
+//! Совместим с snarkjs (plonk_verify.js): KZG коммитменты на BN254,
+//! Fiat-Shamir через Keccak256 transcript, universal setup (Powers of Tau)
+//! без per-circuit церемонии.
+//!
+//! Проверка сводится к одному pairing check:
+//!   e(-A1, X_2) · e(B1, G2) == 1
+//!
+//! Где:
+//!   A1 = Wxi + u · Wxiw
+//!   B1 = xi · Wxi + u·xi·ω · Wxiw + F - E
+//!
+//! Все G1-комбинации (D, F, E, B1) считаются одним alt_bn128_g1_multiexp.
+
+use near_sdk::{env, near, require};
+
+use crate::bn254_types::{G1Point, G2Point, Scalar};
+use crate::fr::Fr;
+
+/// Verification key для PLONK
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct VerificationKey {
+    pub n_public: u32,
+    /// log2 размера домена
+    pub power: u32,
+    pub k1: Scalar,
+    pub k2: Scalar,
+    pub qm: G1Point,
+    pub ql: G1Point,
+    pub qr: G1Point,
+    pub qo: G1Point,
+    pub qc: G1Point,
+    pub s1: G1Point,
+    pub s2: G1Point,
+    pub s3: G1Point,
+    /// [x]_2 из Powers of Tau
+    pub x_2: G2Point,
+    /// Корень из единицы порядка 2^power
+    pub w: Scalar,
+}
+
+/// PLONK proof
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+pub struct Proof {
+    pub a: G1Point,
+    pub b: G1Point,
+    pub c: G1Point,
+    pub z: G1Point,
+    pub t1: G1Point,
+    pub t2: G1Point,
+    pub t3: G1Point,
+    pub wxi: G1Point,
+    pub wxiw: G1Point,
+    pub eval_a: Scalar,
+    pub eval_b: Scalar,
+    pub eval_c: Scalar,
+    pub eval_s1: Scalar,
+    pub eval_s2: Scalar,
+    pub eval_zw: Scalar,
+}
+
+/// Fiat-Shamir challenges
+struct Challenges {
+    beta: Fr,
+    gamma: Fr,
+    alpha: Fr,
+    xi: Fr,
+    /// v[0] не используется — индексы как в snarkjs (v1..v5)
+    v: [Fr; 6],
+    u: Fr,
+}
+
+/// Keccak256 transcript (snarkjs Keccak256Transcript):
+/// G1 — x||y big-endian, скаляры — 32 байта big-endian
+struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    fn add_point(&mut self, p: &G1Point) {
+        let mut x = p.x;
+        let mut y = p.y;
+        x.reverse();
+        y.reverse();
+        self.data.extend_from_slice(&x);
+        self.data.extend_from_slice(&y);
+    }
+
+    fn add_scalar(&mut self, s: &Fr) {
+        self.data.extend_from_slice(&s.to_be_bytes());
+    }
+
+    /// Challenge = keccak256(transcript) mod r, transcript сбрасывается
+    fn challenge(&mut self) -> Fr {
+        let hash = env::keccak256_array(&self.data);
+        self.data.clear();
+        Fr::from_be_bytes_reduced(&hash)
+    }
+}
+
+fn fr(s: &Scalar) -> Fr {
+    Fr::from_le_bytes(&s.0).unwrap_or_else(|| env::panic_str("PLONK: скаляр вне поля Fr"))
+}
+
+fn scalar(f: &Fr) -> Scalar {
+    Scalar(f.to_le_bytes())
+}
+
+/// Генератор G1: (1, 2)
+fn g1_generator() -> G1Point {
+    let mut x = [0u8; 32];
+    let mut y = [0u8; 32];
+    x[0] = 1;
+    y[0] = 2;
+    G1Point { x, y }
+}
+
+/// Генератор G2 (little-endian координаты)
+fn g2_generator() -> G2Point {
+    G2Point {
+        x_re: [
+            237, 246, 146, 217, 92, 189, 222, 70, 221, 218, 94, 247, 212, 34, 67, 103, 121, 68, 92,
+            94, 102, 0, 106, 66, 118, 30, 31, 18, 239, 222, 0, 24,
+        ],
+        x_im: [
+            194, 18, 243, 174, 183, 133, 228, 151, 18, 231, 169, 53, 51, 73, 170, 241, 37, 93, 251,
+            49, 183, 191, 96, 114, 58, 72, 13, 146, 147, 147, 142, 25,
+        ],
+        y_re: [
+            170, 125, 250, 102, 1, 204, 230, 76, 123, 211, 67, 12, 105, 231, 209, 227, 143, 64,
+            203, 141, 128, 113, 171, 74, 235, 109, 140, 219, 165, 94, 200, 18,
+        ],
+        y_im: [
+            91, 151, 34, 209, 220, 218, 172, 85, 243, 142, 179, 112, 51, 49, 75, 188, 149, 51, 12,
+            105, 173, 153, 158, 236, 117, 240, 95, 88, 208, 137, 6, 9,
+        ],
+    }
+}
+
+fn calculate_challenges(vk: &VerificationKey, proof: &Proof, pub_inputs: &[Fr]) -> Challenges {
+    let mut t = Transcript::new();
+
+    // Round 2: beta, gamma
+    for p in [
+        &vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3,
+    ] {
+        t.add_point(p);
+    }
+    for input in pub_inputs {
+        t.add_scalar(input);
+    }
+    t.add_point(&proof.a);
+    t.add_point(&proof.b);
+    t.add_point(&proof.c);
+    let beta = t.challenge();
+
+    t.add_scalar(&beta);
+    let gamma = t.challenge();
+
+    // Round 3: alpha
+    t.add_scalar(&beta);
+    t.add_scalar(&gamma);
+    t.add_point(&proof.z);
+    let alpha = t.challenge();
+
+    // Round 4: xi
+    t.add_scalar(&alpha);
+    t.add_point(&proof.t1);
+    t.add_point(&proof.t2);
+    t.add_point(&proof.t3);
+    let xi = t.challenge();
+
+    // Round 5: v
+    t.add_scalar(&xi);
+    for e in [
+        &proof.eval_a,
+        &proof.eval_b,
+        &proof.eval_c,
+        &proof.eval_s1,
+        &proof.eval_s2,
+        &proof.eval_zw,
+    ] {
+        t.add_scalar(&fr(e));
+    }
+    let mut v = [Fr::ZERO; 6];
+    v[1] = t.challenge();
+    for i in 2..6 {
+        v[i] = v[i - 1].mul(&v[1]);
+    }
+
+    // u
+    t.add_point(&proof.wxi);
+    t.add_point(&proof.wxiw);
+    let u = t.challenge();
+
+    Challenges {
+        beta,
+        gamma,
+        alpha,
+        xi,
+        v,
+        u,
+    }
+}
+
+/// Проверяет PLONK proof (snarkjs)
+///
+/// Возвращает true если proof корректен
+pub fn verify(vk: &VerificationKey, proof: &Proof, public_inputs: &[Scalar]) -> bool {
+    require!(
+        public_inputs.len() == vk.n_public as usize,
+        "PLONK: неверное количество public inputs"
+    );
+
+    let pub_inputs: Vec<Fr> = public_inputs.iter().map(fr).collect();
+    let ch = calculate_challenges(vk, proof, &pub_inputs);
+
+    let eval_a = fr(&proof.eval_a);
+    let eval_b = fr(&proof.eval_b);
+    let eval_c = fr(&proof.eval_c);
+    let eval_s1 = fr(&proof.eval_s1);
+    let eval_s2 = fr(&proof.eval_s2);
+    let eval_zw = fr(&proof.eval_zw);
+    let w = fr(&vk.w);
+
+    // ── Lagrange L_i(xi), i = 1..n_public; Z_H(xi) = xi^n - 1 ──
+    let mut xin = ch.xi;
+    for _ in 0..vk.power {
+        xin = xin.square();
+    }
+    let zh = xin.sub(&Fr::one());
+    let n = Fr::from_u64(1u64 << vk.power);
+
+    let mut lagrange = Vec::with_capacity(vk.n_public.max(1) as usize);
+    let mut wi = Fr::one();
+    for _ in 0..vk.n_public.max(1) {
+        let denom = n
+            .mul(&ch.xi.sub(&wi))
+            .inverse()
+            .unwrap_or_else(|| env::panic_str("PLONK: xi в домене"));
+        lagrange.push(wi.mul(&zh).mul(&denom));
+        wi = wi.mul(&w);
+    }
+    let l1 = lagrange[0];
+
+    // ── PI(xi) = -Σ pub[i] · L_{i+1}(xi) ────────────────────
+    let mut pi = Fr::ZERO;
+    for (input, l) in pub_inputs.iter().zip(lagrange.iter()) {
+        pi = pi.sub(&input.mul(l));
+    }
+
+    let alpha2 = ch.alpha.square();
+
+    // ── r0 ───────────────────────────────────────────────────
+    let e3a = eval_a.add(&ch.beta.mul(&eval_s1)).add(&ch.gamma);
+    let e3b = eval_b.add(&ch.beta.mul(&eval_s2)).add(&ch.gamma);
+    let e3c = eval_c.add(&ch.gamma);
+    let e3 = e3a.mul(&e3b).mul(&e3c).mul(&eval_zw).mul(&ch.alpha);
+    let r0 = pi.sub(&l1.mul(&alpha2)).sub(&e3);
+
+    // ── Скаляры для D ────────────────────────────────────────
+    let betaxi = ch.beta.mul(&ch.xi);
+    let d2a1 = eval_a.add(&betaxi).add(&ch.gamma);
+    let d2a2 = eval_b.add(&betaxi.mul(&fr(&vk.k1))).add(&ch.gamma);
+    let d2a3 = eval_c.add(&betaxi.mul(&fr(&vk.k2))).add(&ch.gamma);
+    let d2a = d2a1.mul(&d2a2).mul(&d2a3).mul(&ch.alpha);
+    let z_coef = d2a.add(&l1.mul(&alpha2)).add(&ch.u);
+
+    let s3_coef = e3a.mul(&e3b).mul(&ch.alpha.mul(&ch.beta)).mul(&eval_zw);
+
+    // ── E = e · G1 ───────────────────────────────────────────
+    let e = r0
+        .neg()
+        .add(&ch.v[1].mul(&eval_a))
+        .add(&ch.v[2].mul(&eval_b))
+        .add(&ch.v[3].mul(&eval_c))
+        .add(&ch.v[4].mul(&eval_s1))
+        .add(&ch.v[5].mul(&eval_s2))
+        .add(&ch.u.mul(&eval_zw));
+
+    // ── B1 = xi·Wxi + u·xi·ω·Wxiw + D + Σ v_i·P_i - E ────────
+    //   D = ab·Qm + a·Ql + b·Qr + c·Qo + Qc + z_coef·Z - s3_coef·S3 - zh·(T1 + xin·T2 + xin²·T3)
+    let s = ch.u.mul(&ch.xi).mul(&w);
+    let g1 = g1_generator();
+    let b1_terms: [(Fr, &G1Point); 18] = [
+        (ch.xi, &proof.wxi),
+        (s, &proof.wxiw),
+        (eval_a.mul(&eval_b), &vk.qm),
+        (eval_a, &vk.ql),
+        (eval_b, &vk.qr),
+        (eval_c, &vk.qo),
+        (Fr::one(), &vk.qc),
+        (z_coef, &proof.z),
+        (s3_coef.neg(), &vk.s3),
+        (zh.neg(), &proof.t1),
+        (zh.mul(&xin).neg(), &proof.t2),
+        (zh.mul(&xin.square()).neg(), &proof.t3),
+        (ch.v[1], &proof.a),
+        (ch.v[2], &proof.b),
+        (ch.v[3], &proof.c),
+        (ch.v[4], &vk.s1),
+        (ch.v[5], &vk.s2),
+        (e.neg(), &g1),
+    ];
+    let b1 = g1_multiexp(&b1_terms);
+
+    // ── A1 = Wxi + u·Wxiw ────────────────────────────────────
+    let a1 = g1_multiexp(&[(Fr::one(), &proof.wxi), (ch.u, &proof.wxiw)]);
+
+    // ── e(-A1, X_2) · e(B1, G2) == 1 ─────────────────────────
+    let mut pairing_data = Vec::with_capacity(2 * 192);
+    pairing_data.extend_from_slice(&a1.neg().to_bytes());
+    pairing_data.extend_from_slice(&vk.x_2.to_bytes());
+    pairing_data.extend_from_slice(&b1.to_bytes());
+    pairing_data.extend_from_slice(&g2_generator().to_bytes());
+
+    env::alt_bn128_pairing_check(&pairing_data)
+}
+
+/// Σ(scalar[i] · point[i]) через env::alt_bn128_g1_multiexp
+fn g1_multiexp(pairs: &[(Fr, &G1Point)]) -> G1Point {
+    // Формат NEAR: [(g1_point_64bytes, scalar_32bytes_le), ...]
+    let mut data = Vec::with_capacity(pairs.len() * 96);
+    for (s, point) in pairs {
+        data.extend_from_slice(&point.to_bytes());
+        data.extend_from_slice(&scalar(s).to_bytes());
+    }
+
+    let result = env::alt_bn128_g1_multiexp(&data);
+    assert!(
+        result.len() == 64,
+        "alt_bn128_g1_multiexp: ожидалось 64 байт"
+    );
+
+    let mut x = [0u8; 32];
+    let mut y = [0u8; 32];
+    x.copy_from_slice(&result[..32]);
+    y.copy_from_slice(&result[32..]);
+    G1Point { x, y }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::{scalars_from_decimal, SnarkjsPlonkProof, SnarkjsPlonkVerificationKey};
+    use near_sdk::serde_json;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    // См. tests/fixtures/README.md
+    const VK: &str = include_str!("../tests/fixtures/plonk_verification_key.json");
+    const PROOF: &str = include_str!("../tests/fixtures/plonk_proof.json");
+    const PUBLIC: &str = include_str!("../tests/fixtures/plonk_public.json");
+
+    fn fixture() -> (VerificationKey, Proof, Vec<Scalar>) {
+        testing_env!(VMContextBuilder::new().build());
+        let vk: SnarkjsPlonkVerificationKey = serde_json::from_str(VK).unwrap();
+        let proof: SnarkjsPlonkProof = serde_json::from_str(PROOF).unwrap();
+        let public: Vec<String> = serde_json::from_str(PUBLIC).unwrap();
+        (vk.to_vk(), proof.to_proof(), scalars_from_decimal(&public))
+    }
+
+    #[test]
+    fn test_verify_fixture_proof() {
+        let (vk, proof, public) = fixture();
+        assert!(verify(&vk, &proof, &public));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_proof() {
+        let (vk, proof, public) = fixture();
+
+        let mut bad = proof.clone();
+        bad.eval_a = scalar(&fr(&bad.eval_a).add(&Fr::one()));
+        assert!(!verify(&vk, &bad, &public));
+
+        let mut bad = proof.clone();
+        bad.wxi = proof.wxiw.clone();
+        assert!(!verify(&vk, &bad, &public));
+
+        let mut public = public;
+        public[2] = Scalar::from_decimal("1700000001");
+        assert!(!verify(&vk, &proof, &public));
+    }
+
+    #[test]
+    #[should_panic(expected = "PLONK: неверное количество public inputs")]
+    fn test_verify_input_count() {
+        let (vk, proof, public) = fixture();
+        verify(&vk, &proof, &public[..3]);
+    }
+}
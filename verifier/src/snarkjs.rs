@@ -0,0 +1,191 @@
+//! Форматы snarkjs (JSON, decimal strings) → типы BN254 для alt_bn128
+//!
+//! verification_key.json и proof.json принимаются как есть —
+//! лишние поля (protocol, curve, vk_alphabeta_12, ...) игнорируются.
+
+use near_sdk::{near, require};
+
+use crate::bn254_types::{decimal_to_le_bytes, G1Point, G2Point, Scalar};
+use crate::groth16::{Proof, VerificationKey};
+use crate::plonk;
+
+/// Groth16 proof в формате snarkjs (proof.json)
+#[near(serializers = [json])]
+#[derive(Clone)]
+pub struct SnarkjsProof {
+    /// [x, y, "1"]
+    pub pi_a: Vec<String>,
+    /// [[x_re, x_im], [y_re, y_im], ["1", "0"]]
+    pub pi_b: Vec<Vec<String>>,
+    /// [x, y, "1"]
+    pub pi_c: Vec<String>,
+}
+
+/// Verification key в формате snarkjs (verification_key.json)
+#[near(serializers = [json])]
+#[derive(Clone)]
+pub struct SnarkjsVerificationKey {
+    #[serde(rename = "nPublic")]
+    pub n_public: u32,
+    pub vk_alpha_1: Vec<String>,
+    pub vk_beta_2: Vec<Vec<String>>,
+    pub vk_gamma_2: Vec<Vec<String>>,
+    pub vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    pub ic: Vec<Vec<String>>,
+}
+
+/// PLONK proof в формате snarkjs (proof.json, protocol = "plonk")
+#[near(serializers = [json])]
+#[derive(Clone)]
+pub struct SnarkjsPlonkProof {
+    #[serde(rename = "A")]
+    pub a: Vec<String>,
+    #[serde(rename = "B")]
+    pub b: Vec<String>,
+    #[serde(rename = "C")]
+    pub c: Vec<String>,
+    #[serde(rename = "Z")]
+    pub z: Vec<String>,
+    #[serde(rename = "T1")]
+    pub t1: Vec<String>,
+    #[serde(rename = "T2")]
+    pub t2: Vec<String>,
+    #[serde(rename = "T3")]
+    pub t3: Vec<String>,
+    #[serde(rename = "Wxi")]
+    pub wxi: Vec<String>,
+    #[serde(rename = "Wxiw")]
+    pub wxiw: Vec<String>,
+    pub eval_a: String,
+    pub eval_b: String,
+    pub eval_c: String,
+    pub eval_s1: String,
+    pub eval_s2: String,
+    pub eval_zw: String,
+}
+
+/// PLONK verification key в формате snarkjs (verification_key.json, protocol = "plonk")
+#[near(serializers = [json])]
+#[derive(Clone)]
+pub struct SnarkjsPlonkVerificationKey {
+    #[serde(rename = "nPublic")]
+    pub n_public: u32,
+    pub power: u32,
+    pub k1: String,
+    pub k2: String,
+    #[serde(rename = "Qm")]
+    pub qm: Vec<String>,
+    #[serde(rename = "Ql")]
+    pub ql: Vec<String>,
+    #[serde(rename = "Qr")]
+    pub qr: Vec<String>,
+    #[serde(rename = "Qo")]
+    pub qo: Vec<String>,
+    #[serde(rename = "Qc")]
+    pub qc: Vec<String>,
+    #[serde(rename = "S1")]
+    pub s1: Vec<String>,
+    #[serde(rename = "S2")]
+    pub s2: Vec<String>,
+    #[serde(rename = "S3")]
+    pub s3: Vec<String>,
+    #[serde(rename = "X_2")]
+    pub x_2: Vec<Vec<String>>,
+    pub w: String,
+}
+
+/// [x, y, ...] → G1
+pub fn g1_from_snarkjs(point: &[String]) -> G1Point {
+    require!(point.len() >= 2, "G1: ожидалось [x, y]");
+    G1Point::from_decimal(&point[0], &point[1])
+}
+
+/// [[x_re, x_im], [y_re, y_im], ...] → G2
+pub fn g2_from_snarkjs(point: &[Vec<String>]) -> G2Point {
+    require!(
+        point.len() >= 2 && point[0].len() == 2 && point[1].len() == 2,
+        "G2: ожидалось [[x_re, x_im], [y_re, y_im]]"
+    );
+    G2Point {
+        x_im: decimal_to_le_bytes(&point[0][1]),
+        x_re: decimal_to_le_bytes(&point[0][0]),
+        y_im: decimal_to_le_bytes(&point[1][1]),
+        y_re: decimal_to_le_bytes(&point[1][0]),
+    }
+}
+
+impl SnarkjsProof {
+    pub fn to_proof(&self) -> Proof {
+        Proof {
+            a: g1_from_snarkjs(&self.pi_a),
+            b: g2_from_snarkjs(&self.pi_b),
+            c: g1_from_snarkjs(&self.pi_c),
+        }
+    }
+}
+
+impl SnarkjsVerificationKey {
+    pub fn to_vk(&self) -> VerificationKey {
+        require!(
+            self.ic.len() == self.n_public as usize + 1,
+            "IC: ожидалось nPublic + 1 точек"
+        );
+        VerificationKey {
+            alpha_g1: g1_from_snarkjs(&self.vk_alpha_1),
+            beta_g2: g2_from_snarkjs(&self.vk_beta_2),
+            gamma_g2: g2_from_snarkjs(&self.vk_gamma_2),
+            delta_g2: g2_from_snarkjs(&self.vk_delta_2),
+            ic: self.ic.iter().map(|p| g1_from_snarkjs(p)).collect(),
+        }
+    }
+}
+
+/// Decimal public inputs → Scalar
+pub fn scalars_from_decimal(inputs: &[String]) -> Vec<Scalar> {
+    inputs.iter().map(|s| Scalar::from_decimal(s)).collect()
+}
+
+impl SnarkjsPlonkProof {
+    pub fn to_proof(&self) -> plonk::Proof {
+        plonk::Proof {
+            a: g1_from_snarkjs(&self.a),
+            b: g1_from_snarkjs(&self.b),
+            c: g1_from_snarkjs(&self.c),
+            z: g1_from_snarkjs(&self.z),
+            t1: g1_from_snarkjs(&self.t1),
+            t2: g1_from_snarkjs(&self.t2),
+            t3: g1_from_snarkjs(&self.t3),
+            wxi: g1_from_snarkjs(&self.wxi),
+            wxiw: g1_from_snarkjs(&self.wxiw),
+            eval_a: Scalar::from_decimal(&self.eval_a),
+            eval_b: Scalar::from_decimal(&self.eval_b),
+            eval_c: Scalar::from_decimal(&self.eval_c),
+            eval_s1: Scalar::from_decimal(&self.eval_s1),
+            eval_s2: Scalar::from_decimal(&self.eval_s2),
+            eval_zw: Scalar::from_decimal(&self.eval_zw),
+        }
+    }
+}
+
+impl SnarkjsPlonkVerificationKey {
+    pub fn to_vk(&self) -> plonk::VerificationKey {
+        require!(self.power <= 28, "PLONK: power макс 28");
+        plonk::VerificationKey {
+            n_public: self.n_public,
+            power: self.power,
+            k1: Scalar::from_decimal(&self.k1),
+            k2: Scalar::from_decimal(&self.k2),
+            qm: g1_from_snarkjs(&self.qm),
+            ql: g1_from_snarkjs(&self.ql),
+            qr: g1_from_snarkjs(&self.qr),
+            qo: g1_from_snarkjs(&self.qo),
+            qc: g1_from_snarkjs(&self.qc),
+            s1: g1_from_snarkjs(&self.s1),
+            s2: g1_from_snarkjs(&self.s2),
+            s3: g1_from_snarkjs(&self.s3),
+            x_2: g2_from_snarkjs(&self.x_2),
+            w: Scalar::from_decimal(&self.w),
+        }
+    }
+}